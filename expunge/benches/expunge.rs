@@ -0,0 +1,83 @@
+//! Compares the cost of expunging large vectors of structs, e.g. a batch of log records.
+//!
+//! - `default` is the derived impl, which replaces each string with a new, empty one & frees the
+//!   old one
+//! - `in_place` is the derived impl with `#[expunge(in_place)]`, which clears each string in place
+//!   & keeps its allocation
+//!
+//! The records are cloned outside of the measurement, and the expunged records are dropped
+//! outside of it, so only the work done by `expunge` itself is measured.
+//!
+//! Run with `cargo bench -p expunge --all-features --bench expunge`. See `benches/BASELINE.md` for the numbers
+//! this is tracked against.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use expunge::Expunge;
+use std::hint::black_box;
+
+macro_rules! record {
+    ($name:ident $(, $attr:meta)?) => {
+        #[derive(Clone, Expunge)]
+        $(#[expunge($attr)])?
+        struct $name {
+            #[expunge(skip)]
+            id: u64,
+            name: String,
+            email: String,
+            tags: Vec<String>,
+            note: Option<String>,
+        }
+
+        impl $name {
+            fn new(id: u64) -> Self {
+                Self {
+                    id,
+                    name: format!("Jane Doe {id}"),
+                    email: format!("jane.doe.{id}@example.com"),
+                    tags: vec!["beta".to_string(), format!("cohort-{}", id % 7)],
+                    note: Some(format!("called about order #{id}")),
+                }
+            }
+        }
+    };
+}
+
+record!(Record);
+record!(InPlaceRecord, in_place);
+
+fn bench_expunge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("expunge");
+
+    for records in [10, 100, 1000] {
+        let default: Vec<_> = (0..records).map(Record::new).collect();
+        let in_place: Vec<_> = (0..records).map(InPlaceRecord::new).collect();
+
+        group.bench_with_input(
+            BenchmarkId::new("default", records),
+            &default,
+            |b, records| {
+                b.iter_batched(
+                    || records.clone(),
+                    |records| black_box(records.expunge()),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("in_place", records),
+            &in_place,
+            |b, records| {
+                b.iter_batched(
+                    || records.clone(),
+                    |records| black_box(records.expunge()),
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_expunge);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Compares the cost of logging expunged values with slog.
+//!
+//! - `raw` logs the value without expunging it, as a lower bound
+//! - `clone_expunge` clones & expunges the value every time it's logged, which is what the derived
+//!   `slog::Value` impl used to do
+//! - `borrowed` is the derived `slog::Value` impl, which expunges while serializing without cloning
+//! - `pre_expunged` logs a value that was expunged once up front, which is what a lazily expunged
+//!   & cached value (`LazyExpunged`) would cost when it's logged more than once
+//!
+//! Run with `cargo bench -p expunge --all-features --bench slog`. See `benches/BASELINE.md` for
+//! the numbers this is tracked against.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use expunge::Expunge;
+use serde::Serialize;
+use slog::{info, o, Drain, Logger};
+use std::{hint::black_box, io, sync::Mutex};
+
+#[derive(Clone, Expunge, Serialize)]
+#[expunge(slog)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge(as = "<expunged>".to_string())]
+    name: String,
+    email: String,
+    #[expunge(as = "<expunged>".to_string())]
+    ssn: String,
+    addresses: Vec<String>,
+}
+
+impl User {
+    fn new(addresses: usize) -> Self {
+        Self {
+            id: 101,
+            name: "Jane Doe".to_string(),
+            email: "jane.doe@example.com".to_string(),
+            ssn: "078-05-1120".to_string(),
+            addresses: (0..addresses)
+                .map(|i| format!("{i} Some Street, Some Town"))
+                .collect(),
+        }
+    }
+}
+
+/// Logs the value as is, without expunging it
+#[derive(Clone, Serialize, slog_derive::SerdeValue)]
+struct Owned(User);
+
+fn logger() -> Logger {
+    let drain = Mutex::new(slog_json::Json::default(io::sink())).fuse();
+    Logger::root(drain, o!())
+}
+
+fn bench_slog(c: &mut Criterion) {
+    let logger = logger();
+    let mut group = c.benchmark_group("slog");
+
+    for addresses in [0, 10, 100] {
+        let user = User::new(addresses);
+        let raw = Owned(user.clone());
+        let pre_expunged = Owned(user.clone().expunge());
+
+        group.bench_with_input(BenchmarkId::new("raw", addresses), &raw, |b, raw| {
+            b.iter(|| info!(logger, "user"; "user" => black_box(raw)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("clone_expunge", addresses),
+            &user,
+            |b, user| {
+                b.iter(|| info!(logger, "user"; "user" => Owned(black_box(user).clone().expunge())))
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("borrowed", addresses), &user, |b, user| {
+            b.iter(|| info!(logger, "user"; "user" => black_box(user)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("pre_expunged", addresses),
+            &pre_expunged,
+            |b, pre_expunged| b.iter(|| info!(logger, "user"; "user" => black_box(pre_expunged))),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_slog);
+criterion_main!(benches);
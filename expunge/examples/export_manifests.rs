@@ -0,0 +1,38 @@
+//! Writes the manifest of every type deriving `Expunge` in the binary, e.g.
+//!
+//! ```sh
+//! cargo run -p expunge --features manifest-export --example export_manifests -- target/manifests
+//! ```
+//!
+//! In an application, the same call can be made from a small bin, or a test that runs in CI.
+
+use expunge::Expunge;
+
+#[allow(dead_code)]
+#[derive(Expunge)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge(class = pii, keep_last = 4)]
+    phone: String,
+}
+
+#[allow(dead_code)]
+#[derive(Expunge)]
+enum Payment {
+    Card {
+        #[expunge(class = financial, as = "<card>".to_string())]
+        number: String,
+    },
+    Cash,
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "expunge-manifest".to_string());
+    for path in expunge::manifest::export(dir)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! Logs an expunged value in a loop, to profile the slog path with e.g.
+//!
+//! ```sh
+//! cargo flamegraph -p expunge --all-features --example slog_flamegraph
+//! ```
+
+use expunge::Expunge;
+use serde::Serialize;
+use slog::{info, o, Drain, Logger};
+use std::{io, sync::Mutex};
+
+#[derive(Clone, Expunge, Serialize)]
+#[expunge(slog)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge(as = "<expunged>".to_string())]
+    name: String,
+    email: String,
+    #[expunge(as = "<expunged>".to_string())]
+    ssn: String,
+    addresses: Vec<String>,
+}
+
+fn main() {
+    let iterations = std::env::args()
+        .nth(1)
+        .map(|n| n.parse().expect("iterations should be a number"))
+        .unwrap_or(1_000_000);
+
+    let drain = Mutex::new(slog_json::Json::default(io::sink())).fuse();
+    let logger = Logger::root(drain, o!());
+
+    let user = User {
+        id: 101,
+        name: "Jane Doe".to_string(),
+        email: "jane.doe@example.com".to_string(),
+        ssn: "078-05-1120".to_string(),
+        addresses: (0..10)
+            .map(|i| format!("{i} Some Street, Some Town"))
+            .collect(),
+    };
+
+    for _ in 0..iterations {
+        info!(logger, "user"; "user" => &user);
+    }
+}
@@ -0,0 +1,19 @@
+//! Helpers used by `#[derive(Expunge)]` to produce clearer compile errors
+
+/// Wraps the value of `#[expunge(as = ...)]`, so that a value of the wrong type is reported
+/// against the attribute, along with this function's name, rather than somewhere in the generated
+/// code
+#[inline(always)]
+pub fn as_value_must_match_field_type<T>(value: T) -> T {
+    value
+}
+
+/// Wraps the `source` of an `#[expunge(error)]`, so that a source whose message might not be
+/// redacted is reported against the field
+#[inline(always)]
+pub fn source_must_be_redacted<E>(source: &E) -> &(dyn std::error::Error + 'static)
+where
+    E: crate::RedactedError + 'static,
+{
+    source
+}
@@ -0,0 +1,80 @@
+use super::{utils::TruncateDate, Expunge};
+use ::chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// Dates & times are expunged as the unix epoch, i.e. 1970-01-01T00:00:00Z
+impl Expunge for NaiveDate {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        DateTime::<Utc>::UNIX_EPOCH.date_naive()
+    }
+}
+
+impl Expunge for NaiveTime {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        NaiveTime::MIN
+    }
+}
+
+impl Expunge for NaiveDateTime {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        DateTime::<Utc>::UNIX_EPOCH.naive_utc()
+    }
+}
+
+/// The timezone is left as-is
+impl<Tz> Expunge for DateTime<Tz>
+where
+    Tz: TimeZone,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        DateTime::<Utc>::UNIX_EPOCH.with_timezone(&self.timezone())
+    }
+}
+
+impl TruncateDate for NaiveDate {
+    fn truncate_to_year(self) -> Self {
+        NaiveDate::from_ymd_opt(self.year(), 1, 1).expect("the first day of the year is valid")
+    }
+
+    fn truncate_to_month(self) -> Self {
+        NaiveDate::from_ymd_opt(self.year(), self.month(), 1)
+            .expect("the first day of the month is valid")
+    }
+}
+
+impl TruncateDate for NaiveDateTime {
+    fn truncate_to_year(self) -> Self {
+        self.date().truncate_to_year().and_time(NaiveTime::MIN)
+    }
+
+    fn truncate_to_month(self) -> Self {
+        self.date().truncate_to_month().and_time(NaiveTime::MIN)
+    }
+}
+
+/// Truncation happens in UTC, the timezone is left as-is
+impl<Tz> TruncateDate for DateTime<Tz>
+where
+    Tz: TimeZone,
+{
+    fn truncate_to_year(self) -> Self {
+        let naive = self.naive_utc().truncate_to_year();
+        DateTime::from_naive_utc_and_offset(naive, self.offset().clone())
+    }
+
+    fn truncate_to_month(self) -> Self {
+        let naive = self.naive_utc().truncate_to_month();
+        DateTime::from_naive_utc_and_offset(naive, self.offset().clone())
+    }
+}
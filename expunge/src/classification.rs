@@ -0,0 +1,56 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// The kind of sensitive data a field holds, set with `#[expunge(class = ...)]`.
+///
+/// This is the shared vocabulary for all of the policy-oriented features, e.g. it's included in
+/// the events received by observers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Classification {
+    /// Personally identifiable information, e.g. names & email addresses
+    Pii,
+    /// Sensitive personal information, e.g. ethnicity or religion
+    Spi,
+    /// Credentials, keys & tokens
+    Secret,
+    /// e.g. card numbers & account balances
+    Financial,
+    /// e.g. medical records
+    Health,
+    Other(String),
+}
+
+impl Classification {
+    /// The lowercase name, as used in attributes
+    pub fn as_str(&self) -> &str {
+        match self {
+            Classification::Pii => "pii",
+            Classification::Spi => "spi",
+            Classification::Secret => "secret",
+            Classification::Financial => "financial",
+            Classification::Health => "health",
+            Classification::Other(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Names are matched case-insensitively, anything unrecognized is [Classification::Other]
+impl FromStr for Classification {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "pii" => Classification::Pii,
+            "spi" => Classification::Spi,
+            "secret" => Classification::Secret,
+            "financial" => Classification::Financial,
+            "health" => Classification::Health,
+            _ => Classification::Other(s.to_string()),
+        })
+    }
+}
@@ -0,0 +1,265 @@
+//! Recoverable redaction, which encrypts values so that they can only be restored by jobs with the
+//! key.
+//!
+//! Fields annotated with `#[expunge(encrypt)]` are encrypted with the key of the registered
+//! [KeyProvider], and replaced with the base64 encoded ciphertext. Without a key, or if the value
+//! can't be encrypted, the field is expunged as usual instead, so values are never left as-is.
+//!
+//! ```rust
+//! use expunge::{crypto::{self, Key}, Expunge};
+//!
+//! #[derive(Expunge)]
+//! struct Order {
+//!     #[expunge(encrypt)]
+//!     address: String,
+//!     #[expunge(skip)]
+//!     id: u64,
+//! }
+//!
+//! crypto::set_key(Key::new([7; 32]));
+//!
+//! let order = Order {
+//!     address: "1 Main St".to_string(),
+//!     id: 101,
+//! }
+//! .expunge();
+//! assert_ne!("1 Main St", order.address);
+//!
+//! assert_eq!("1 Main St", crypto::decrypt(&order.address, "Order.address")?);
+//! # Ok::<_, crypto::CryptoError>(())
+//! ```
+//!
+//! Values are encrypted with XChaCha20-Poly1305. Each value has a random 192 bit nonce, so
+//! encrypting the same value twice gives different ciphertexts.
+//!
+//! The ciphertext is bound to a context, which is authenticated but not encrypted. Fields are
+//! encrypted with their type & field path as the context, e.g. `Order.address` or
+//! `Payment::Card.number`, so a ciphertext that's moved to another field fails to decrypt.
+
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+const VERSION: u8 = 2;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+
+/// A 256 bit key. It's never printed by `Debug`, and it's zeroized when it's dropped.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key([u8; 32]);
+
+impl Key {
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Generates a random key
+    pub fn generate() -> Result<Self, CryptoError> {
+        let mut bytes = [0; 32];
+        getrandom::fill(&mut bytes).map_err(|_| CryptoError::Random)?;
+        Ok(Self(bytes))
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new((&self.0).into())
+    }
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for Key {}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(<redacted>)")
+    }
+}
+
+/// Supplies the key that values are encrypted & decrypted with, e.g. from a KMS
+pub trait KeyProvider: Send + Sync {
+    fn key(&self) -> Result<Key, CryptoError>;
+}
+
+impl KeyProvider for Key {
+    fn key(&self) -> Result<Key, CryptoError> {
+        Ok(self.clone())
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CryptoError {
+    /// No key has been registered
+    NoKey,
+    /// The [KeyProvider] couldn't supply the key
+    Provider(Box<dyn std::error::Error + Send + Sync>),
+    /// A nonce couldn't be generated
+    Random,
+    /// The value couldn't be encrypted
+    Encryption,
+    /// The ciphertext isn't base64, or is too short
+    Malformed,
+    /// The ciphertext has been tampered with, or was encrypted with a different key
+    Authentication,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::NoKey => f.write_str("no key has been registered"),
+            CryptoError::Provider(err) => write!(f, "couldn't get the key: {err}"),
+            CryptoError::Random => f.write_str("couldn't generate a nonce"),
+            CryptoError::Encryption => f.write_str("couldn't encrypt the value"),
+            CryptoError::Malformed => f.write_str("malformed ciphertext"),
+            CryptoError::Authentication => f.write_str("ciphertext failed authentication"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CryptoError::Provider(err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+static PROVIDER: RwLock<Option<Arc<dyn KeyProvider>>> = RwLock::new(None);
+
+/// Encrypts `#[expunge(encrypt)]` fields with this key, replacing any previously registered key
+/// or provider
+pub fn set_key(key: Key) {
+    set_key_provider(key);
+}
+
+/// Encrypts `#[expunge(encrypt)]` fields with the provider's key, replacing any previously
+/// registered key or provider
+pub fn set_key_provider(provider: impl KeyProvider + 'static) {
+    *PROVIDER.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(provider));
+}
+
+/// Unregisters the key, so that `#[expunge(encrypt)]` fields are expunged as usual
+pub fn clear_key() {
+    *PROVIDER.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+fn key() -> Result<Key, CryptoError> {
+    let provider = PROVIDER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    provider.ok_or(CryptoError::NoKey)?.key()
+}
+
+/// The version & the context are authenticated along with the ciphertext
+fn associated_data(context: &str) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(1 + context.len());
+    aad.push(VERSION);
+    aad.extend_from_slice(context.as_bytes());
+    aad
+}
+
+/// Encrypts the value with the given key, returning the base64 encoded ciphertext. It can only be
+/// decrypted with the same context, e.g. the type & field path.
+pub fn encrypt_with(key: &Key, plaintext: &str, context: &str) -> Result<String, CryptoError> {
+    let mut nonce = [0; NONCE_LEN];
+    getrandom::fill(&mut nonce).map_err(|_| CryptoError::Random)?;
+
+    let aad = associated_data(context);
+    let ciphertext = key
+        .cipher()
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: &aad,
+            },
+        )
+        .map_err(|_| CryptoError::Encryption)?;
+
+    let mut encoded = Vec::with_capacity(1 + NONCE_LEN + ciphertext.len());
+    encoded.push(VERSION);
+    encoded.extend_from_slice(&nonce);
+    encoded.extend_from_slice(&ciphertext);
+    Ok(STANDARD.encode(encoded))
+}
+
+/// Decrypts a value encrypted by [encrypt_with] with the same key & context
+pub fn decrypt_with(key: &Key, ciphertext: &str, context: &str) -> Result<String, CryptoError> {
+    let decoded = STANDARD
+        .decode(ciphertext)
+        .map_err(|_| CryptoError::Malformed)?;
+    if decoded.len() < 1 + NONCE_LEN + TAG_LEN || decoded[0] != VERSION {
+        return Err(CryptoError::Malformed);
+    }
+    let (nonce, ciphertext) = decoded[1..].split_at(NONCE_LEN);
+
+    let aad = associated_data(context);
+    let plaintext = key
+        .cipher()
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| CryptoError::Authentication)?;
+    // authenticated ciphertexts are always encrypted from a `&str`
+    String::from_utf8(plaintext).map_err(|_| CryptoError::Malformed)
+}
+
+/// Encrypts the value with the registered key, returning the base64 encoded ciphertext
+pub fn encrypt(plaintext: &str, context: &str) -> Result<String, CryptoError> {
+    encrypt_with(&key()?, plaintext, context)
+}
+
+/// Decrypts a value with the registered key, e.g. a field annotated with `#[expunge(encrypt)]`,
+/// whose context is its type & field path, e.g. `Order.address` or `Payment::Card.number`
+pub fn decrypt(ciphertext: &str, context: &str) -> Result<String, CryptoError> {
+    decrypt_with(&key()?, ciphertext, context)
+}
+
+/// Values that can be encrypted by `#[expunge(encrypt)]`, which passes the type & field path as
+/// the context
+pub trait Encrypt {
+    fn encrypt(self, context: &str) -> Self
+    where
+        Self: Sized;
+}
+
+impl Encrypt for String {
+    fn encrypt(self, context: &str) -> Self {
+        // the value is never left as-is
+        encrypt(&self, context).unwrap_or_default()
+    }
+}
+
+impl Encrypt for Cow<'_, str> {
+    fn encrypt(self, context: &str) -> Self {
+        Cow::Owned(self.into_owned().encrypt(context))
+    }
+}
+
+impl<T> Encrypt for Option<T>
+where
+    T: Encrypt,
+{
+    fn encrypt(self, context: &str) -> Self {
+        self.map(|value| value.encrypt(context))
+    }
+}
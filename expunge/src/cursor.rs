@@ -0,0 +1,113 @@
+//! Opaque, signed continuation tokens for paginated APIs.
+//!
+//! Cursors often embed query state such as filter values. Values are expunged before they're
+//! encoded, so sensitive filters (e.g. emails) never end up in the token, and the token is signed
+//! with HMAC-SHA256 so that it can't be tampered with by clients.
+//!
+//! ```rust
+//! use expunge::{cursor, Expunge};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Expunge, Serialize, Deserialize)]
+//! #[expunge(allow_debug)]
+//! struct Query {
+//!     #[expunge(with = sha256::digest)]
+//!     email: String,
+//!     #[expunge(skip)]
+//!     offset: u64,
+//! }
+//!
+//! let key = b"some secret key";
+//! let query = Query { email: "alice@example.com".to_string(), offset: 100 };
+//!
+//! let token = cursor::encode(query, key).expect("should encode");
+//! let decoded: Query = cursor::decode(&token, key).expect("should decode");
+//!
+//! assert_eq!(100, decoded.offset);
+//! assert_eq!(sha256::digest("alice@example.com"), decoded.email);
+//! ```
+
+use std::fmt;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::Sha256;
+
+use crate::Expunge;
+
+const SEPARATOR: char = '.';
+
+/// Errors that can occur when encoding or decoding a cursor
+#[derive(Debug)]
+pub enum CursorError {
+    /// The token isn't in the expected `<payload>.<signature>` format
+    Malformed,
+    /// The signature doesn't match the payload, i.e. the token has been tampered with or was
+    /// signed with a different key
+    InvalidSignature,
+    /// The payload couldn't be serialized or deserialized
+    Serde(serde_json::Error),
+}
+
+impl fmt::Display for CursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CursorError::Malformed => f.write_str("malformed cursor"),
+            CursorError::InvalidSignature => f.write_str("invalid cursor signature"),
+            CursorError::Serde(err) => write!(f, "invalid cursor payload: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for CursorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CursorError::Serde(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+fn mac(key: &[u8]) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+/// Expunges the value and encodes it as an opaque, url-safe token signed with the given key
+pub fn encode<T>(value: T, key: &[u8]) -> Result<String, CursorError>
+where
+    T: Expunge + Serialize,
+{
+    let payload = serde_json::to_vec(&value.expunge()).map_err(CursorError::Serde)?;
+
+    let mut mac = mac(key);
+    mac.update(&payload);
+    let signature = mac.finalize().into_bytes();
+
+    Ok(format!(
+        "{}{SEPARATOR}{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Verifies the token's signature with the given key and decodes the (expunged) value
+pub fn decode<T>(token: &str, key: &[u8]) -> Result<T, CursorError>
+where
+    T: DeserializeOwned,
+{
+    let (payload, signature) = token.split_once(SEPARATOR).ok_or(CursorError::Malformed)?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| CursorError::Malformed)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| CursorError::Malformed)?;
+
+    let mut mac = mac(key);
+    mac.update(&payload);
+    mac.verify_slice(&signature)
+        .map_err(|_| CursorError::InvalidSignature)?;
+
+    serde_json::from_slice(&payload).map_err(CursorError::Serde)
+}
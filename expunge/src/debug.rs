@@ -0,0 +1,63 @@
+//! Helpers used by the `Debug` implementation generated by the `Expunge` derive
+
+use std::fmt::{Debug, Display, Formatter, Result};
+
+/// Printed in place of sensitive values
+pub struct Placeholder;
+
+impl Debug for Placeholder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("<expunged>")
+    }
+}
+
+impl Display for Placeholder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(self, f)
+    }
+}
+
+/// Printed in place of sensitive values when a custom placeholder is configured
+pub struct Text(pub &'static str);
+
+impl Debug for Text {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Display for Text {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Wraps a field that isn't sensitive so that it can be printed if it implements `Debug`, falling
+/// back to [Placeholder] otherwise.
+///
+/// Both [ViaDebug] and [ViaPlaceholder] must be in scope, and the method must be called on
+/// `&&Field(..)` so that [ViaDebug] takes precedence when it applies.
+pub struct Field<'a, T>(pub &'a T);
+
+pub trait ViaDebug {
+    fn as_debug(&self) -> &dyn Debug;
+}
+
+impl<'a, T> ViaDebug for &Field<'a, T>
+where
+    T: Debug,
+{
+    fn as_debug(&self) -> &dyn Debug {
+        self.0
+    }
+}
+
+pub trait ViaPlaceholder {
+    fn as_debug(&self) -> &dyn Debug;
+}
+
+impl<'a, T> ViaPlaceholder for Field<'a, T> {
+    fn as_debug(&self) -> &dyn Debug {
+        &Placeholder
+    }
+}
@@ -0,0 +1,97 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::Expunge;
+
+/// Wraps a value that's expunged by replacing it with its `Default`, e.g. for types from other
+/// crates that don't implement [Expunge] when wiping them is acceptable.
+///
+/// It dereferences to the value, and with the `serde` feature it's serialized & deserialized
+/// transparently.
+///
+/// ```rust
+/// use expunge::{DefaultExpunged, Expunge};
+/// use std::net::SocketAddr;
+///
+/// #[derive(Expunge)]
+/// struct Connection {
+///     // `SocketAddr` doesn't implement `Expunge`
+///     peer: DefaultExpunged<Option<SocketAddr>>,
+/// }
+///
+/// let connection = Connection {
+///     peer: DefaultExpunged::new("10.0.0.1:443".parse().ok()),
+/// };
+/// assert!(connection.peer.is_some());
+/// assert_eq!(None, *connection.expunge().peer);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DefaultExpunged<T>(pub T);
+
+impl<T> DefaultExpunged<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Returns the value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Expunge for DefaultExpunged<T>
+where
+    T: Default,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Self(T::default())
+    }
+}
+
+impl<T> From<T> for DefaultExpunged<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for DefaultExpunged<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for DefaultExpunged<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for DefaultExpunged<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for DefaultExpunged<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self)
+    }
+}
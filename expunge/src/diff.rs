@@ -0,0 +1,189 @@
+use std::fmt;
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use serde_json::Value;
+
+/// A value that was changed by expunging
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Where the value is, e.g. `address.lines[0]`. Empty if the whole value was changed.
+    pub path: String,
+    /// The value after expunging, or `None` if it was left out of the expunged output
+    pub expunged: Option<Value>,
+}
+
+/// The paths that were changed by expunging a value. See [diff].
+///
+/// Only the expunged side of each change is kept, so that the diff can be logged or stored, e.g.
+/// as evidence of what was redacted in a payload, without the original values.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpungeDiff {
+    changes: Vec<Change>,
+}
+
+impl ExpungeDiff {
+    /// The changed values. The keys of objects are compared in sorted order.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// The paths of the changed values, e.g. `address.lines[0]`
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.changes.iter().map(|change| change.path.as_str())
+    }
+
+    /// Whether the value at the path was changed
+    pub fn is_changed(&self, path: &str) -> bool {
+        self.paths().any(|changed| changed == path)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+impl fmt::Display for ExpungeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let path = if change.path.is_empty() {
+                "<root>"
+            } else {
+                &change.path
+            };
+            match &change.expunged {
+                Some(value) => write!(f, "{path}: {value}")?,
+                None => write!(f, "{path}: <removed>")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Change {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut change = serializer.serialize_struct("Change", 2)?;
+        change.serialize_field("path", &self.path)?;
+        change.serialize_field("expunged", &self.expunged)?;
+        change.end()
+    }
+}
+
+impl Serialize for ExpungeDiff {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut diff = serializer.serialize_struct("ExpungeDiff", 1)?;
+        diff.serialize_field("changes", &self.changes)?;
+        diff.end()
+    }
+}
+
+/// Serializes an original & an expunged value, and reports every path whose value was changed.
+///
+/// ```rust
+/// use expunge::Expunge;
+/// use serde::Serialize;
+///
+/// #[derive(Clone, Serialize, Expunge)]
+/// struct User {
+///     #[expunge(skip)]
+///     username: String,
+///     emails: Vec<String>,
+///     #[expunge(keep_last = 4)]
+///     phone: String,
+/// }
+///
+/// let user = User {
+///     username: "jane_doe".to_string(),
+///     emails: vec!["jane@example.com".to_string()],
+///     phone: "+44 20 7946 0958".to_string(),
+/// };
+/// let diff = expunge::diff(&user, &user.clone().expunge());
+///
+/// assert_eq!(vec!["emails[0]", "phone"], diff.paths().collect::<Vec<_>>());
+/// assert_eq!(r#"emails[0]: ""
+/// phone: "************0958""#, diff.to_string());
+/// ```
+///
+/// Values are compared as JSON, so the original & expunged values can be of different types, e.g.
+/// `T` & [Expunged<T>](crate::Expunged). Objects & arrays are compared element by element, and
+/// any other value is reported as a whole.
+///
+/// # Panics
+///
+/// If either value can't be serialized as JSON, e.g. a map with non-string keys
+pub fn diff<O, E>(original: &O, expunged: &E) -> ExpungeDiff
+where
+    O: Serialize + ?Sized,
+    E: Serialize + ?Sized,
+{
+    let original = serde_json::to_value(original).expect("the original should serialize");
+    let expunged = serde_json::to_value(expunged).expect("the expunged value should serialize");
+
+    let mut changes = vec![];
+    collect_changes(String::new(), &original, &expunged, &mut changes);
+    ExpungeDiff { changes }
+}
+
+fn collect_changes(path: String, original: &Value, expunged: &Value, changes: &mut Vec<Change>) {
+    match (original, expunged) {
+        (Value::Object(original), Value::Object(expunged)) => {
+            for (key, value) in original {
+                let path = join(&path, key);
+                match expunged.get(key) {
+                    Some(expunged) => collect_changes(path, value, expunged, changes),
+                    None => changes.push(Change {
+                        path,
+                        expunged: None,
+                    }),
+                }
+            }
+            for (key, value) in expunged {
+                if !original.contains_key(key) {
+                    changes.push(Change {
+                        path: join(&path, key),
+                        expunged: Some(value.clone()),
+                    });
+                }
+            }
+        }
+        (Value::Array(original), Value::Array(expunged)) => {
+            for i in 0..original.len().max(expunged.len()) {
+                let path = format!("{path}[{i}]");
+                match (original.get(i), expunged.get(i)) {
+                    (Some(original), Some(expunged)) => {
+                        collect_changes(path, original, expunged, changes)
+                    }
+                    (_, expunged) => changes.push(Change {
+                        path,
+                        expunged: expunged.cloned(),
+                    }),
+                }
+            }
+        }
+        (original, expunged) if original != expunged => changes.push(Change {
+            path,
+            expunged: Some(expunged.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
@@ -0,0 +1,322 @@
+//! A runtime redaction engine for documents whose types aren't known at compile time, e.g. in
+//! webhook proxies or log pipelines.
+//!
+//! Rules select values with JSONPath-style selectors and apply an [Action] to them. Selectors
+//! support a subset of JSONPath:
+//!
+//! - `$` the root of the document
+//! - `.key` or `['key']` a key of an object
+//! - `[0]` an element of an array
+//! - `.*` or `[*]` every key or element
+//! - `..key` or `..*` recursive descent
+//!
+//! ```rust
+//! use expunge::dynamic::{Action, Engine};
+//! use serde_json::json;
+//!
+//! let engine = Engine::new()
+//!     .rule("$.user.cards[*].pan", Action::Mask { keep_first: 0, keep_last: 4 })?
+//!     .rule("$..password", Action::Expunge)?
+//!     .rule("$.user.name", Action::As(json!("<redacted>")))?;
+//!
+//! let document = json!({
+//!     "user": {
+//!         "name": "Jane",
+//!         "password": "hunter2",
+//!         "cards": [{ "pan": "4242424242424242" }],
+//!     },
+//! });
+//!
+//! assert_eq!(
+//!     json!({
+//!         "user": {
+//!             "name": "<redacted>",
+//!             "password": "",
+//!             "cards": [{ "pan": "************4242" }],
+//!         },
+//!     }),
+//!     engine.apply(document),
+//! );
+//! # Ok::<_, expunge::dynamic::SelectorError>(())
+//! ```
+//!
+//! Other formats that can be (de)serialized with serde, such as TOML or YAML documents, can be
+//! redacted with [Engine::apply_serde].
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::Expunge;
+
+/// What to do with a selected value
+#[derive(Clone)]
+pub enum Action {
+    /// Expunge the value, as with [Value::expunge]
+    Expunge,
+    /// Replace the value
+    As(Value),
+    /// Transform the value with a function
+    With(Arc<dyn Fn(Value) -> Value + Send + Sync>),
+    /// Mask a string, as with [crate::utils::mask]. Other values are expunged.
+    Mask { keep_first: usize, keep_last: usize },
+}
+
+impl Action {
+    /// Transform the value with a function
+    pub fn with(f: impl Fn(Value) -> Value + Send + Sync + 'static) -> Self {
+        Action::With(Arc::new(f))
+    }
+
+    fn apply(&self, value: Value) -> Value {
+        match self {
+            Action::Expunge => value.expunge(),
+            Action::As(replacement) => replacement.clone(),
+            Action::With(f) => f(value),
+            Action::Mask {
+                keep_first,
+                keep_last,
+            } => match value {
+                Value::String(s) => Value::String(crate::utils::mask(s, *keep_first, *keep_last)),
+                value => value.expunge(),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Expunge => f.write_str("Expunge"),
+            Action::As(value) => f.debug_tuple("As").field(value).finish(),
+            Action::With(_) => f.write_str("With(..)"),
+            Action::Mask {
+                keep_first,
+                keep_last,
+            } => f
+                .debug_struct("Mask")
+                .field("keep_first", keep_first)
+                .field("keep_last", keep_last)
+                .finish(),
+        }
+    }
+}
+
+/// The selector couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorError {
+    selector: String,
+    position: usize,
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid selector `{}` at position {}",
+            self.selector, self.position
+        )
+    }
+}
+
+impl std::error::Error for SelectorError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Matcher {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Child(Matcher),
+    Descendant(Matcher),
+}
+
+/// A parsed JSONPath-style selector, e.g. `$.user.cards[*].pan`. See the [module docs](self) for
+/// the supported syntax.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector(Vec<Segment>);
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(selector: &str) -> Result<Self, Self::Err> {
+        let chars: Vec<char> = selector.chars().collect();
+        let error = |position| SelectorError {
+            selector: selector.to_string(),
+            position,
+        };
+
+        if chars.first() != Some(&'$') {
+            return Err(error(0));
+        }
+
+        let mut segments = vec![];
+        let mut i = 1;
+        while i < chars.len() {
+            match chars[i] {
+                '.' => {
+                    let descendant = chars.get(i + 1) == Some(&'.');
+                    i += if descendant { 2 } else { 1 };
+                    let matcher = if chars.get(i) == Some(&'*') {
+                        i += 1;
+                        Matcher::Wildcard
+                    } else if descendant && chars.get(i) == Some(&'[') {
+                        let (matcher, next) = parse_bracket(&chars, i).ok_or(error(i))?;
+                        i = next;
+                        matcher
+                    } else {
+                        let start = i;
+                        while i < chars.len() && !matches!(chars[i], '.' | '[') {
+                            i += 1;
+                        }
+                        if start == i {
+                            return Err(error(start));
+                        }
+                        Matcher::Key(chars[start..i].iter().collect())
+                    };
+                    segments.push(if descendant {
+                        Segment::Descendant(matcher)
+                    } else {
+                        Segment::Child(matcher)
+                    });
+                }
+                '[' => {
+                    let (matcher, next) = parse_bracket(&chars, i).ok_or(error(i))?;
+                    i = next;
+                    segments.push(Segment::Child(matcher));
+                }
+                _ => return Err(error(i)),
+            }
+        }
+
+        Ok(Selector(segments))
+    }
+}
+
+/// Parses `[*]`, `[0]` or `['key']` starting at the `[`, returning the index after the `]`. Quoted
+/// keys end at the closing quote, so they can contain `]`.
+fn parse_bracket(chars: &[char], start: usize) -> Option<(Matcher, usize)> {
+    let open = start + 1;
+    if let Some(&quote @ ('\'' | '"')) = chars.get(open) {
+        let close = open + 1 + chars[open + 1..].iter().position(|&c| c == quote)?;
+        if chars.get(close + 1) != Some(&']') {
+            return None;
+        }
+        let key = chars[open + 1..close].iter().collect();
+        return Some((Matcher::Key(key), close + 2));
+    }
+
+    let end = start + chars[start..].iter().position(|&c| c == ']')?;
+    let inner: String = chars[open..end].iter().collect();
+    let matcher = if inner == "*" {
+        Matcher::Wildcard
+    } else {
+        Matcher::Index(inner.parse().ok()?)
+    };
+    Some((matcher, end + 1))
+}
+
+impl Selector {
+    /// Calls `f` with every value matching the selector
+    fn select(&self, value: &mut Value, f: &mut dyn FnMut(&mut Value)) {
+        select(value, &self.0, f);
+    }
+}
+
+/// Where a child is in its parent
+enum Address<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+impl Matcher {
+    fn matches(&self, address: &Address) -> bool {
+        match (self, address) {
+            (Matcher::Wildcard, _) => true,
+            (Matcher::Key(key), Address::Key(other)) => key == other,
+            (Matcher::Index(index), Address::Index(other)) => index == other,
+            _ => false,
+        }
+    }
+}
+
+fn children(value: &mut Value) -> Vec<(Address<'_>, &mut Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, value)| (Address::Key(key), value))
+            .collect(),
+        Value::Array(values) => values
+            .iter_mut()
+            .enumerate()
+            .map(|(i, value)| (Address::Index(i), value))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn select(value: &mut Value, segments: &[Segment], f: &mut dyn FnMut(&mut Value)) {
+    let Some((segment, rest)) = segments.split_first() else {
+        f(value);
+        return;
+    };
+    match segment {
+        Segment::Child(matcher) => {
+            for (address, child) in children(value) {
+                if matcher.matches(&address) {
+                    select(child, rest, f);
+                }
+            }
+        }
+        Segment::Descendant(matcher) => {
+            for (address, child) in children(value) {
+                if matcher.matches(&address) {
+                    select(child, rest, f);
+                }
+                select(child, segments, f);
+            }
+        }
+    }
+}
+
+/// Applies [Action]s to the values selected by each rule, in the order they were added
+#[derive(Debug, Clone, Default)]
+pub struct Engine {
+    rules: Vec<(Selector, Action)>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule, returning an error if the selector is invalid
+    pub fn rule(mut self, selector: &str, action: Action) -> Result<Self, SelectorError> {
+        self.rules.push((selector.parse()?, action));
+        Ok(self)
+    }
+
+    /// Redacts the document according to the rules
+    pub fn apply(&self, mut document: Value) -> Value {
+        for (selector, action) in &self.rules {
+            selector.select(&mut document, &mut |value| {
+                *value = action.apply(value.take());
+            });
+        }
+        document
+    }
+
+    /// Redacts any document that can be (de)serialized with serde, e.g. a `toml::Value`, by
+    /// converting it to & from a [Value]
+    pub fn apply_serde<T>(&self, document: T) -> Result<T, serde_json::Error>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let document = serde_json::to_value(document)?;
+        serde_json::from_value(self.apply(document))
+    }
+}
@@ -0,0 +1,498 @@
+//! Irrecoverable erasure, for right-to-be-forgotten flows where even hashed or masked values are
+//! still considered personal data.
+//!
+//! [Erase] is derived with `#[derive(Erase)]`, using the same `#[expunge(...)]` attributes as
+//! `Expunge`. Unlike `expunge`, `erase` ignores `with`, `hash`, `keep_first`/`keep_last`,
+//! profiles & conditions. Fields marked `skip` are left as-is, `as` & `none` fields are replaced
+//! as usual, after they're zeroized if they implement `Zeroize` with the `zeroize` feature, and
+//! every other field is erased:
+//!
+//! - types that implement [Erase], e.g. other types deriving it, are erased recursively, as are
+//!   collections, smart pointers & tuples of them
+//! - types that derive `Expunge` must also derive [Erase], otherwise it's a compile error, since
+//!   their `with` & `hash` rules would be applied instead. This includes collections & smart
+//!   pointers of them, but tuples only when every element derives `Expunge`
+//! - type parameters must implement [Erase]
+//! - with the `zeroize` feature, types that implement `Zeroize` are zeroized before they're
+//!   expunged
+//! - other types are expunged by their [Expunge] implementation, e.g. numbers are set to `0`
+//!
+//! ```rust
+//! use expunge::{Erase, Expunge};
+//!
+//! #[derive(Expunge, Erase)]
+//! struct Address {
+//!     #[expunge(keep_last = 3)]
+//!     postcode: String,
+//! }
+//!
+//! #[derive(Expunge, Erase)]
+//! struct User {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     #[expunge(with = sha256::digest)]
+//!     email: String,
+//!     address: Option<Address>,
+//! }
+//!
+//! let user = || User {
+//!     id: 101,
+//!     email: "jane@example.com".to_string(),
+//!     address: Some(Address {
+//!         postcode: "SW1A 1AA".to_string(),
+//!     }),
+//! };
+//!
+//! let expunged = user().expunge();
+//! assert_eq!(64, expunged.email.len());
+//!
+//! let erased = user().erase();
+//! assert_eq!(101, erased.id);
+//! assert_eq!("", erased.email);
+//! assert_eq!("", erased.address.unwrap().postcode);
+//! ```
+
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    hash::Hash,
+    rc::Rc,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+use crate::Expunge;
+
+/// Destroys the data irrecoverably. It's implemented by `#[derive(Erase)]`.
+pub trait Erase {
+    fn erase(self) -> Self
+    where
+        Self: Sized;
+}
+
+impl<T> Erase for Option<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.map(Erase::erase)
+    }
+}
+
+impl<R, E> Erase for Result<R, E>
+where
+    R: Erase,
+    E: Erase,
+{
+    fn erase(self) -> Self {
+        match self {
+            Ok(v) => Ok(v.erase()),
+            Err(e) => Err(e.erase()),
+        }
+    }
+}
+
+impl<T> Erase for Vec<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+impl<K, V> Erase for HashMap<K, V>
+where
+    K: Hash + Eq,
+    V: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(|(k, v)| (k, v.erase())).collect()
+    }
+}
+
+impl<T> Erase for HashSet<T>
+where
+    T: Erase + Hash + Eq,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+impl<T> Erase for Box<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        Box::new((*self).erase())
+    }
+}
+
+impl<T> Erase for Box<[T]>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_vec().erase().into_boxed_slice()
+    }
+}
+
+impl<T, const N: usize> Erase for [T; N]
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.map(Erase::erase)
+    }
+}
+
+impl<T> Erase for VecDeque<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+impl<T> Erase for LinkedList<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+impl<T> Erase for BinaryHeap<T>
+where
+    T: Erase + Ord,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+impl<K, V> Erase for BTreeMap<K, V>
+where
+    K: Ord,
+    V: Erase,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(|(k, v)| (k, v.erase())).collect()
+    }
+}
+
+impl<T> Erase for BTreeSet<T>
+where
+    T: Erase + Ord,
+{
+    fn erase(self) -> Self {
+        self.into_iter().map(Erase::erase).collect()
+    }
+}
+
+/// The value is cloned if there are other references to it, so that they are left unerased.
+impl<T> Erase for Rc<T>
+where
+    T: Erase + Clone,
+{
+    fn erase(self) -> Self {
+        Rc::new(Rc::unwrap_or_clone(self).erase())
+    }
+}
+
+/// The value is cloned if there are other references to it, so that they are left unerased.
+impl<T> Erase for Arc<T>
+where
+    T: Erase + Clone,
+{
+    fn erase(self) -> Self {
+        Arc::new(Arc::unwrap_or_clone(self).erase())
+    }
+}
+
+/// Borrowed values are converted to owned values before being erased.
+impl<B> Erase for Cow<'_, B>
+where
+    B: ToOwned + ?Sized,
+    B::Owned: Erase,
+{
+    fn erase(self) -> Self {
+        Cow::Owned(self.into_owned().erase())
+    }
+}
+
+/// Poisoned values are erased all the same.
+impl<T> Erase for Mutex<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        Mutex::new(
+            self.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .erase(),
+        )
+    }
+}
+
+/// Poisoned values are erased all the same.
+impl<T> Erase for RwLock<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        RwLock::new(
+            self.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .erase(),
+        )
+    }
+}
+
+impl<T> Erase for Cell<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        Cell::new(self.into_inner().erase())
+    }
+}
+
+impl<T> Erase for RefCell<T>
+where
+    T: Erase,
+{
+    fn erase(self) -> Self {
+        RefCell::new(self.into_inner().erase())
+    }
+}
+
+macro_rules! erase_tuple {
+    ($($name:ident),+) => {
+        impl<$($name),+> Erase for ($($name,)+)
+        where
+            $($name: Erase,)+
+        {
+            #[allow(non_snake_case)]
+            fn erase(self) -> Self {
+                let ($($name,)+) = self;
+                ($($name.erase(),)+)
+            }
+        }
+
+        impl<$($name),+> Derived for ($($name,)+) where $($name: Derived,)+ {}
+    };
+}
+
+erase_tuple!(A);
+erase_tuple!(A, B);
+erase_tuple!(A, B, C);
+erase_tuple!(A, B, C, D);
+erase_tuple!(A, B, C, D, E);
+erase_tuple!(A, B, C, D, E, F);
+erase_tuple!(A, B, C, D, E, F, G);
+erase_tuple!(A, B, C, D, E, F, G, H);
+erase_tuple!(A, B, C, D, E, F, G, H, I);
+erase_tuple!(A, B, C, D, E, F, G, H, I, J);
+erase_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+erase_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Implemented by `#[derive(Expunge)]`, so that fields of derived types can be required to
+/// implement [Erase] too
+#[doc(hidden)]
+pub trait Derived {}
+
+impl<T> Derived for Option<T> where T: Derived {}
+
+impl<R, E> Derived for Result<R, E>
+where
+    R: Derived,
+    E: Derived,
+{
+}
+
+impl<T> Derived for Vec<T> where T: Derived {}
+
+impl<K, V> Derived for HashMap<K, V> where V: Derived {}
+
+impl<T> Derived for HashSet<T> where T: Derived {}
+
+impl<T> Derived for Box<T> where T: Derived {}
+
+impl<T> Derived for Box<[T]> where T: Derived {}
+
+impl<T, const N: usize> Derived for [T; N] where T: Derived {}
+
+impl<T> Derived for VecDeque<T> where T: Derived {}
+
+impl<T> Derived for LinkedList<T> where T: Derived {}
+
+impl<T> Derived for BinaryHeap<T> where T: Derived {}
+
+impl<K, V> Derived for BTreeMap<K, V> where V: Derived {}
+
+impl<T> Derived for BTreeSet<T> where T: Derived {}
+
+impl<T> Derived for Rc<T> where T: Derived {}
+
+impl<T> Derived for Arc<T> where T: Derived {}
+
+impl<B> Derived for Cow<'_, B>
+where
+    B: ToOwned + ?Sized,
+    B::Owned: Derived,
+{
+}
+
+impl<T> Derived for Mutex<T> where T: Derived {}
+
+impl<T> Derived for RwLock<T> where T: Derived {}
+
+impl<T> Derived for Cell<T> where T: Derived {}
+
+impl<T> Derived for RefCell<T> where T: Derived {}
+
+/// Wraps a field so that it can be erased according to its type.
+///
+/// [ViaErase], [ViaDerived], [ViaZeroize] & [ViaExpunge] must be in scope, and the method must be
+/// called on `&&&&ErasedField::new(..)`, so that the most specific implementation takes precedence.
+#[doc(hidden)]
+pub struct ErasedField<T>(Cell<Option<T>>);
+
+impl<T> ErasedField<T> {
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    fn take(&self) -> T {
+        self.0.take().expect("a field is only erased once")
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaErase {
+    type Value;
+
+    fn erase(&self) -> Self::Value;
+}
+
+impl<T> ViaErase for &&&ErasedField<T>
+where
+    T: Erase,
+{
+    type Value = T;
+
+    fn erase(&self) -> T {
+        self.take().erase()
+    }
+}
+
+/// Derived types that don't implement [Erase] are selected here rather than falling back to
+/// [ViaExpunge], so that the bound on the method fails to compile
+#[doc(hidden)]
+pub trait ViaDerived {
+    type Value;
+
+    fn erase(&self) -> Self::Value
+    where
+        Self::Value: Erase;
+}
+
+impl<T> ViaDerived for &&ErasedField<T>
+where
+    T: Derived,
+{
+    type Value = T;
+
+    fn erase(&self) -> T
+    where
+        T: Erase,
+    {
+        self.take().erase()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaZeroize {
+    type Value;
+
+    fn erase(&self) -> Self::Value;
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> ViaZeroize for &ErasedField<T>
+where
+    T: zeroize::Zeroize + Expunge,
+{
+    type Value = T;
+
+    fn erase(&self) -> T {
+        let mut value = self.take();
+        value.zeroize();
+        value.expunge()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaExpunge {
+    type Value;
+
+    fn erase(&self) -> Self::Value;
+}
+
+impl<T> ViaExpunge for ErasedField<T>
+where
+    T: Expunge,
+{
+    type Value = T;
+
+    fn erase(&self) -> T {
+        self.take().expunge()
+    }
+}
+
+/// Wraps a field that's about to be replaced, so that it can be zeroized first if its type
+/// implements `Zeroize`.
+///
+/// [ViaWipe] & [ViaKeep] must be in scope, and the method must be called on
+/// `&&WipedField::new(..)`, so that zeroizing takes precedence.
+#[doc(hidden)]
+pub struct WipedField<'a, T>(Cell<Option<&'a mut T>>);
+
+impl<'a, T> WipedField<'a, T> {
+    pub fn new(value: &'a mut T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    #[cfg_attr(not(feature = "zeroize"), allow(dead_code))]
+    fn take(&self) -> &'a mut T {
+        self.0.take().expect("a field is only wiped once")
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaWipe {
+    fn wipe(&self);
+}
+
+#[cfg(feature = "zeroize")]
+impl<T> ViaWipe for &WipedField<'_, T>
+where
+    T: zeroize::Zeroize,
+{
+    fn wipe(&self) {
+        self.take().zeroize();
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaKeep {
+    fn wipe(&self);
+}
+
+impl<T> ViaKeep for WipedField<'_, T> {
+    fn wipe(&self) {}
+}
@@ -0,0 +1,317 @@
+//! Error types, and redacting errors when they're reported
+//!
+//! Errors from third-party code can't be annotated, so with the `regex` feature the messages of
+//! an error chain can be run through a [Scrubber](crate::utils::Scrubber) instead. With the
+//! `anyhow` or `eyre` features, [scrub_report] does so for their reports:
+//!
+//! ```rust
+//! # #[cfg(feature = "anyhow")]
+//! # {
+//! use anyhow::Context;
+//!
+//! let report = Err::<(), _>(anyhow::anyhow!("no user with email jane@example.com"))
+//!     .context("login failed")
+//!     .unwrap_err();
+//!
+//! let report = expunge::error::scrub_report(report);
+//! assert_eq!("login failed: no user with email <email>", format!("{report:#}"));
+//! # }
+//! ```
+
+use std::{fmt, sync::Arc};
+
+/// Errors from the crate's runtime subsystems, for when a single error type is more convenient
+/// than the error types of each module
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A selector of the [dynamic](crate::dynamic) engine couldn't be parsed, e.g. an invalid
+    /// JSON path
+    #[cfg(feature = "json")]
+    Selector(crate::dynamic::SelectorError),
+    /// A JSON pointer of a [json::Policy](crate::json::Policy) couldn't be parsed
+    #[cfg(feature = "json")]
+    Pointer(crate::json::PointerError),
+    /// A [policy](crate::policy) rule couldn't be parsed
+    Rule(crate::policy::RuleError),
+    /// A key couldn't be loaded from a keyring, e.g. by a KMS backed `KeyProvider` of the `crypto`
+    /// feature
+    Keyring(Box<dyn std::error::Error + Send + Sync>),
+    /// A value failed validation, e.g. deserialized input that still contained the placeholder of
+    /// an expunged value
+    Validation {
+        /// Where the value is, e.g. `address.lines[0]`
+        path: String,
+        reason: String,
+    },
+    /// A [cursor](crate::cursor) couldn't be encoded or decoded
+    #[cfg(feature = "cursor")]
+    Cursor(crate::cursor::CursorError),
+    /// A field wasn't set before building a value with a builder generated by
+    /// `#[expunge(builder)]`
+    MissingField {
+        container: &'static str,
+        field: &'static str,
+    },
+    /// A value couldn't be stored or resolved by a [Tokenizer](crate::tokenize::Tokenizer)
+    Tokenize(crate::tokenize::TokenizeError),
+    /// A value couldn't be encrypted or decrypted
+    #[cfg(feature = "crypto")]
+    Crypto(crate::crypto::CryptoError),
+}
+
+/// Wrapped errors are transparent: their messages are written by `Display`, so `source` skips
+/// them & returns their own source instead, and report chains don't print a message twice
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "json")]
+            Error::Selector(ref err) => err.fmt(f),
+            #[cfg(feature = "json")]
+            Error::Pointer(ref err) => err.fmt(f),
+            Error::Rule(ref err) => err.fmt(f),
+            Error::Keyring(ref err) => write!(f, "couldn't get the key: {err}"),
+            Error::Validation {
+                ref path,
+                ref reason,
+            } => write!(f, "`{path}` {reason}"),
+            #[cfg(feature = "cursor")]
+            Error::Cursor(ref err) => err.fmt(f),
+            Error::MissingField { container, field } => {
+                write!(f, "`{container}.{field}` must be set before building")
+            }
+            Error::Tokenize(ref err) => err.fmt(f),
+            #[cfg(feature = "crypto")]
+            Error::Crypto(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            #[cfg(feature = "json")]
+            Error::Selector(ref err) => err.source(),
+            #[cfg(feature = "json")]
+            Error::Pointer(ref err) => err.source(),
+            Error::Rule(ref err) => err.source(),
+            Error::Keyring(ref err) => err.source(),
+            Error::Validation { .. } => None,
+            #[cfg(feature = "cursor")]
+            Error::Cursor(ref err) => err.source(),
+            Error::MissingField { .. } => None,
+            Error::Tokenize(ref err) => err.source(),
+            #[cfg(feature = "crypto")]
+            Error::Crypto(ref err) => err.source(),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<crate::dynamic::SelectorError> for Error {
+    fn from(err: crate::dynamic::SelectorError) -> Self {
+        Error::Selector(err)
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<crate::json::PointerError> for Error {
+    fn from(err: crate::json::PointerError) -> Self {
+        Error::Pointer(err)
+    }
+}
+
+impl From<crate::policy::RuleError> for Error {
+    fn from(err: crate::policy::RuleError) -> Self {
+        Error::Rule(err)
+    }
+}
+
+#[cfg(feature = "cursor")]
+impl From<crate::cursor::CursorError> for Error {
+    fn from(err: crate::cursor::CursorError) -> Self {
+        Error::Cursor(err)
+    }
+}
+
+#[cfg(feature = "crypto")]
+impl From<crate::crypto::CryptoError> for Error {
+    fn from(err: crate::crypto::CryptoError) -> Self {
+        match err {
+            crate::crypto::CryptoError::Provider(err) => Error::Keyring(err),
+            err => Error::Crypto(err),
+        }
+    }
+}
+
+impl From<crate::tokenize::TokenizeError> for Error {
+    fn from(err: crate::tokenize::TokenizeError) -> Self {
+        Error::Tokenize(err)
+    }
+}
+
+/// An error whose `Display` & `Debug` implementations don't print sensitive values, which is
+/// implemented by `#[derive(Expunge)]` with `#[expunge(error)]`.
+///
+/// Only these errors can be the `source` of an `#[expunge(error)]`, so that the whole chain is
+/// redacted when it's reported, e.g. by `anyhow`.
+pub trait RedactedError: std::error::Error {}
+
+impl<E> RedactedError for Box<E> where E: RedactedError {}
+
+impl<E> RedactedError for Arc<E> where E: RedactedError {}
+
+/// An error chain whose messages have been scrubbed, see [scrub_chain]
+#[cfg(feature = "regex")]
+#[derive(Debug)]
+pub struct ScrubbedError {
+    message: String,
+    source: Option<Box<ScrubbedError>>,
+}
+
+#[cfg(feature = "regex")]
+impl fmt::Display for ScrubbedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "regex")]
+impl std::error::Error for ScrubbedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+#[cfg(feature = "regex")]
+impl RedactedError for ScrubbedError {}
+
+/// Copies the `Display` output of each error in the chain, scrubbed. The types of the errors are
+/// lost, so they can no longer be downcast.
+#[cfg(feature = "regex")]
+pub fn scrub_chain(
+    error: &(dyn std::error::Error + 'static),
+    scrubber: &crate::utils::Scrubber,
+) -> ScrubbedError {
+    ScrubbedError {
+        message: scrubber.scrub(error.to_string()),
+        source: error
+            .source()
+            .map(|source| Box::new(scrub_chain(source, scrubber))),
+    }
+}
+
+/// An error report, such as `anyhow::Error` or `eyre::Report`, that can be scrubbed with
+/// [scrub_report]
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+pub trait Report: Sized {
+    /// The outermost error of the chain
+    fn error(&self) -> &(dyn std::error::Error + 'static);
+
+    /// Wraps the scrubbed chain
+    fn from_scrubbed(error: ScrubbedError) -> Self;
+}
+
+#[cfg(feature = "anyhow")]
+impl Report for anyhow::Error {
+    fn error(&self) -> &(dyn std::error::Error + 'static) {
+        self.as_ref()
+    }
+
+    fn from_scrubbed(error: ScrubbedError) -> Self {
+        anyhow::Error::new(error)
+    }
+}
+
+#[cfg(feature = "eyre")]
+impl Report for eyre::Report {
+    fn error(&self) -> &(dyn std::error::Error + 'static) {
+        self.as_ref()
+    }
+
+    fn from_scrubbed(error: ScrubbedError) -> Self {
+        eyre::Report::new(error)
+    }
+}
+
+/// Scrubs each message of the report's chain with the built-in patterns of
+/// [Scrubber](crate::utils::Scrubber)
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+pub fn scrub_report<R>(report: R) -> R
+where
+    R: Report,
+{
+    scrub_report_with(report, &crate::utils::Scrubber::new())
+}
+
+/// Scrubs each message of the report's chain with a custom scrubber
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+pub fn scrub_report_with<R>(report: R, scrubber: &crate::utils::Scrubber) -> R
+where
+    R: Report,
+{
+    R::from_scrubbed(scrub_chain(report.error(), scrubber))
+}
+
+/// Installs an `eyre` handler that scrubs every message of a report when it's printed, including
+/// reports created by third-party code. It fails if a handler has already been installed.
+#[cfg(feature = "eyre")]
+pub fn install_eyre_hook(scrubber: crate::utils::Scrubber) -> Result<(), eyre::InstallError> {
+    let scrubber = Arc::new(scrubber);
+    eyre::set_hook(Box::new(move |_| {
+        Box::new(ScrubbingHandler {
+            scrubber: scrubber.clone(),
+        })
+    }))
+}
+
+#[cfg(feature = "eyre")]
+struct ScrubbingHandler {
+    scrubber: Arc<crate::utils::Scrubber>,
+}
+
+#[cfg(feature = "eyre")]
+impl eyre::EyreHandler for ScrubbingHandler {
+    fn debug(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        if f.alternate() {
+            return fmt::Debug::fmt(&scrub_chain(error, &self.scrubber), f);
+        }
+
+        let error = scrub_chain(error, &self.scrubber);
+        write!(f, "{error}")?;
+        let mut source = error.source.as_deref();
+        if source.is_some() {
+            write!(f, "\n\nCaused by:")?;
+        }
+        let mut i = 0;
+        while let Some(cause) = source {
+            write!(f, "\n    {i}: {cause}")?;
+            source = cause.source.as_deref();
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn display(
+        &self,
+        error: &(dyn std::error::Error + 'static),
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let error = scrub_chain(error, &self.scrubber);
+        write!(f, "{error}")?;
+        if f.alternate() {
+            let mut source = error.source.as_deref();
+            while let Some(cause) = source {
+                write!(f, ": {cause}")?;
+                source = cause.source.as_deref();
+            }
+        }
+        Ok(())
+    }
+}
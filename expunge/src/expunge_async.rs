@@ -0,0 +1,39 @@
+use std::future::Future;
+
+/// Trait for expunging values where some fields require I/O, e.g. tokenizing them with a vault
+/// service or encrypting them with a KMS key. It's implemented by `#[derive(Expunge)]` when a field
+/// is annotated with `with_async`.
+///
+/// `Expunge` is still implemented for the same types, and fields with `with_async` are expunged by
+/// their other attributes there, so that values are never left unexpunged by a synchronous caller.
+///
+/// ```rust
+/// use expunge::{Expunge, ExpungeAsync};
+///
+/// async fn tokenize(card: String) -> String {
+///     // e.g. a request to a vault service
+///     format!("tok_{}", card.len())
+/// }
+///
+/// #[derive(Expunge)]
+/// struct Payment {
+///     #[expunge(with_async = tokenize)]
+///     card_number: String,
+///     #[expunge(skip)]
+///     amount: u64,
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let payment = Payment {
+///     card_number: "4242424242424242".to_string(),
+///     amount: 100,
+/// };
+/// let payment = payment.expunge_async().await;
+/// assert_eq!("tok_16", payment.card_number);
+/// # });
+/// ```
+pub trait ExpungeAsync {
+    fn expunge_async(self) -> impl Future<Output = Self> + Send
+    where
+        Self: Sized;
+}
@@ -0,0 +1,33 @@
+use crate::Expunge;
+
+/// Expunges a value in place, e.g. a field of a larger value that's only borrowed mutably. It's
+/// implemented for every type that implements [Expunge] & [Default].
+///
+/// ```rust
+/// use expunge::{Expunge, ExpungeMut};
+///
+/// #[derive(Default, Expunge)]
+/// struct User {
+///     #[expunge(skip)]
+///     id: u64,
+///     email: String,
+/// }
+///
+/// let mut users = vec![User { id: 1, email: "jane@example.com".to_string() }];
+/// users[0].expunge_mut();
+///
+/// assert_eq!(1, users[0].id);
+/// assert_eq!("", users[0].email);
+/// ```
+pub trait ExpungeMut {
+    fn expunge_mut(&mut self);
+}
+
+impl<T> ExpungeMut for T
+where
+    T: Expunge + Default,
+{
+    fn expunge_mut(&mut self) {
+        *self = std::mem::take(self).expunge();
+    }
+}
@@ -0,0 +1,116 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    policy::{PolicyText, Rule},
+    Expunge,
+};
+
+/// A redaction strategy as a value, so that it can be constructed at runtime, stored in config &
+/// passed around. It's cheap to clone, and can be shared between threads.
+///
+/// Fields can be expunged with one using `#[expunge(expunger = ...)]`, where the expression
+/// evaluates to an `Expunger` of the field's type, or a reference to one.
+///
+/// ```rust
+/// use expunge::{Expunge, Expunger};
+/// use std::sync::LazyLock;
+///
+/// static EMAIL: LazyLock<Expunger<String>> = LazyLock::new(|| {
+///     Expunger::chain([
+///         Expunger::with(|email: String| email.to_lowercase()),
+///         Expunger::with(|email| expunge::utils::mask(email, 1, 4)),
+///     ])
+/// });
+///
+/// #[derive(Expunge)]
+/// struct User {
+///     #[expunge(expunger = EMAIL)]
+///     email: String,
+///     #[expunge(expunger = Expunger::as_value(0))]
+///     age: u8,
+/// }
+///
+/// let user = User {
+///     email: "Jane@Example.com".to_string(),
+///     age: 42,
+/// }
+/// .expunge();
+/// assert_eq!("j***********.com", user.email);
+/// assert_eq!(0, user.age);
+/// ```
+pub struct Expunger<T>(Arc<dyn Fn(T) -> T + Send + Sync>);
+
+impl<T: 'static> Expunger<T> {
+    /// Transforms values with the function
+    pub fn with(f: impl Fn(T) -> T + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Replaces values with a copy of this one
+    pub fn as_value(value: T) -> Self
+    where
+        T: Clone + Send + Sync,
+    {
+        Self::with(move |_| value.clone())
+    }
+
+    /// Replaces values with their `Default`
+    pub fn default_value() -> Self
+    where
+        T: Default,
+    {
+        Self::with(|_| T::default())
+    }
+
+    /// Expunges values with their [Expunge] implementation
+    pub fn expunged() -> Self
+    where
+        T: Expunge,
+    {
+        Self::with(Expunge::expunge)
+    }
+
+    /// Leaves values as-is
+    pub fn skip() -> Self {
+        Self::with(|value| value)
+    }
+
+    /// Applies each expunger in turn, so that each one transforms the output of the previous one.
+    /// Values are left as-is without any expungers.
+    pub fn chain(expungers: impl IntoIterator<Item = Expunger<T>>) -> Self {
+        let expungers: Vec<_> = expungers.into_iter().collect();
+        Self::with(move |value| expungers.iter().fold(value, |value, e| e.apply(value)))
+    }
+
+    /// Applies `next` to the output of this expunger
+    pub fn then(self, next: Expunger<T>) -> Self {
+        Self::chain([self, next])
+    }
+
+    /// Expunges the value
+    pub fn apply(&self, value: T) -> T {
+        (self.0)(value)
+    }
+}
+
+impl<T> Clone for Expunger<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for Expunger<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Expunger")
+    }
+}
+
+/// Strings can be expunged by a [Rule], e.g. one that's parsed from config
+impl<T> From<Rule> for Expunger<T>
+where
+    T: PolicyText + 'static,
+{
+    fn from(rule: Rule) -> Self {
+        Self::with(move |value: T| value.apply(&rule))
+    }
+}
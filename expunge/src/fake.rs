@@ -0,0 +1,99 @@
+//! Realistic synthetic values, e.g. for generating sanitized staging datasets from production
+//! dumps. Requires the `fake` feature.
+//!
+//! Fields annotated with `#[expunge(fake = "...")]` are replaced with a random value of the
+//! [Kind], instead of being emptied:
+//!
+//! ```rust
+//! use expunge::Expunge;
+//!
+//! #[derive(Expunge)]
+//! struct Customer {
+//!     #[expunge(fake = "name")]
+//!     name: String,
+//!     #[expunge(fake = "email")]
+//!     email: String,
+//!     #[expunge(fake = "phone")]
+//!     phone: Option<String>,
+//! }
+//!
+//! let customer = Customer {
+//!     name: "Jane Doe".to_string(),
+//!     email: "jane@example.org".to_string(),
+//!     phone: Some("+49 30 1234567".to_string()),
+//! }
+//! .expunge();
+//!
+//! assert_ne!("Jane Doe", customer.name);
+//! assert!(customer.email.ends_with("@example.com"));
+//! assert!(customer.phone.unwrap().starts_with("+1 555-01"));
+//! ```
+//!
+//! Values are random, so equal inputs have different fakes. Use a
+//! [Pseudonymizer](crate::pseudonymize::Pseudonymizer) where they must be consistent.
+
+use std::borrow::Cow;
+
+use crate::pseudonymize::{FIRST_NAMES, LAST_NAMES};
+
+/// The kind of value to generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Kind {
+    /// A full name, e.g. `Alex Smith`
+    Name,
+    /// A first name, e.g. `Alex`
+    FirstName,
+    /// A last name, e.g. `Smith`
+    LastName,
+    /// An email address at `example.com`, e.g. `alex.smith42@example.com`
+    Email,
+    /// A phone number in the fictional `555-01xx` range, e.g. `+1 555-0142`
+    Phone,
+}
+
+/// Generates a random value of the kind
+pub fn fake(kind: Kind) -> String {
+    let first = || FIRST_NAMES[fastrand::usize(..FIRST_NAMES.len())];
+    let last = || LAST_NAMES[fastrand::usize(..LAST_NAMES.len())];
+    match kind {
+        Kind::Name => format!("{} {}", first(), last()),
+        Kind::FirstName => first().to_string(),
+        Kind::LastName => last().to_string(),
+        Kind::Email => format!(
+            "{}.{}{}@example.com",
+            first().to_lowercase(),
+            last().to_lowercase(),
+            fastrand::u8(..100)
+        ),
+        Kind::Phone => format!("+1 555-01{:02}", fastrand::u8(..100)),
+    }
+}
+
+/// Values that can be replaced with fakes by `#[expunge(fake = "...")]`
+pub trait Fake {
+    fn fake(self, kind: Kind) -> Self
+    where
+        Self: Sized;
+}
+
+impl Fake for String {
+    fn fake(self, kind: Kind) -> Self {
+        fake(kind)
+    }
+}
+
+impl Fake for Cow<'_, str> {
+    fn fake(self, kind: Kind) -> Self {
+        Cow::Owned(fake(kind))
+    }
+}
+
+impl<T> Fake for Option<T>
+where
+    T: Fake,
+{
+    fn fake(self, kind: Kind) -> Self {
+        self.map(|value| value.fake(kind))
+    }
+}
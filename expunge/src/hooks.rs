@@ -0,0 +1,56 @@
+//! Audit hooks, called whenever a derived implementation expunges a field.
+//!
+//! This is a convenience layer over [observers](crate::observer), e.g. to build an audit trail
+//! proving that values were expunged before they left the service. Hooks can be registered
+//! globally, or scoped to a single call.
+//!
+//! ```rust
+//! use expunge::{hooks::{self, ExpungeEvent}, Expunge};
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Expunge)]
+//! #[expunge(class = pii)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! let events = Arc::new(Mutex::new(vec![]));
+//! let user = hooks::scoped(
+//!     {
+//!         let events = events.clone();
+//!         move |event: ExpungeEvent| events.lock().unwrap().push(event)
+//!     },
+//!     || User { name: "Alice".to_string() }.expunge(),
+//! );
+//!
+//! let events = events.lock().unwrap();
+//! assert_eq!("User", events[0].type_name);
+//! assert_eq!("name", events[0].field);
+//! assert_eq!(Some(expunge::Classification::Pii), events[0].class);
+//! ```
+
+use crate::observer::{self, EventMeta, Registration};
+
+/// Describes a field being expunged, including its type name, field name & classification
+pub use crate::observer::EventMeta as ExpungeEvent;
+
+/// Identifies a registered hook so that it can be unregistered
+pub use crate::observer::ObserverId as HookId;
+
+/// Registers a hook that's called for every field that's expunged, on any thread
+pub fn register(hook: impl Fn(ExpungeEvent) + Send + Sync + 'static) -> HookId {
+    observer::register(Registration::new(move |meta: &EventMeta| {
+        hook(meta.clone())
+    }))
+}
+
+/// Unregisters a previously registered hook. Returns false if it wasn't registered.
+pub fn unregister(id: HookId) -> bool {
+    observer::unregister(id)
+}
+
+/// Runs `f` with a hook that's called for every field expunged on the current thread while `f`
+/// runs
+pub fn scoped<R>(hook: impl Fn(ExpungeEvent) + 'static, f: impl FnOnce() -> R) -> R {
+    observer::scoped(move |meta: &EventMeta| hook(meta.clone()), f)
+}
@@ -0,0 +1,38 @@
+use std::iter::Map;
+
+use crate::{Expunge, Expunged};
+
+/// Expunges each item of an iterator lazily, e.g. rows streamed from a database
+///
+/// ```rust
+/// use expunge::prelude::*;
+///
+/// let emails = vec!["jane@example.com".to_string(), "john@example.com".to_string()];
+///
+/// for email in emails.into_iter().expunged() {
+///     assert_eq!("", email);
+/// }
+/// ```
+pub trait ExpungeIterator: Iterator + Sized
+where
+    Self::Item: Expunge,
+{
+    /// Expunges each item
+    #[allow(clippy::type_complexity)]
+    fn expunged(self) -> Map<Self, fn(Self::Item) -> Self::Item> {
+        self.map(Expunge::expunge)
+    }
+
+    /// Expunges each item, wrapping it in the [Expunged] guard
+    #[allow(clippy::type_complexity)]
+    fn guarded(self) -> Map<Self, fn(Self::Item) -> Expunged<Self::Item>> {
+        self.map(Expunged::new)
+    }
+}
+
+impl<I> ExpungeIterator for I
+where
+    I: Iterator,
+    I::Item: Expunge,
+{
+}
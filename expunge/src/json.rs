@@ -0,0 +1,300 @@
+//! Expunge semi-structured [serde_json::Value]s.
+//!
+//! [Expunge] is implemented for [Value] directly, which expunges every primitive in it. A [Policy]
+//! can be used instead to only expunge the values of particular keys, or to expunge everything
+//! except particular paths.
+//!
+//! ```rust
+//! use expunge::json::Policy;
+//! use serde_json::json;
+//!
+//! let policy = Policy::new().redact_keys(["password", "ssn"]);
+//!
+//! let payload = json!({
+//!     "username": "jane",
+//!     "password": "hunter2",
+//!     "profile": { "ssn": "078-05-1120", "age": 40 },
+//! });
+//!
+//! assert_eq!(
+//!     json!({
+//!         "username": "jane",
+//!         "password": "",
+//!         "profile": { "ssn": "", "age": 40 },
+//!     }),
+//!     policy.apply(payload),
+//! );
+//! ```
+
+use crate::Expunge;
+use serde_json::{Map, Number, Value};
+use std::{collections::HashSet, fmt};
+
+/// Primitive values are expunged as their default values, the same as their std counterparts.
+/// Arrays & objects are expunged recursively, keeping their keys & lengths. `null` is left as is.
+impl Expunge for Value {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        match self {
+            Value::Null => Value::Null,
+            Value::Bool(_) => Value::Bool(false),
+            Value::Number(_) => Value::Number(Number::from(0)),
+            Value::String(_) => Value::String(String::new()),
+            Value::Array(values) => Value::Array(values.expunge()),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| (key, value.expunge()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// How a key's value should be expunged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Redact,
+    #[cfg(feature = "hash")]
+    Hash,
+}
+
+/// A JSON pointer passed to [Policy::allow_pointers] isn't valid according to
+/// [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PointerError {
+    pointer: String,
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid JSON pointer `{}`, it must be empty or start with `/` & only escape `~0` or `~1`",
+            self.pointer
+        )
+    }
+}
+
+impl std::error::Error for PointerError {}
+
+/// Splits a pointer into its unescaped reference tokens
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, PointerError> {
+    let invalid = || PointerError {
+        pointer: pointer.to_string(),
+    };
+    if pointer.is_empty() {
+        return Ok(vec![]);
+    }
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        return Err(invalid());
+    };
+    tokens
+        .split('/')
+        .map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(invalid()),
+                }
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Expunges the values of particular keys, wherever they appear in a [Value]. Everything else is
+/// left as is, unless paths are allowed with [Policy::allow_pointers].
+///
+/// Keys are matched exactly, at any depth. When a key matches, its whole value is expunged,
+/// including anything nested in it.
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    redact: HashSet<String>,
+    #[cfg(feature = "hash")]
+    hash: HashSet<String>,
+    // the reference tokens of each allowed JSON pointer
+    allow: Vec<Vec<String>>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expunge the values of these keys, as with [Value::expunge]
+    pub fn redact_keys<I>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.redact.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Replace the values of these keys with digests of them, using [crate::utils::hash]. Values
+    /// that aren't strings are hashed as JSON.
+    #[cfg(feature = "hash")]
+    pub fn hash_keys<I>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.hash.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// Switches to allowlist mode, where everything is expunged except the values at these
+    /// [JSON pointers](https://www.rfc-editor.org/rfc/rfc6901), e.g. `/user/name` or `/items/0`.
+    ///
+    /// This is a safer default for untrusted or evolving payloads, since new fields are expunged
+    /// until they're explicitly allowed. Keys are still expunged within allowed values.
+    ///
+    /// ```rust
+    /// use expunge::json::Policy;
+    /// use serde_json::json;
+    ///
+    /// let policy = Policy::new()
+    ///     .allow_pointers(["/user/name", "/items"])?
+    ///     .redact_keys(["secret"]);
+    ///
+    /// let payload = json!({
+    ///     "user": { "name": "jane", "email": "jane@example.com" },
+    ///     "items": [{ "id": 1, "secret": "shh" }],
+    ///     "new_field": "unreviewed",
+    /// });
+    ///
+    /// assert_eq!(
+    ///     json!({
+    ///         "user": { "name": "jane", "email": "" },
+    ///         "items": [{ "id": 1, "secret": "" }],
+    ///         "new_field": "",
+    ///     }),
+    ///     policy.apply(payload),
+    /// );
+    /// # Ok::<_, expunge::json::PointerError>(())
+    /// ```
+    ///
+    /// As in the RFC, the empty pointer `""` refers to the whole document & `"/"` to the empty
+    /// key. Pointers that are neither empty nor start with `/` are rejected.
+    pub fn allow_pointers<I>(mut self, pointers: I) -> Result<Self, PointerError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        for pointer in pointers {
+            self.allow.push(parse_pointer(pointer.as_ref())?);
+        }
+        Ok(self)
+    }
+
+    fn action(&self, key: &str) -> Option<Action> {
+        if self.redact.contains(key) {
+            return Some(Action::Redact);
+        }
+        #[cfg(feature = "hash")]
+        if self.hash.contains(key) {
+            return Some(Action::Hash);
+        }
+        None
+    }
+
+    /// Expunges the value according to the policy
+    pub fn apply(&self, value: Value) -> Value {
+        if self.allow.is_empty() {
+            self.apply_keys(value)
+        } else {
+            self.apply_allowed(value, &mut vec![])
+        }
+    }
+
+    /// Applies the key based rules
+    fn apply_keys(&self, value: Value) -> Value {
+        match value {
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.apply_keys(value))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = match self.action(&key) {
+                            Some(action) => self.transform(action, value),
+                            None => self.apply_keys(value),
+                        };
+                        (key, value)
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+            value => value,
+        }
+    }
+
+    /// Expunges everything that isn't allowed, where `path` is the location of `value`
+    fn apply_allowed(&self, value: Value, path: &mut Vec<String>) -> Value {
+        let is_allowed = self.allow.iter().any(|allowed| path.starts_with(allowed));
+        if is_allowed {
+            return self.apply_keys(value);
+        }
+        let is_ancestor = self.allow.iter().any(|allowed| allowed.starts_with(path));
+        if !is_ancestor {
+            return value.expunge();
+        }
+
+        match value {
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        path.push(i.to_string());
+                        let value = self.apply_allowed(value, path);
+                        path.pop();
+                        value
+                    })
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = match self.action(&key) {
+                            Some(action) => self.transform(action, value),
+                            None => {
+                                path.push(key.clone());
+                                let value = self.apply_allowed(value, path);
+                                path.pop();
+                                value
+                            }
+                        };
+                        (key, value)
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+            value => value.expunge(),
+        }
+    }
+
+    fn transform(&self, action: Action, value: Value) -> Value {
+        match action {
+            Action::Redact => value.expunge(),
+            #[cfg(feature = "hash")]
+            Action::Hash => {
+                let value = match value {
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                Value::String(crate::utils::hash(value, None))
+            }
+        }
+    }
+}
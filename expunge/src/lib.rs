@@ -1,17 +1,168 @@
 #![doc = include_str!(concat!("../", std::env!("CARGO_PKG_README")))]
 
 use std::{
-    collections::{HashMap, HashSet},
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
+    marker::{PhantomData, PhantomPinned},
     ops::Deref,
+    rc::Rc,
+    sync::{Arc, Mutex, PoisonError, RwLock},
 };
 
 pub use expunge_derive::*;
 
 pub mod primitives;
 
+pub mod placeholder;
+
+pub mod prelude;
+
+#[doc(hidden)]
+pub mod debug;
+
+#[doc(hidden)]
+pub mod checks;
+
+#[doc(hidden)]
+pub mod telemetry;
+
+mod classification;
+pub use classification::Classification;
+
+mod summary;
+pub use summary::{Summarize, Summary};
+
+mod maybe_expunged;
+pub use maybe_expunged::MaybeExpunged;
+
+mod shared_expunged;
+pub use shared_expunged::SharedExpunged;
+
+mod default_expunged;
+pub use default_expunged::DefaultExpunged;
+
+mod expunger;
+pub use expunger::Expunger;
+
+pub mod error;
+pub use error::{Error, RedactedError};
+
+mod iter;
+pub use iter::ExpungeIterator;
+
+mod expunge_async;
+pub use expunge_async::ExpungeAsync;
+
+mod expunge_mut;
+pub use expunge_mut::ExpungeMut;
+
+mod try_expunge;
+pub use try_expunge::TryExpunge;
+
+pub mod erase;
+pub use erase::Erase;
+
+pub mod policy;
+
+pub mod profile;
+
+pub mod tokenize;
+
+pub mod pseudonymize;
+
+#[cfg(feature = "fake")]
+pub mod fake;
+
+#[cfg(feature = "crypto")]
+pub mod crypto;
+
+#[cfg(feature = "cursor")]
+pub mod cursor;
+
+#[cfg(feature = "json")]
+pub mod json;
+
+#[cfg(feature = "json")]
+pub mod dynamic;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "json")]
+pub mod reject;
+
+#[cfg(feature = "json")]
+mod diff;
+#[cfg(feature = "json")]
+pub use diff::{diff, Change, ExpungeDiff};
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "slog")]
+pub mod slog;
+
+#[cfg(feature = "observer")]
+pub mod observer;
+
+#[cfg(feature = "observer")]
+pub mod hooks;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "manifest-export")]
+pub mod manifest;
+
+// the derive only emits manifests with its own `manifest-export` feature, but that can be enabled
+// by feature unification while this crate's isn't, so what it references must always exist
+#[cfg(not(feature = "manifest-export"))]
+#[doc(hidden)]
+pub mod manifest {
+    pub trait Manifest {
+        const MANIFEST: &'static str;
+    }
+
+    #[macro_export]
+    macro_rules! __register_manifest {
+        ($type_name:expr, $manifest:expr) => {};
+    }
+}
+
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
+#[cfg(feature = "otel")]
+pub mod otel;
+
+#[cfg(feature = "sentry")]
+pub mod sentry;
+
+#[cfg(feature = "tonic")]
+pub mod tonic;
+
+#[cfg(feature = "regex")]
+pub mod panic;
+
+#[cfg(feature = "regex")]
+pub mod stream;
+
+#[cfg(any(feature = "sqlx", feature = "diesel"))]
+pub mod sql;
+
 /// A collection of utils for common ways to expunge things
 pub mod utils;
 
+#[cfg(feature = "valuable")]
+mod valuable;
+
+#[cfg(feature = "chrono")]
+mod chrono;
+
+#[cfg(feature = "time")]
+mod time;
+
 #[cfg(feature = "zeroize")]
 #[doc(hidden)]
 pub use ::zeroize;
@@ -19,16 +170,12 @@ pub use ::zeroize;
 #[cfg(feature = "zeroize")]
 use secrecy::Secret;
 #[cfg(feature = "zeroize")]
-use zeroize::{DefaultIsZeroes, Zeroize};
+use zeroize::Zeroize;
 
 #[cfg(feature = "zeroize")]
 #[doc(hidden)]
 pub use ::secrecy;
 
-#[cfg(feature = "serde")]
-#[doc(hidden)]
-pub use ::serde;
-
 /// Trait for recursively expunging values marked as sensitive
 pub trait Expunge {
     fn expunge(self) -> Self
@@ -67,7 +214,10 @@ where
 /// [Expunged] is a type guard that can be used to ensure that values have been expunged. It is
 /// impossible to construct `Expunged<T>` with an unexpunged T.
 ///
-/// The
+/// With the `serde` feature it can be serialized & deserialized transparently. Deserialized values
+/// are taken to be already expunged, e.g. when they're read back from a log, so that
+/// transformations that aren't idempotent, such as `hash` or `encrypt`, aren't applied twice.
+/// Untrusted input should be deserialized as `T` & expunged with [Expunged::new] instead.
 ///
 /// ### Usage
 ///
@@ -89,6 +239,7 @@ where
 ///     println!("Some expunged pii: {pii:?}");
 /// }
 /// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Expunged<T>(T);
 
 impl<T> From<T> for Expunged<T>
@@ -96,15 +247,86 @@ where
     T: Expunge,
 {
     fn from(value: T) -> Self {
-        Expunged(value.expunge())
+        Expunged::new(value)
     }
 }
 
-#[allow(dead_code)]
 impl<T> Expunged<T> {
-    fn into_inner(self) -> T {
+    /// Expunges the value
+    pub fn new(value: T) -> Self
+    where
+        T: Expunge,
+    {
+        Expunged(value.expunge())
+    }
+
+    /// Expunges the value with [ExpungeAsync]
+    pub async fn new_async(value: T) -> Self
+    where
+        T: ExpungeAsync,
+    {
+        Expunged(value.expunge_async().await)
+    }
+
+    /// Returns the expunged value
+    pub fn into_inner(self) -> T {
         self.0
     }
+
+    /// A compact summary of which fields were expunged, for when even the expunged value
+    /// shouldn't be printed
+    ///
+    /// ```rust
+    /// use expunge::{Expunge, Expunged};
+    ///
+    /// #[derive(Expunge)]
+    /// struct User {
+    ///     #[expunge(skip)]
+    ///     id: u64,
+    ///     name: String,
+    ///     email: String,
+    /// }
+    ///
+    /// let user = Expunged::new(User {
+    ///     id: 1,
+    ///     name: "Alice".to_string(),
+    ///     email: "alice@example.com".to_string(),
+    /// });
+    ///
+    /// assert_eq!("Expunged<User>: 2/3 fields redacted", user.summary().to_string());
+    /// ```
+    pub fn summary(&self) -> Summary
+    where
+        T: Summarize,
+    {
+        self.0.summarize()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for Expunged<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for Expunged<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Expunged)
+    }
 }
 
 impl<T> Deref for Expunged<T> {
@@ -115,6 +337,8 @@ impl<T> Deref for Expunged<T> {
     }
 }
 
+/// Formats the expunged value. To print only which fields were redacted, e.g. where even the fields
+/// that are left as-is shouldn't end up in logs, opt in with [Expunged::summary].
 impl<T> std::fmt::Display for Expunged<T>
 where
     T: std::fmt::Display,
@@ -182,10 +406,235 @@ where
     }
 }
 
+impl<T> Expunge for Box<[T]>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_vec().expunge().into_boxed_slice()
+    }
+}
+
+impl<T, const N: usize> Expunge for [T; N]
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.map(Expunge::expunge)
+    }
+}
+
+impl<T> Expunge for VecDeque<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_iter().map(Expunge::expunge).collect()
+    }
+}
+
+impl<T> Expunge for LinkedList<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_iter().map(Expunge::expunge).collect()
+    }
+}
+
+impl<T> Expunge for BinaryHeap<T>
+where
+    T: Expunge + Ord,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_iter().map(Expunge::expunge).collect()
+    }
+}
+
+impl<K, V> Expunge for BTreeMap<K, V>
+where
+    K: Ord,
+    V: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_iter().map(|(k, v)| (k, v.expunge())).collect()
+    }
+}
+
+impl<T> Expunge for BTreeSet<T>
+where
+    T: Expunge + Ord,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self.into_iter().map(Expunge::expunge).collect()
+    }
+}
+
+/// The value is cloned if there are other references to it, so that they are left unexpunged.
+impl<T> Expunge for Rc<T>
+where
+    T: Expunge + Clone,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Rc::new(Rc::unwrap_or_clone(self).expunge())
+    }
+}
+
+/// The value is cloned if there are other references to it, so that they are left unexpunged.
+impl<T> Expunge for Arc<T>
+where
+    T: Expunge + Clone,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Arc::new(Arc::unwrap_or_clone(self).expunge())
+    }
+}
+
+/// Immutable strings are replaced with empty strings, the same as [String]. Other references to a
+/// shared string are left unexpunged.
+macro_rules! expunge_str {
+    ($($ty:ty),*) => {
+        $(
+            impl Expunge for $ty {
+                fn expunge(self) -> Self
+                where
+                    Self: Sized,
+                {
+                    <$ty>::from("")
+                }
+            }
+        )*
+    };
+}
+
+expunge_str!(Box<str>, Rc<str>, Arc<str>);
+
+/// Borrowed values are converted to owned values before being expunged.
+impl<'a, B> Expunge for Cow<'a, B>
+where
+    B: ToOwned + ?Sized,
+    B::Owned: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Cow::Owned(self.into_owned().expunge())
+    }
+}
+
+/// Markers have no data, so there's nothing to expunge
+impl<T> Expunge for PhantomData<T>
+where
+    T: ?Sized,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl Expunge for PhantomPinned {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// Poisoned values are expunged all the same.
+impl<T> Expunge for Mutex<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Mutex::new(
+            self.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .expunge(),
+        )
+    }
+}
+
+/// Poisoned values are expunged all the same.
+impl<T> Expunge for RwLock<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        RwLock::new(
+            self.into_inner()
+                .unwrap_or_else(PoisonError::into_inner)
+                .expunge(),
+        )
+    }
+}
+
+impl<T> Expunge for Cell<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Cell::new(self.into_inner().expunge())
+    }
+}
+
+impl<T> Expunge for RefCell<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        RefCell::new(self.into_inner().expunge())
+    }
+}
+
 #[cfg(feature = "zeroize")]
+/// Secrets can't be exposed by accident, so they're left as-is. This includes `SecretString` &
+/// `SecretVec<u8>`.
 impl<T> Expunge for Secret<T>
 where
-    T: DefaultIsZeroes,
     T: Zeroize,
 {
     fn expunge(self) -> Self
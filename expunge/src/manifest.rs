@@ -0,0 +1,112 @@
+//! Exports how each type is expunged, so that non-Rust tooling (e.g. data catalogs or SQL masking
+//! views) can use the same annotations as the source of truth.
+//!
+//! With the `manifest-export` feature, `#[derive(Expunge)]` generates a JSON descriptor for each
+//! type, available through [Manifest]:
+//!
+//! ```rust
+//! use expunge::{manifest::Manifest, Expunge};
+//!
+//! #[derive(Expunge)]
+//! struct User {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     #[expunge(class = pii, keep_last = 4)]
+//!     phone: String,
+//! }
+//!
+//! assert_eq!(
+//!     r#"{"type":"User","kind":"struct","fields":[{"name":"id","action":"skip","conditional":false,"zeroize":false},{"name":"phone","action":"mask","value":"0,4","class":"pii","conditional":false,"zeroize":false}]}"#,
+//!     User::MANIFEST,
+//! );
+//! ```
+//!
+//! Each field has an `action`, one of `skip`, `expunge`, `as`, `with`, `with_self`, `hash`,
+//! `encrypt`, `none`, `clear` or `mask`. Actions with an argument include it as `value`, e.g. the
+//! expression for `as` or the salt field for `hash`.
+//!
+//! Every descriptor is also registered, so that they can be listed with [entries], or written to
+//! files with [export], e.g. from a small bin or a test that runs in CI:
+//!
+//! ```rust,no_run
+//! for path in expunge::manifest::export("target/expunge-manifest")? {
+//!     println!("wrote {}", path.display());
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+//!
+//! Only types in crates that are linked into the binary are registered.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+#[doc(hidden)]
+pub use ::inventory;
+
+/// Implemented by `#[derive(Expunge)]` with the `manifest-export` feature
+pub trait Manifest {
+    /// A JSON descriptor of how each field is expunged
+    const MANIFEST: &'static str;
+}
+
+/// A descriptor registered by `#[derive(Expunge)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    /// The name of the type, e.g. `User`
+    pub type_name: &'static str,
+    /// The module the type was declared in, e.g. `my_crate::models`
+    pub module_path: &'static str,
+    /// The same as [Manifest::MANIFEST]
+    pub manifest: &'static str,
+}
+
+impl Entry {
+    /// The name of the file [export] writes the descriptor to, e.g. `my_crate.models.User.json`
+    pub fn file_name(&self) -> String {
+        format!(
+            "{}.{}.json",
+            self.module_path.replace("::", "."),
+            self.type_name
+        )
+    }
+}
+
+inventory::collect!(Entry);
+
+/// Every registered descriptor, in no particular order
+pub fn entries() -> impl Iterator<Item = &'static Entry> {
+    inventory::iter::<Entry>.into_iter()
+}
+
+/// Writes every registered descriptor to its own file in `dir`, which is created if it doesn't
+/// exist. Returns the paths written, sorted.
+pub fn export(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let mut paths = vec![];
+    for entry in entries() {
+        let path = dir.join(entry.file_name());
+        std::fs::write(&path, entry.manifest)?;
+        paths.push(path);
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Registers a descriptor with [entries], called by `#[derive(Expunge)]`
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_manifest {
+    ($type_name:expr, $manifest:expr) => {
+        $crate::manifest::inventory::submit! {
+            $crate::manifest::Entry {
+                type_name: $type_name,
+                module_path: ::core::module_path!(),
+                manifest: $manifest,
+            }
+        }
+    };
+}
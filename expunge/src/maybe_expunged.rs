@@ -0,0 +1,87 @@
+use crate::{Expunge, Expunged};
+
+/// Tracks whether a value has been expunged at runtime, for pipelines that pass data through
+/// several stages before it reaches a sink (e.g. a log or an external API).
+///
+/// ```rust
+/// use expunge::{Expunge, MaybeExpunged};
+///
+/// #[derive(Expunge)]
+/// struct User {
+///     name: String,
+/// }
+///
+/// let user = MaybeExpunged::Raw(User { name: "Alice".to_string() })
+///     .map_raw(|user| User { name: user.name.to_uppercase() })
+///     .expunge();
+///
+/// assert_eq!("", user.assert_expunged().name);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MaybeExpunged<T> {
+    Raw(T),
+    /// Holds an [Expunged] so that raw values can't be passed off as expunged
+    Expunged(Expunged<T>),
+}
+
+impl<T> MaybeExpunged<T> {
+    pub fn is_expunged(&self) -> bool {
+        matches!(self, Self::Expunged(_))
+    }
+
+    /// Transforms the value if it hasn't been expunged yet
+    pub fn map_raw(self, f: impl FnOnce(T) -> T) -> Self {
+        match self {
+            Self::Raw(value) => Self::Raw(f(value)),
+            expunged => expunged,
+        }
+    }
+
+    /// Expunges the value if it hasn't been expunged yet
+    pub fn into_expunged(self) -> Expunged<T>
+    where
+        T: Expunge,
+    {
+        match self {
+            Self::Raw(value) => Expunged::new(value),
+            Self::Expunged(value) => value,
+        }
+    }
+
+    /// Enforces that the value has been expunged before it reaches a sink.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the value hasn't been expunged. In release builds, the value is
+    /// expunged instead.
+    #[track_caller]
+    pub fn assert_expunged(self) -> Expunged<T>
+    where
+        T: Expunge,
+    {
+        debug_assert!(
+            self.is_expunged(),
+            "a raw `{}` reached a sink without being expunged",
+            std::any::type_name::<T>()
+        );
+        self.into_expunged()
+    }
+}
+
+impl<T> Expunge for MaybeExpunged<T>
+where
+    T: Expunge,
+{
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Self::Expunged(self.into_expunged())
+    }
+}
+
+impl<T> From<Expunged<T>> for MaybeExpunged<T> {
+    fn from(value: Expunged<T>) -> Self {
+        Self::Expunged(value)
+    }
+}
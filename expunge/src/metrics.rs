@@ -0,0 +1,18 @@
+//! Counts expunged fields with the [metrics](https://docs.rs/metrics) crate, so that you can
+//! monitor whether redaction is actually happening in production.
+//!
+//! Derived implementations increment [FIELDS_TOTAL] once for every field that's expunged, labelled
+//! with `type` and `field`. Fields of enum variants are labelled with the type `Enum::Variant`.
+//! Skipped fields aren't counted.
+//!
+//! Nothing is recorded until a recorder is installed, e.g. with `metrics::set_global_recorder`.
+
+/// The name of the counter, e.g. `expunge_fields_total{type="User",field="ssn"}`
+pub const FIELDS_TOTAL: &str = "expunge_fields_total";
+
+/// Increments [FIELDS_TOTAL]. This is called by derived implementations for each field that's
+/// expunged.
+#[doc(hidden)]
+pub fn increment(type_name: &'static str, field: &'static str) {
+    ::metrics::counter!(FIELDS_TOTAL, "type" => type_name, "field" => field).increment(1);
+}
@@ -0,0 +1,247 @@
+//! Observe values being expunged, e.g. for metrics, auditing or debugging.
+//!
+//! Observers are registered globally and are notified for every field that's expunged by a
+//! derived [Expunge](crate::Expunge) implementation. Multiple observers can be registered at once,
+//! each with its own minimum severity and sampling rate.
+//!
+//! Dispatch never holds a lock while observers are running, and does nothing more than an atomic
+//! load when no observers are registered. Observers that do slow work (e.g. I/O) should be wrapped
+//! with [non_blocking] so that expunging doesn't wait on them.
+//!
+//! ```rust
+//! use expunge::observer::{self, EventMeta, ExpungeObserver, Registration};
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//!
+//! struct Counter(AtomicUsize);
+//!
+//! impl ExpungeObserver for Counter {
+//!     fn on_expunge(&self, _meta: &EventMeta) {
+//!         self.0.fetch_add(1, Ordering::Relaxed);
+//!     }
+//! }
+//!
+//! let id = observer::register(Registration::new(Counter(AtomicUsize::new(0))).sample_every(10));
+//! // ...
+//! observer::unregister(id);
+//! ```
+
+use crate::Classification;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc, PoisonError, RwLock,
+    },
+};
+
+/// How significant an event is. Observers only receive events at or above their minimum severity.
+///
+/// Derived implementations set this from the class of the field, see [Severity::for_class].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Debug,
+    Info,
+    Warn,
+}
+
+impl Severity {
+    /// Unclassified fields are [Severity::Debug], [Classification::Pii] & custom classes are
+    /// [Severity::Info], and the remaining, more sensitive classes are [Severity::Warn]
+    pub fn for_class(class: Option<&Classification>) -> Self {
+        match class {
+            None => Severity::Debug,
+            Some(Classification::Pii | Classification::Other(_)) => Severity::Info,
+            Some(
+                Classification::Spi
+                | Classification::Secret
+                | Classification::Financial
+                | Classification::Health,
+            ) => Severity::Warn,
+        }
+    }
+}
+
+/// Describes a single field being expunged
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventMeta {
+    /// The name of the type being expunged
+    pub type_name: &'static str,
+    /// The enum variant the field belongs to, if any
+    pub variant: Option<&'static str>,
+    /// The name of the field, or its index for tuple structs & variants
+    pub field: &'static str,
+    /// Set with `#[expunge(class = ...)]`
+    pub class: Option<Classification>,
+    pub severity: Severity,
+}
+
+/// Receives events when values are expunged
+pub trait ExpungeObserver: Send + Sync {
+    fn on_expunge(&self, meta: &EventMeta);
+}
+
+impl<F> ExpungeObserver for F
+where
+    F: Fn(&EventMeta) + Send + Sync,
+{
+    fn on_expunge(&self, meta: &EventMeta) {
+        self(meta)
+    }
+}
+
+/// An observer along with the options it was registered with
+pub struct Registration {
+    observer: Box<dyn ExpungeObserver>,
+    min_severity: Severity,
+    sample_every: u64,
+    seen: AtomicU64,
+}
+
+impl Registration {
+    /// Receives every event, regardless of severity
+    pub fn new(observer: impl ExpungeObserver + 'static) -> Self {
+        Self {
+            observer: Box::new(observer),
+            min_severity: Severity::Debug,
+            sample_every: 1,
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Only receive events at or above the given severity
+    pub fn min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = severity;
+        self
+    }
+
+    /// Only receive every `n`th event. Sampling is deterministic, so `sample_every(100)` receives
+    /// exactly 1% of events.
+    pub fn sample_every(mut self, n: u64) -> Self {
+        self.sample_every = n.max(1);
+        self
+    }
+
+    fn dispatch(&self, meta: &EventMeta) {
+        if meta.severity < self.min_severity {
+            return;
+        }
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        if seen % self.sample_every != 0 {
+            return;
+        }
+        self.observer.on_expunge(meta);
+    }
+}
+
+/// Identifies a registered observer so that it can be unregistered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObserverId(usize);
+
+type Observers = Arc<[(ObserverId, Arc<Registration>)]>;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+static OBSERVERS: RwLock<Option<Observers>> = RwLock::new(None);
+
+type ScopedObserver = Rc<dyn Fn(&EventMeta)>;
+
+thread_local! {
+    static SCOPED: RefCell<Vec<ScopedObserver>> = const { RefCell::new(Vec::new()) };
+}
+
+fn update(f: impl FnOnce(&mut Vec<(ObserverId, Arc<Registration>)>)) {
+    let mut observers = OBSERVERS.write().unwrap_or_else(PoisonError::into_inner);
+    let mut next: Vec<_> = observers.iter().flat_map(|o| o.iter().cloned()).collect();
+    f(&mut next);
+    ENABLED.store(!next.is_empty(), Ordering::Release);
+    *observers = Some(next.into());
+}
+
+/// Registers an observer globally
+pub fn register(registration: Registration) -> ObserverId {
+    let id = ObserverId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    update(|observers| observers.push((id, Arc::new(registration))));
+    id
+}
+
+/// Unregisters a previously registered observer. Returns false if it wasn't registered.
+pub fn unregister(id: ObserverId) -> bool {
+    let mut found = false;
+    update(|observers| {
+        let before = observers.len();
+        observers.retain(|(other, _)| *other != id);
+        found = observers.len() != before;
+    });
+    found
+}
+
+/// Whether any observers are registered, either globally or scoped to the current thread
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire) || SCOPED.with(|scoped| !scoped.borrow().is_empty())
+}
+
+/// Runs `f` with an observer that receives the events emitted on the current thread while `f`
+/// runs, in addition to any global observers.
+///
+/// Scoped observers aren't sampled or filtered by severity.
+pub fn scoped<R>(observer: impl Fn(&EventMeta) + 'static, f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            SCOPED.with(|scoped| scoped.borrow_mut().pop());
+        }
+    }
+
+    SCOPED.with(|scoped| scoped.borrow_mut().push(Rc::new(observer)));
+    let _guard = Guard;
+    f()
+}
+
+/// Notifies all registered observers. This is called by derived implementations for each field
+/// that's expunged.
+pub fn notify(meta: &EventMeta) {
+    if !is_enabled() {
+        return;
+    }
+
+    // the lock is only held long enough to take a snapshot, so observers can (un)register others
+    let observers = OBSERVERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    for (_, registration) in observers.iter().flat_map(|o| o.iter()) {
+        registration.dispatch(meta);
+    }
+
+    // also a snapshot, in case scoped observers expunge things themselves
+    let scoped = SCOPED.with(|scoped| scoped.borrow().clone());
+    for observer in scoped {
+        observer(meta);
+    }
+}
+
+/// Wraps an observer so that events are sent over a channel and handled on a background thread,
+/// so that slow observers don't hold up expunging.
+///
+/// The background thread exits once the returned observer is dropped.
+pub fn non_blocking(observer: impl ExpungeObserver + 'static) -> NonBlocking {
+    let (sender, receiver) = mpsc::channel::<EventMeta>();
+    std::thread::spawn(move || {
+        for meta in receiver {
+            observer.on_expunge(&meta);
+        }
+    });
+    NonBlocking(sender)
+}
+
+/// An observer that handles events on a background thread. See [non_blocking].
+pub struct NonBlocking(mpsc::Sender<EventMeta>);
+
+impl ExpungeObserver for NonBlocking {
+    fn on_expunge(&self, meta: &EventMeta) {
+        // the receiver only goes away if the observer panicked, in which case there's nothing to do
+        let _ = self.0.send(meta.clone());
+    }
+}
@@ -0,0 +1,322 @@
+//! Scrubs the attributes of OpenTelemetry spans & logs before they're exported, so that exporters,
+//! e.g. OTLP, never ship raw personal data.
+//!
+//! [ScrubSpanProcessor] & [ScrubLogProcessor] wrap the processor that exports, and run an
+//! [AttributeScrubber] over every span or log record before passing it on:
+//!
+//! - the values of attributes whose keys match a pattern, e.g. `*password*`, are replaced with
+//!   `<expunged>`
+//! - every other string value is scrubbed by a [Scrubber], which replaces emails, card numbers &
+//!   other sensitive data in free text
+//!
+//! ```rust
+//! use expunge::otel::{AttributeScrubber, ScrubSpanProcessor};
+//! use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SimpleSpanProcessor};
+//!
+//! let exporter = InMemorySpanExporter::default();
+//! let scrubber = AttributeScrubber::new().redact_keys(["user.email", "http.request.header.*"]);
+//! let provider = SdkTracerProvider::builder()
+//!     .with_span_processor(
+//!         ScrubSpanProcessor::new(SimpleSpanProcessor::new(exporter.clone())).scrubber(scrubber),
+//!     )
+//!     .build();
+//! ```
+//!
+//! Other processors that are registered with the provider alongside the wrapper still see the
+//! raw values, so the processor that exports should always be the wrapped one.
+
+use std::{collections::HashMap, time::Duration};
+
+use opentelemetry::{
+    logs::{AnyValue, LogRecord as _, Logger as _, LoggerProvider as _},
+    trace::Status,
+    Array, Context, InstrumentationScope, Key, KeyValue, StringValue, Value,
+};
+use opentelemetry_sdk::{
+    error::OTelSdkResult,
+    logs::{LogProcessor, SdkLogRecord, SdkLogger, SdkLoggerProvider},
+    trace::{Span, SpanData, SpanProcessor},
+    Resource,
+};
+
+use crate::utils::{matches_glob, Scrubber, SENSITIVE_KEYS};
+
+/// Redacts the values of attributes by their keys, and scrubs the other string values. See the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct AttributeScrubber {
+    keys: Vec<String>,
+    scrubber: Option<Scrubber>,
+}
+
+impl AttributeScrubber {
+    /// A scrubber with the built-in key patterns, e.g. `*password*` & `*token*`, and the built-in
+    /// patterns of [Scrubber]
+    pub fn new() -> Self {
+        Self {
+            keys: SENSITIVE_KEYS.iter().map(ToString::to_string).collect(),
+            scrubber: Some(Scrubber::new()),
+        }
+    }
+
+    /// A scrubber that leaves every attribute as-is
+    pub fn empty() -> Self {
+        Self {
+            keys: vec![],
+            scrubber: None,
+        }
+    }
+
+    /// Redacts the values of attributes whose keys match one of the patterns, ignoring ASCII case.
+    /// `*` matches any number of characters, e.g. `http.request.header.*`.
+    pub fn redact_keys<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.keys.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Scrub string values with a different scrubber, e.g. with custom patterns
+    pub fn scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = Some(scrubber);
+        self
+    }
+
+    /// Whether the value of the attribute would be redacted entirely
+    pub fn is_redacted(&self, key: &str) -> bool {
+        self.keys.iter().any(|pattern| matches_glob(pattern, key))
+    }
+
+    /// Scrubs the value of a span attribute
+    pub fn scrub(&self, attribute: KeyValue) -> KeyValue {
+        let KeyValue { key, value, .. } = attribute;
+        if self.is_redacted(key.as_str()) {
+            return KeyValue::new(key, "<expunged>");
+        }
+        let value = match value {
+            Value::String(value) => Value::String(self.scrub_str(value)),
+            Value::Array(Array::String(values)) => Value::Array(Array::String(
+                values
+                    .into_iter()
+                    .map(|value| self.scrub_str(value))
+                    .collect(),
+            )),
+            value => value,
+        };
+        KeyValue::new(key, value)
+    }
+
+    /// Scrubs the value of a log attribute, or the body of a log record, including the values of
+    /// nested maps
+    pub fn scrub_any(&self, key: &Key, value: AnyValue) -> AnyValue {
+        if self.is_redacted(key.as_str()) {
+            return AnyValue::from("<expunged>");
+        }
+        self.scrub_any_value(value)
+    }
+
+    fn scrub_any_value(&self, value: AnyValue) -> AnyValue {
+        match value {
+            AnyValue::String(value) => AnyValue::String(self.scrub_str(value)),
+            AnyValue::ListAny(values) => AnyValue::ListAny(Box::new(
+                values
+                    .into_iter()
+                    .map(|value| self.scrub_any_value(value))
+                    .collect(),
+            )),
+            AnyValue::Map(map) => AnyValue::Map(Box::new(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = self.scrub_any(&key, value);
+                        (key, value)
+                    })
+                    .collect::<HashMap<_, _>>(),
+            )),
+            value => value,
+        }
+    }
+
+    fn scrub_str(&self, value: StringValue) -> StringValue {
+        match &self.scrubber {
+            Some(scrubber) => scrubber.scrub(value.as_str().to_string()).into(),
+            None => value,
+        }
+    }
+}
+
+impl Default for AttributeScrubber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scrubs the name, attributes, events (names & attributes), links & status of each span before
+/// it's passed to the inner processor. See the [module docs](self).
+#[derive(Debug)]
+pub struct ScrubSpanProcessor<P> {
+    inner: P,
+    scrubber: AttributeScrubber,
+}
+
+impl<P> ScrubSpanProcessor<P> {
+    /// Scrubs spans with the built-in patterns of [AttributeScrubber]
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            scrubber: AttributeScrubber::new(),
+        }
+    }
+
+    /// Use a different scrubber, e.g. with more keys
+    pub fn scrubber(mut self, scrubber: AttributeScrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    fn scrub_all(&self, attributes: Vec<KeyValue>) -> Vec<KeyValue> {
+        attributes
+            .into_iter()
+            .map(|attribute| self.scrubber.scrub(attribute))
+            .collect()
+    }
+}
+
+impl<P> SpanProcessor for ScrubSpanProcessor<P>
+where
+    P: SpanProcessor,
+{
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.inner.on_start(span, cx)
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        if let Some(scrubber) = &self.scrubber.scrubber {
+            span.name = scrubber.scrub(span.name.into_owned()).into();
+            if let Status::Error { description } = span.status {
+                span.status = Status::error(scrubber.scrub(description.into_owned()));
+            }
+        }
+        span.attributes = self.scrub_all(span.attributes);
+        for event in &mut span.events.events {
+            // exceptions are recorded as events, whose names can include their messages
+            if let Some(scrubber) = &self.scrubber.scrubber {
+                event.name = scrubber
+                    .scrub(std::mem::take(&mut event.name).into_owned())
+                    .into();
+            }
+            event.attributes = self.scrub_all(std::mem::take(&mut event.attributes));
+        }
+        for link in &mut span.links.links {
+            link.attributes = self.scrub_all(std::mem::take(&mut link.attributes));
+        }
+        self.inner.on_end(span)
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource)
+    }
+}
+
+/// Scrubs the body & attributes of each log record before it's passed to the inner processor. See
+/// the [module docs](self).
+#[derive(Debug)]
+pub struct ScrubLogProcessor<P> {
+    inner: P,
+    scrubber: AttributeScrubber,
+    // the attributes of a record can't be replaced, so a scrubbed copy is built from a blank record
+    blank: SdkLogger,
+}
+
+impl<P> ScrubLogProcessor<P> {
+    /// Scrubs log records with the built-in patterns of [AttributeScrubber]
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            scrubber: AttributeScrubber::new(),
+            blank: SdkLoggerProvider::builder().build().logger("expunge"),
+        }
+    }
+
+    /// Use a different scrubber, e.g. with more keys
+    pub fn scrubber(mut self, scrubber: AttributeScrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    fn scrubbed(&self, record: &SdkLogRecord) -> SdkLogRecord {
+        let mut scrubbed = self.blank.create_log_record();
+        if let Some(name) = record.event_name() {
+            scrubbed.set_event_name(name);
+        }
+        if let Some(target) = record.target() {
+            scrubbed.set_target(target.clone());
+        }
+        if let Some(timestamp) = record.timestamp() {
+            scrubbed.set_timestamp(timestamp);
+        }
+        if let Some(timestamp) = record.observed_timestamp() {
+            scrubbed.set_observed_timestamp(timestamp);
+        }
+        if let Some(trace_context) = record.trace_context() {
+            scrubbed.set_trace_context(
+                trace_context.trace_id,
+                trace_context.span_id,
+                trace_context.trace_flags,
+            );
+        }
+        if let Some(severity_text) = record.severity_text() {
+            scrubbed.set_severity_text(severity_text);
+        }
+        if let Some(severity_number) = record.severity_number() {
+            scrubbed.set_severity_number(severity_number);
+        }
+        if let Some(body) = record.body() {
+            scrubbed.set_body(self.scrubber.scrub_any_value(body.clone()));
+        }
+        for (key, value) in record.attributes_iter() {
+            scrubbed.add_attribute(key.clone(), self.scrubber.scrub_any(key, value.clone()));
+        }
+        scrubbed
+    }
+}
+
+impl<P> LogProcessor for ScrubLogProcessor<P>
+where
+    P: LogProcessor,
+{
+    fn emit(&self, data: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        *data = self.scrubbed(data);
+        self.inner.emit(data, instrumentation)
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn event_enabled(
+        &self,
+        level: opentelemetry::logs::Severity,
+        target: &str,
+        name: Option<&str>,
+    ) -> bool {
+        self.inner.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource)
+    }
+}
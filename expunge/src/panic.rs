@@ -0,0 +1,111 @@
+//! Scrubs panic messages before they're printed, for secrets interpolated into `panic!` or
+//! `expect` messages, including those of third-party code.
+//!
+//! ```rust
+//! expunge::panic::install_scrubbing_hook();
+//! ```
+//!
+//! The payload itself can't be modified by a panic hook, so a payload recovered with
+//! [std::panic::catch_unwind] is left as-is. Hooks installed after this one, e.g. by crash
+//! reporters, can use [scrubbed_message] themselves.
+//!
+//! The hook that was installed before this one, e.g. `std`'s default hook, is replaced, since
+//! [PanicHookInfo] can't be constructed outside of `std` and the hook would receive the unscrubbed
+//! message. Other hooks can be chained with [PreviousHook::Chain] instead, and are called with the
+//! scrubbed message.
+
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    io::Write,
+    panic::{Location, PanicHookInfo},
+};
+
+use crate::utils::Scrubber;
+
+/// A panic, with its message scrubbed, which is passed to hooks chained with [PreviousHook::Chain]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrubbedPanic<'a> {
+    pub message: &'a str,
+    pub location: Option<&'a Location<'a>>,
+}
+
+type ChainedHook = dyn Fn(&ScrubbedPanic<'_>) + Send + Sync;
+
+/// What to do with the panic hook that was installed before the scrubbing hook
+pub enum PreviousHook {
+    /// Drop it, e.g. `std`'s default hook, which would print the message again unscrubbed
+    Replace,
+    /// Call a hook after the scrubbed output has been printed, e.g. a crash reporter's
+    Chain(Box<ChainedHook>),
+}
+
+/// Installs a panic hook that prints the same output as the default hook, but with the message
+/// scrubbed by the built-in patterns of [Scrubber]. The previous hook is replaced.
+pub fn install_scrubbing_hook() {
+    install_scrubbing_hook_with(Scrubber::new(), PreviousHook::Replace);
+}
+
+/// Installs a panic hook that prints the same output as the default hook, but with the message
+/// scrubbed by a custom scrubber
+///
+/// ```rust
+/// use expunge::{panic::PreviousHook, utils::Scrubber};
+///
+/// expunge::panic::install_scrubbing_hook_with(
+///     Scrubber::new(),
+///     PreviousHook::Chain(Box::new(|panic| {
+///         // e.g. report the scrubbed message to a crash reporter
+///         let _ = panic.message;
+///     })),
+/// );
+/// ```
+pub fn install_scrubbing_hook_with(scrubber: Scrubber, previous: PreviousHook) {
+    let chained = match previous {
+        PreviousHook::Replace => None,
+        PreviousHook::Chain(hook) => Some(hook),
+    };
+
+    std::panic::set_hook(Box::new(move |info| {
+        let message = scrubbed_message(info, &scrubber);
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        let location = info
+            .location()
+            .map(|location| format!(" at {location}"))
+            .unwrap_or_default();
+        let backtrace = Backtrace::capture();
+
+        // write it at once, so that it isn't interleaved with other output
+        let mut output = format!("thread '{name}' panicked{location}:\n{message}\n");
+        match backtrace.status() {
+            BacktraceStatus::Captured => {
+                output.push_str(&format!("stack backtrace:\n{backtrace}\n"))
+            }
+            _ => output.push_str(
+                "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n",
+            ),
+        }
+        let _ = std::io::stderr().lock().write_all(output.as_bytes());
+
+        if let Some(chained) = &chained {
+            chained(&ScrubbedPanic {
+                message: &message,
+                location: info.location(),
+            });
+        }
+    }));
+}
+
+/// The message of the panic, scrubbed. Payloads that aren't strings are described as `Box<dyn Any>`,
+/// the same as the default hook.
+pub fn scrubbed_message(info: &PanicHookInfo<'_>, scrubber: &Scrubber) -> String {
+    let payload = info.payload();
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        return "Box<dyn Any>".to_string();
+    };
+    scrubber.scrub(message)
+}
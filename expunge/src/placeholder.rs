@@ -0,0 +1,122 @@
+//! Placeholders that are stored into fields without allocating, for `#[expunge(as_str = ...)]`.
+//!
+//! `as = "<redacted>".into()` builds a new value each time a field is expunged, which allocates
+//! for most string types. `as_str` takes a `&'static str`, which can be any constant expression,
+//! and converts it with [FromPlaceholder] instead:
+//!
+//! - `&'static str`, `Cow<'static, str>` & the string types of the `smol_str`, `compact_str` &
+//!   `bytestring` features borrow the placeholder
+//! - `Arc<str>` clones a copy that's allocated the first time the placeholder is used
+//! - `String` & `Box<str>` still allocate
+//!
+//! ```rust
+//! use expunge::Expunge;
+//! use std::{borrow::Cow, sync::Arc};
+//!
+//! const REDACTED: &str = "<redacted>";
+//!
+//! #[derive(Expunge)]
+//! struct Request {
+//!     #[expunge(as_str = "<user>")]
+//!     user: Cow<'static, str>,
+//!     #[expunge(as_str = REDACTED)]
+//!     token: Arc<str>,
+//! }
+//!
+//! let request = Request {
+//!     user: Cow::Owned("jane".to_string()),
+//!     token: Arc::from("secret"),
+//! }
+//! .expunge();
+//!
+//! assert!(matches!(request.user, Cow::Borrowed("<user>")));
+//! assert_eq!("<redacted>", &*request.token);
+//! ```
+
+use std::{
+    borrow::Cow,
+    sync::{Arc, OnceLock},
+};
+
+/// A placeholder that's shared by every value expunged at an `as_str`, so that types which own
+/// their data are only allocated once
+#[derive(Debug)]
+pub struct StaticPlaceholder {
+    text: &'static str,
+    arc: OnceLock<Arc<str>>,
+}
+
+impl StaticPlaceholder {
+    pub const fn new(text: &'static str) -> Self {
+        Self {
+            text,
+            arc: OnceLock::new(),
+        }
+    }
+
+    pub const fn as_str(&self) -> &'static str {
+        self.text
+    }
+
+    /// The placeholder as an `Arc<str>`, which is allocated on first use
+    pub fn as_arc(&self) -> &Arc<str> {
+        self.arc.get_or_init(|| Arc::from(self.text))
+    }
+}
+
+/// Converts a [StaticPlaceholder] into the type of a field. Implement this for custom string types
+/// to use them with `#[expunge(as_str = ...)]`.
+pub trait FromPlaceholder {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self;
+}
+
+impl FromPlaceholder for &'static str {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        placeholder.as_str()
+    }
+}
+
+impl FromPlaceholder for Cow<'static, str> {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        Cow::Borrowed(placeholder.as_str())
+    }
+}
+
+impl FromPlaceholder for Arc<str> {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        Arc::clone(placeholder.as_arc())
+    }
+}
+
+impl FromPlaceholder for String {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        placeholder.as_str().to_string()
+    }
+}
+
+impl FromPlaceholder for Box<str> {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        placeholder.as_str().into()
+    }
+}
+
+#[cfg(feature = "smol_str")]
+impl FromPlaceholder for ::smol_str::SmolStr {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        Self::new_static(placeholder.as_str())
+    }
+}
+
+#[cfg(feature = "compact_str")]
+impl FromPlaceholder for ::compact_str::CompactString {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        Self::const_new(placeholder.as_str())
+    }
+}
+
+#[cfg(feature = "bytestring")]
+impl FromPlaceholder for ::bytestring::ByteString {
+    fn from_placeholder(placeholder: &'static StaticPlaceholder) -> Self {
+        Self::from_static(placeholder.as_str())
+    }
+}
@@ -0,0 +1,489 @@
+//! Redaction rules that are loaded at runtime, e.g. from a config file, so that what's redacted
+//! can be changed without redeploying.
+//!
+//! `#[derive(Expunge)]` also implements [ExpungeWith] for every [Policy]. Fields with a rule in
+//! the policy are transformed by the rule, and other fields by their attributes as usual. Rules
+//! are keyed by `Type.field`, or `Type.Variant.field` for enums, with tuple fields numbered from 0.
+//! The policy is also passed on to nested types that derive `Expunge`, including in an `Option`,
+//! `Box` or `Vec`.
+//!
+//! With the `serde` feature, [Rules] can be deserialized from any format, e.g. TOML:
+//!
+//! ```toml
+//! User.email = "hash"
+//! User.ssn = "mask(keep_last = 4)"
+//! User.id = "skip"
+//! Event.Login.ip = "expunge"
+//! ```
+//!
+//! ```rust
+//! use expunge::{policy::{ExpungeWith, Rule, Rules}, Expunge};
+//!
+//! #[derive(Expunge)]
+//! struct User {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     ssn: String,
+//!     name: String,
+//! }
+//!
+//! let rules = Rules::new().rule("User.ssn", "mask(keep_last = 4)".parse()?);
+//!
+//! let user = User {
+//!     id: 101,
+//!     ssn: "078-05-1120".to_string(),
+//!     name: "Jane".to_string(),
+//! }
+//! .expunge_with(&rules);
+//!
+//! assert_eq!("*******1120", user.ssn);
+//! assert_eq!("", user.name, "fields without a rule are expunged by their attributes");
+//! # Ok::<_, expunge::policy::RuleError>(())
+//! ```
+//!
+//! `hash` & `mask` only apply to `String` & `Cow<str>` fields (and `Option`s of them). Other fields
+//! are expunged as with the `expunge` rule instead.
+
+use std::{cell::Cell, collections::HashMap, fmt, str::FromStr};
+
+use crate::Expunge;
+
+/// A source of rules for [ExpungeWith]
+pub trait Policy {
+    /// The rule for a field, e.g. `User.email`, if there is one
+    fn rule(&self, path: &str) -> Option<&Rule>;
+}
+
+/// A policy without any rules, so that every field is expunged by its attributes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoPolicy;
+
+impl Policy for NoPolicy {
+    #[inline(always)]
+    fn rule(&self, _: &str) -> Option<&Rule> {
+        None
+    }
+}
+
+impl<P> Policy for &P
+where
+    P: Policy + ?Sized,
+{
+    fn rule(&self, path: &str) -> Option<&Rule> {
+        (**self).rule(path)
+    }
+}
+
+/// Expunges the value, overriding the attributes of fields with the rules of a [Policy]. It's
+/// implemented by `#[derive(Expunge)]`.
+pub trait ExpungeWith<P>
+where
+    P: Policy + ?Sized,
+{
+    fn expunge_with(self, policy: &P) -> Self
+    where
+        Self: Sized;
+}
+
+/// What to do with a field
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Rule {
+    /// Leave the field as-is
+    Skip,
+    /// Expunge the field, ignoring its attributes
+    Expunge,
+    /// Replace the field with a digest of it, as with `#[expunge(hash)]`
+    #[cfg(feature = "hash")]
+    Hash,
+    /// Mask the field, as with `#[expunge(keep_first = .., keep_last = ..)]`
+    Mask { keep_first: usize, keep_last: usize },
+}
+
+/// The rule couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError(String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid rule `{}`, expected `skip`, `expunge`, `hash` or `mask(keep_first = N, keep_last = N)`",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || RuleError(s.to_string());
+        let (name, args) = match s.trim().split_once('(') {
+            Some((name, args)) => (
+                name.trim(),
+                Some(args.trim_end().strip_suffix(')').ok_or_else(err)?),
+            ),
+            None => (s.trim(), None),
+        };
+        match (name, args) {
+            ("skip", None) => Ok(Rule::Skip),
+            ("expunge", None) => Ok(Rule::Expunge),
+            #[cfg(feature = "hash")]
+            ("hash", None) => Ok(Rule::Hash),
+            ("mask", args) => {
+                let (mut keep_first, mut keep_last) = (0, 0);
+                for arg in args
+                    .unwrap_or_default()
+                    .split(',')
+                    .filter(|a| !a.trim().is_empty())
+                {
+                    let (key, value) = arg.split_once('=').ok_or_else(err)?;
+                    let value = value.trim().parse().map_err(|_| err())?;
+                    match key.trim() {
+                        "keep_first" => keep_first = value,
+                        "keep_last" => keep_last = value,
+                        _ => return Err(err()),
+                    }
+                }
+                Ok(Rule::Mask {
+                    keep_first,
+                    keep_last,
+                })
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+/// Rules keyed by the path of the field, e.g. `User.email`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rules {
+    rules: HashMap<String, Rule>,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule, replacing any previous rule for the same field
+    pub fn rule(mut self, path: impl Into<String>, rule: Rule) -> Self {
+        self.rules.insert(path.into(), rule);
+        self
+    }
+}
+
+impl Policy for Rules {
+    fn rule(&self, path: &str) -> Option<&Rule> {
+        self.rules.get(path)
+    }
+}
+
+impl<S> FromIterator<(S, Rule)> for Rules
+where
+    S: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (S, Rule)>>(iter: I) -> Self {
+        Self {
+            rules: iter.into_iter().map(|(k, v)| (k.into(), v)).collect(),
+        }
+    }
+}
+
+/// Leaves every field that isn't in the group as-is, for the `expunge_group` method generated for
+/// types with `#[expunge(group = "...")]` fields
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct Group<'a> {
+    group: &'a str,
+    type_name: &'a str,
+    // the groups of each field, keyed by its path
+    fields: &'a [(&'a str, &'a [&'a str])],
+}
+
+impl<'a> Group<'a> {
+    pub fn new(group: &'a str, type_name: &'a str, fields: &'a [(&'a str, &'a [&'a str])]) -> Self {
+        Self {
+            group,
+            type_name,
+            fields,
+        }
+    }
+}
+
+impl Policy for Group<'_> {
+    fn rule(&self, path: &str) -> Option<&Rule> {
+        static SKIP: Rule = Rule::Skip;
+        // the fields of nested types are expunged by their attributes
+        let own = path
+            .strip_prefix(self.type_name)
+            .is_some_and(|field| field.starts_with('.'));
+        let in_group = self
+            .fields
+            .iter()
+            .any(|(field, groups)| *field == path && groups.contains(&self.group));
+        (own && !in_group).then_some(&SKIP)
+    }
+}
+
+/// Nested tables are flattened into dotted paths, so `User.email = "hash"` in TOML and
+/// `User: { email: hash }` in YAML are the same.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Rules {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, MapAccess, Visitor};
+
+        struct Table<'a> {
+            prefix: &'a str,
+            rules: &'a mut HashMap<String, Rule>,
+        }
+
+        impl<'de> serde::de::DeserializeSeed<'de> for Table<'_> {
+            type Value = ();
+
+            fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                deserializer.deserialize_any(self)
+            }
+        }
+
+        impl<'de> Visitor<'de> for Table<'_> {
+            type Value = ();
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a rule or a table of rules")
+            }
+
+            fn visit_str<E>(self, rule: &str) -> Result<(), E>
+            where
+                E: Error,
+            {
+                if self.prefix.is_empty() {
+                    return Err(E::custom("expected a table of rules"));
+                }
+                let rule = rule.parse().map_err(E::custom)?;
+                self.rules.insert(self.prefix.to_string(), rule);
+                Ok(())
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<(), A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                while let Some(key) = map.next_key::<String>()? {
+                    let prefix = if self.prefix.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{key}", self.prefix)
+                    };
+                    map.next_value_seed(Table {
+                        prefix: &prefix,
+                        rules: self.rules,
+                    })?;
+                }
+                Ok(())
+            }
+        }
+
+        let mut rules = HashMap::new();
+        deserializer.deserialize_map(Table {
+            prefix: "",
+            rules: &mut rules,
+        })?;
+        Ok(Rules { rules })
+    }
+}
+
+/// Text that `hash` & `mask` rules can be applied to
+#[doc(hidden)]
+pub trait PolicyText: Expunge {
+    fn apply(self, rule: &Rule) -> Self;
+}
+
+impl PolicyText for String {
+    fn apply(self, rule: &Rule) -> Self {
+        match *rule {
+            Rule::Skip => self,
+            Rule::Expunge => self.expunge(),
+            #[cfg(feature = "hash")]
+            Rule::Hash => crate::utils::hash(self, None),
+            Rule::Mask {
+                keep_first,
+                keep_last,
+            } => crate::utils::mask(self, keep_first, keep_last),
+        }
+    }
+}
+
+impl PolicyText for std::borrow::Cow<'_, str> {
+    fn apply(self, rule: &Rule) -> Self {
+        match rule {
+            Rule::Skip => self,
+            _ => std::borrow::Cow::Owned(self.into_owned().apply(rule)),
+        }
+    }
+}
+
+impl<T> PolicyText for Option<T>
+where
+    T: PolicyText,
+{
+    fn apply(self, rule: &Rule) -> Self {
+        self.map(|value| value.apply(rule))
+    }
+}
+
+/// Wraps a field so that a rule can be applied to it according to its type.
+///
+/// [ViaText], [ViaExpunge] & [ViaAny] must be in scope, and the method must be called on
+/// `&&&PolicyField::new(..)`, so that the most specific implementation takes precedence.
+#[doc(hidden)]
+pub struct PolicyField<T>(Cell<Option<T>>);
+
+impl<T> PolicyField<T> {
+    pub fn new(value: T) -> Self {
+        Self(Cell::new(Some(value)))
+    }
+
+    fn take(&self) -> T {
+        self.0.take().expect("a rule is only applied once")
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaText {
+    type Value;
+
+    fn apply(&self, rule: &Rule) -> Self::Value;
+}
+
+impl<T> ViaText for &&PolicyField<T>
+where
+    T: PolicyText,
+{
+    type Value = T;
+
+    fn apply(&self, rule: &Rule) -> T {
+        self.take().apply(rule)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaExpunge {
+    type Value;
+
+    fn apply(&self, rule: &Rule) -> Self::Value;
+}
+
+impl<T> ViaExpunge for &PolicyField<T>
+where
+    T: Expunge,
+{
+    type Value = T;
+
+    fn apply(&self, rule: &Rule) -> T {
+        match rule {
+            Rule::Skip => self.take(),
+            _ => self.take().expunge(),
+        }
+    }
+}
+
+/// Values that can't be expunged, which are always skipped by their attributes
+#[doc(hidden)]
+pub trait ViaAny {
+    type Value;
+
+    fn apply(&self, rule: &Rule) -> Self::Value;
+}
+
+impl<T> ViaAny for PolicyField<T> {
+    type Value = T;
+
+    fn apply(&self, _: &Rule) -> T {
+        self.take()
+    }
+}
+
+/// Passes the policy on to fields of types that implement [ExpungeWith], e.g. other derived types,
+/// so that their rules apply too. Other fields are expunged by their attributes.
+///
+/// [ViaPolicy] & [ViaAttributes] must be in scope, and the method must be called on
+/// `&&PolicyField::new(..)`.
+#[doc(hidden)]
+pub trait ViaPolicy<P: ?Sized> {
+    type Value;
+
+    fn expunge_nested(&self, policy: &P) -> Self::Value;
+}
+
+impl<T, P> ViaPolicy<P> for &PolicyField<T>
+where
+    T: ExpungeWith<P>,
+    P: Policy + ?Sized,
+{
+    type Value = T;
+
+    fn expunge_nested(&self, policy: &P) -> T {
+        self.take().expunge_with(policy)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaAttributes<P: ?Sized> {
+    type Value;
+
+    fn expunge_nested(&self, policy: &P) -> Self::Value;
+}
+
+impl<T, P> ViaAttributes<P> for PolicyField<T>
+where
+    T: Expunge,
+    P: ?Sized,
+{
+    type Value = T;
+
+    fn expunge_nested(&self, _: &P) -> T {
+        self.take().expunge()
+    }
+}
+
+impl<T, P> ExpungeWith<P> for Option<T>
+where
+    T: ExpungeWith<P>,
+    P: Policy + ?Sized,
+{
+    fn expunge_with(self, policy: &P) -> Self {
+        self.map(|value| value.expunge_with(policy))
+    }
+}
+
+impl<T, P> ExpungeWith<P> for Box<T>
+where
+    T: ExpungeWith<P>,
+    P: Policy + ?Sized,
+{
+    fn expunge_with(self, policy: &P) -> Self {
+        Box::new((*self).expunge_with(policy))
+    }
+}
+
+impl<T, P> ExpungeWith<P> for Vec<T>
+where
+    T: ExpungeWith<P>,
+    P: Policy + ?Sized,
+{
+    fn expunge_with(self, policy: &P) -> Self {
+        self.into_iter()
+            .map(|value| value.expunge_with(policy))
+            .collect()
+    }
+}
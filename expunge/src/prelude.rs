@@ -0,0 +1,25 @@
+//! Re-exports the commonly used traits & types, so that they can be imported at once
+//!
+//! ```rust
+//! use expunge::prelude::*;
+//!
+//! #[derive(Expunge)]
+//! struct User {
+//!     name: String,
+//! }
+//!
+//! let user: Expunged<User> = User { name: "Jane".to_string() }.into();
+//! assert_eq!("", user.name);
+//! ```
+
+pub use crate::{
+    utils::{ExpungeKeys, Oversized, TruncateDate},
+    Classification, DefaultExpunged, Erase, Expunge, ExpungeAsync, ExpungeIterator, ExpungeMut,
+    Expunged, MaybeExpunged, RedactedError, SharedExpunged, Summarize, Summary, TryExpunge,
+};
+
+#[cfg(feature = "json")]
+pub use crate::reject::{RejectRedacted, Unredacted};
+
+#[cfg(feature = "manifest-export")]
+pub use crate::manifest::Manifest;
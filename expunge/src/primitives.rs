@@ -32,3 +32,52 @@ expunge_as_default!(bool);
 expunge_as_default!(());
 expunge_as_default!(String);
 expunge_as_default!(&str);
+
+#[cfg(feature = "smol_str")]
+expunge_as_default!(::smol_str::SmolStr);
+#[cfg(feature = "compact_str")]
+expunge_as_default!(::compact_str::CompactString);
+#[cfg(feature = "bytestring")]
+expunge_as_default!(::bytestring::ByteString);
+#[cfg(feature = "uuid")]
+expunge_as_default!(::uuid::Uuid);
+#[cfg(feature = "rust_decimal")]
+expunge_as_default!(::rust_decimal::Decimal);
+#[cfg(feature = "bigdecimal")]
+expunge_as_default!(::bigdecimal::BigDecimal);
+#[cfg(feature = "num-bigint")]
+expunge_as_default!(::num_bigint::BigInt);
+#[cfg(feature = "num-bigint")]
+expunge_as_default!(::num_bigint::BigUint);
+
+#[doc(hidden)]
+macro_rules! expunge_tuple {
+    ($($name:ident),+) => {
+        impl<$($name),+> Expunge for ($($name,)+)
+        where
+            $($name: Expunge,)+
+        {
+            #[allow(non_snake_case)]
+            fn expunge(self) -> Self
+            where
+                Self: Sized,
+            {
+                let ($($name,)+) = self;
+                ($($name.expunge(),)+)
+            }
+        }
+    };
+}
+
+expunge_tuple!(A);
+expunge_tuple!(A, B);
+expunge_tuple!(A, B, C);
+expunge_tuple!(A, B, C, D);
+expunge_tuple!(A, B, C, D, E);
+expunge_tuple!(A, B, C, D, E, F);
+expunge_tuple!(A, B, C, D, E, F, G);
+expunge_tuple!(A, B, C, D, E, F, G, H);
+expunge_tuple!(A, B, C, D, E, F, G, H, I);
+expunge_tuple!(A, B, C, D, E, F, G, H, I, J);
+expunge_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+expunge_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
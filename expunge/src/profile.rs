@@ -0,0 +1,52 @@
+//! The redaction profile that's selected at runtime, e.g. `prod`, `staging` or `debug`.
+//!
+//! Fields can specify how they're expunged under each profile, which takes precedence over their
+//! other attributes while that profile is selected:
+//!
+//! ```rust
+//! use expunge::Expunge;
+//!
+//! #[derive(Expunge)]
+//! struct User {
+//!     #[expunge(profile(prod = expunge, staging = mask(keep_last = 4), debug = skip))]
+//!     email: String,
+//! }
+//!
+//! let user = || User {
+//!     email: "jane@example.com".to_string(),
+//! };
+//!
+//! expunge::profile::set("staging");
+//! assert_eq!("************.com", user().expunge().email);
+//!
+//! expunge::profile::set("debug");
+//! assert_eq!("jane@example.com", user().expunge().email);
+//!
+//! expunge::profile::clear();
+//! assert_eq!("", user().expunge().email);
+//! ```
+//!
+//! The profile is global, so it's typically selected once at startup, e.g. from an environment
+//! variable. Rules of a [Policy](crate::policy::Policy) take precedence over profiles.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+static PROFILE: RwLock<Option<Arc<str>>> = RwLock::new(None);
+
+/// Selects the profile
+pub fn set(name: impl AsRef<str>) {
+    *PROFILE.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::from(name.as_ref()));
+}
+
+/// Deselects the profile, so that fields are expunged by their other attributes
+pub fn clear() {
+    *PROFILE.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// The selected profile, if there is one
+pub fn current() -> Option<Arc<str>> {
+    PROFILE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
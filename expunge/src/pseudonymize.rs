@@ -0,0 +1,157 @@
+//! Consistent fake values, so that expunged datasets remain useful for analytics & for debugging
+//! flows that span multiple events.
+//!
+//! A [Pseudonymizer] replaces equal inputs with equal fake outputs for as long as it lives, e.g.
+//! `Jane Doe` becomes `Alex Smith` in every record, while `John Doe` becomes another name. It's
+//! typically a `static`, so that it can be used with `with`:
+//!
+//! ```rust
+//! use expunge::{pseudonymize::Pseudonymizer, Expunge};
+//!
+//! static PSEUDO: Pseudonymizer = Pseudonymizer::new();
+//!
+//! #[derive(Expunge)]
+//! struct Event {
+//!     #[expunge(with = PSEUDO.name())]
+//!     name: String,
+//!     #[expunge(with = PSEUDO.email())]
+//!     email: String,
+//!     #[expunge(with = PSEUDO.id("session"))]
+//!     session: String,
+//! }
+//!
+//! let event = || Event {
+//!     name: "Jane Doe".to_string(),
+//!     email: "jane@example.org".to_string(),
+//!     session: "4f1c2a".to_string(),
+//! };
+//!
+//! let first = event().expunge();
+//! let second = event().expunge();
+//! assert_eq!("Alex Smith", first.name);
+//! assert_eq!("alex.smith@example.com", first.email);
+//! assert_eq!("session_1", first.session);
+//! assert_eq!(first.name, second.name);
+//! ```
+//!
+//! Pseudonyms are assigned in the order that inputs are first seen, so they only identify an
+//! input within a session. The mapping is held in memory, and [Pseudonymizer::clear] starts a new
+//! session.
+//!
+//! Inputs aren't kept. The mapping is keyed by a digest of each input, with a key that's random
+//! for each process, so it can't be reversed by hashing guesses. Each kind of pseudonym maps at
+//! most [DEFAULT_MAX_INPUTS] inputs by default, after which its session starts over, so that
+//! long-lived pseudonymizers don't grow without bound.
+
+use std::{
+    collections::{hash_map::RandomState, BTreeMap},
+    hash::BuildHasher,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// How many inputs of each kind a [Pseudonymizer] maps before starting over, unless it's created
+/// with [Pseudonymizer::with_max_inputs]
+pub const DEFAULT_MAX_INPUTS: usize = 100_000;
+
+pub(crate) const FIRST_NAMES: &[&str] = &[
+    "Alex", "Sam", "Robin", "Jordan", "Casey", "Taylor", "Morgan", "Riley", "Jamie", "Avery",
+    "Quinn", "Charlie", "Drew", "Emerson", "Finley", "Harper",
+];
+
+pub(crate) const LAST_NAMES: &[&str] = &[
+    "Smith", "Jones", "Brown", "Taylor", "Wilson", "Davies", "Evans", "Thomas", "Johnson",
+    "Roberts", "Walker", "Wright", "Green", "Hall", "Wood", "Clarke",
+];
+
+/// Maps equal inputs to equal fake outputs within a session
+#[derive(Debug)]
+pub struct Pseudonymizer {
+    // the index of the digest of each input, keyed by the kind of pseudonym
+    indexes: Mutex<BTreeMap<String, BTreeMap<u64, usize>>>,
+    // keys the digests, created on first use
+    key: OnceLock<RandomState>,
+    max_inputs: usize,
+}
+
+impl Pseudonymizer {
+    pub const fn new() -> Self {
+        Self::with_max_inputs(DEFAULT_MAX_INPUTS)
+    }
+
+    /// A pseudonymizer that starts a new session for a kind of pseudonym once it has mapped
+    /// `max_inputs` inputs of that kind
+    pub const fn with_max_inputs(max_inputs: usize) -> Self {
+        Self {
+            indexes: Mutex::new(BTreeMap::new()),
+            key: OnceLock::new(),
+            max_inputs,
+        }
+    }
+
+    /// The index of the input among the inputs of the same kind, assigning the next index to new
+    /// inputs
+    fn index(&self, kind: &str, input: String) -> usize {
+        let digest = self.key.get_or_init(RandomState::new).hash_one(input);
+        let mut indexes = self.indexes.lock().unwrap_or_else(PoisonError::into_inner);
+        let inputs = indexes.entry(kind.to_string()).or_default();
+        if inputs.len() >= self.max_inputs && !inputs.contains_key(&digest) {
+            inputs.clear();
+        }
+        let next = inputs.len();
+        *inputs.entry(digest).or_insert(next)
+    }
+
+    /// Replaces names with fake names, e.g. `Alex Smith`
+    pub fn name(&self) -> impl Fn(String) -> String + '_ {
+        move |name| {
+            let (first, last, n) = fake_name(self.index("name", name));
+            match n {
+                0 => format!("{first} {last}"),
+                n => format!("{first} {last} {}", n + 1),
+            }
+        }
+    }
+
+    /// Replaces email addresses with fake addresses, e.g. `alex.smith@example.com`
+    pub fn email(&self) -> impl Fn(String) -> String + '_ {
+        move |email| {
+            let (first, last, n) = fake_name(self.index("email", email));
+            let (first, last) = (first.to_lowercase(), last.to_lowercase());
+            match n {
+                0 => format!("{first}.{last}@example.com"),
+                n => format!("{first}.{last}{}@example.com", n + 1),
+            }
+        }
+    }
+
+    /// Replaces values with the prefix & a sequence number, e.g. `user_1`. Each prefix has its own
+    /// sequence.
+    pub fn id<'a>(&'a self, prefix: &'a str) -> impl Fn(String) -> String + 'a {
+        move |id| format!("{prefix}_{}", self.index(&format!("id:{prefix}"), id) + 1)
+    }
+
+    /// Forgets every input, starting a new session
+    pub fn clear(&self) {
+        self.indexes
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl Default for Pseudonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The first & last name for an index, and how many times every combination has been used before
+fn fake_name(index: usize) -> (&'static str, &'static str, usize) {
+    let combinations = FIRST_NAMES.len() * LAST_NAMES.len();
+    let (n, index) = (index / combinations, index % combinations);
+    (
+        FIRST_NAMES[index % FIRST_NAMES.len()],
+        LAST_NAMES[index / FIRST_NAMES.len()],
+        n,
+    )
+}
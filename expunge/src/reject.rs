@@ -0,0 +1,273 @@
+//! Reject input that contains values which were already expunged.
+//!
+//! Expunged values that are logged or exported can find their way back into requests, e.g. when
+//! sanitized logs are replayed against an API, which would overwrite real data with placeholders.
+//! `#[expunge(reject_redacted_input)]` implements [RejectRedacted] with what the type is expunged
+//! to: the placeholder of the generated `Debug`, every `as` that's a string literal, the shapes of
+//! masked fields, and whether any field is hashed. Deserializing the type through [Unredacted] then
+//! fails if any string in the input is one of them, or is what a nested type that also implements
+//! [RejectRedacted] is expunged to.
+//!
+//! ```rust
+//! use expunge::{reject::Unredacted, Expunge};
+//! use serde::Deserialize;
+//!
+//! #[derive(Expunge, Deserialize)]
+//! #[expunge(reject_redacted_input)]
+//! struct UpdateUser {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     #[expunge(as = "<redacted>".to_string())]
+//!     email: String,
+//! }
+//!
+//! let err = serde_json::from_str::<Unredacted<UpdateUser>>(r#"{"id":7,"email":"<redacted>"}"#)
+//!     .err()
+//!     .unwrap();
+//! assert_eq!(
+//!     "`email` is the redaction placeholder `<redacted>`",
+//!     err.to_string()
+//! );
+//!
+//! let user = serde_json::from_str::<Unredacted<UpdateUser>>(r#"{"id":7,"email":"jane@example.com"}"#)
+//!     .unwrap()
+//!     .into_inner();
+//! assert_eq!("jane@example.com", user.email);
+//! ```
+//!
+//! Placeholders are only rejected if they match exactly. Masked values are rejected if they have
+//! the shape of a masked field of the type, e.g. `************0958` for `keep_last = 4`, and if a
+//! field is hashed, any 64 character hex digest is rejected. Empty strings, which most types are
+//! expunged to by default, can't be told apart from real input and are accepted.
+//!
+//! The input is first deserialized as a [serde_json::Value] to be checked, so [Unredacted] only
+//! works with self-describing formats, e.g. JSON.
+
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer};
+use serde_json::Value;
+use std::{marker::PhantomData, ops::Deref};
+
+/// What expunged values of the type are replaced with. Implemented by
+/// `#[expunge(reject_redacted_input)]`.
+pub trait RejectRedacted {
+    /// The placeholders that fields are replaced with
+    const PLACEHOLDERS: &'static [&'static str];
+    /// The `(keep_first, keep_last)` of each masked field
+    const MASKS: &'static [(usize, usize)] = &[];
+    /// Whether any field is replaced with a hex digest
+    const HASHED: bool = false;
+
+    /// Adds what the type & the types nested in it are expunged to
+    fn collect_redactions(redactions: &mut Redactions) {
+        redactions.add::<Self>();
+    }
+}
+
+impl<T> RejectRedacted for Option<T>
+where
+    T: RejectRedacted,
+{
+    const PLACEHOLDERS: &'static [&'static str] = &[];
+
+    fn collect_redactions(redactions: &mut Redactions) {
+        T::collect_redactions(redactions)
+    }
+}
+
+impl<T> RejectRedacted for Box<T>
+where
+    T: RejectRedacted,
+{
+    const PLACEHOLDERS: &'static [&'static str] = &[];
+
+    fn collect_redactions(redactions: &mut Redactions) {
+        T::collect_redactions(redactions)
+    }
+}
+
+impl<T> RejectRedacted for Vec<T>
+where
+    T: RejectRedacted,
+{
+    const PLACEHOLDERS: &'static [&'static str] = &[];
+
+    fn collect_redactions(redactions: &mut Redactions) {
+        T::collect_redactions(redactions)
+    }
+}
+
+/// What a type & the types nested in it are expunged to, collected by
+/// [RejectRedacted::collect_redactions]
+#[derive(Debug, Clone, Default)]
+pub struct Redactions {
+    placeholders: Vec<&'static str>,
+    masks: Vec<(usize, usize)>,
+    hashed: bool,
+    // the names of the types that have been added, so that recursive types are only added once
+    added: Vec<&'static str>,
+}
+
+impl Redactions {
+    /// What the type & the types nested in it are expunged to
+    pub fn of<T>() -> Self
+    where
+        T: RejectRedacted + ?Sized,
+    {
+        let mut redactions = Self::default();
+        T::collect_redactions(&mut redactions);
+        redactions
+    }
+
+    /// Adds what the type itself is expunged to, returning `false` if it was already added
+    pub fn add<T>(&mut self) -> bool
+    where
+        T: RejectRedacted + ?Sized,
+    {
+        let name = std::any::type_name::<T>();
+        if self.added.contains(&name) {
+            return false;
+        }
+        self.added.push(name);
+        self.placeholders.extend(T::PLACEHOLDERS);
+        self.masks.extend(T::MASKS);
+        self.hashed |= T::HASHED;
+        true
+    }
+
+    /// Why the string is rejected, if it's what a value would be expunged to
+    pub fn rejects(&self, value: &str) -> Option<String> {
+        if let Some(placeholder) = self.placeholders.iter().find(|p| **p == value) {
+            return Some(format!("is the redaction placeholder `{placeholder}`"));
+        }
+        if self
+            .masks
+            .iter()
+            .any(|&(keep_first, keep_last)| is_masked(value, keep_first, keep_last))
+        {
+            return Some(format!("is the masked value `{value}`"));
+        }
+        if self.hashed && value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Some("is a hashed value".to_string());
+        }
+        None
+    }
+}
+
+/// Whether the string is what [mask](crate::utils::mask) produces with the same arguments
+fn is_masked(value: &str, keep_first: usize, keep_last: usize) -> bool {
+    let len = value.chars().count();
+    if len == 0 {
+        return false;
+    }
+    let (start, end) = if len <= keep_first + keep_last {
+        (0, len)
+    } else {
+        (keep_first, len - keep_last)
+    };
+    value
+        .chars()
+        .skip(start)
+        .take(end - start)
+        .all(|c| c == '*')
+}
+
+/// Wraps the type of a field, so that the redactions of nested types are only collected if they
+/// implement [RejectRedacted].
+///
+/// [ViaRejectRedacted] & [ViaAccept] must be in scope, and the method must be called on
+/// `&&Nested::<T>::new()`, so that the most specific implementation takes precedence.
+#[doc(hidden)]
+pub struct Nested<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> Nested<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaRejectRedacted {
+    fn collect_redactions(&self, redactions: &mut Redactions);
+}
+
+impl<T> ViaRejectRedacted for &Nested<T>
+where
+    T: RejectRedacted + ?Sized,
+{
+    fn collect_redactions(&self, redactions: &mut Redactions) {
+        T::collect_redactions(redactions)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaAccept {
+    fn collect_redactions(&self, redactions: &mut Redactions);
+}
+
+impl<T: ?Sized> ViaAccept for Nested<T> {
+    fn collect_redactions(&self, _: &mut Redactions) {}
+}
+
+/// Deserializes `T`, failing if any string in the input is what `T`, or a type nested in it, is
+/// expunged to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unredacted<T>(pub T);
+
+impl<T> Unredacted<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Unredacted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Unredacted<T>
+where
+    T: DeserializeOwned + RejectRedacted,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let redactions = Redactions::of::<T>();
+        if let Some((path, reason)) = find_redacted(String::new(), &value, &redactions) {
+            let path = if path.is_empty() {
+                "the input".to_string()
+            } else {
+                path
+            };
+            return Err(D::Error::custom(crate::Error::Validation { path, reason }));
+        }
+        T::deserialize(value)
+            .map(Unredacted)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// The path of the first string that's rejected, e.g. `address.lines[0]`, and why
+fn find_redacted(path: String, value: &Value, redactions: &Redactions) -> Option<(String, String)> {
+    match value {
+        Value::String(value) => redactions.rejects(value).map(|reason| (path, reason)),
+        Value::Array(values) => values
+            .iter()
+            .enumerate()
+            .find_map(|(i, value)| find_redacted(format!("{path}[{i}]"), value, redactions)),
+        Value::Object(map) => map.iter().find_map(|(key, value)| {
+            let path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            find_redacted(path, value, redactions)
+        }),
+        Value::Null | Value::Bool(_) | Value::Number(_) => None,
+    }
+}
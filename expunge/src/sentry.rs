@@ -0,0 +1,278 @@
+//! Scrubs Sentry events before they're sent, with the same engines as the rest of expunge.
+//!
+//! [EventScrubber] expunges the values of sensitive keys in structured data, e.g. `extra`, and
+//! scrubs free text, e.g. messages & exception values, with a [Scrubber]. Keys are matched by
+//! patterns such as `*token*`, ignoring ASCII case, the same as the `tracing` & `otel`
+//! integrations. It covers:
+//!
+//! - the message, log entry & exception values of events
+//! - the message & data of breadcrumbs
+//! - tags, `extra` & custom contexts
+//! - the request's headers, cookies, data, query string & environment, and the query of its URL
+//! - the user's email, username & IP address, which are removed, and other data
+//! - the variables of stack frames, in the event's, exceptions' & threads' stack traces
+//!
+//! ```rust
+//! use sentry_core::{protocol::Event, ClientOptions};
+//!
+//! let options = ClientOptions {
+//!     before_send: Some(expunge::sentry::before_send()),
+//!     ..Default::default()
+//! };
+//!
+//! let event = expunge::sentry::scrub_event(Event {
+//!     message: Some("signup failed for jane@example.com".to_string()),
+//!     ..Default::default()
+//! });
+//! assert_eq!(Some("signup failed for <email>"), event.message.as_deref());
+//! ```
+
+use std::sync::{Arc, OnceLock};
+
+use sentry_core::{
+    protocol::{Breadcrumb, Context, Event, Map, Request, Stacktrace, User, Value},
+    BeforeCallback,
+};
+
+use crate::{
+    json::Policy,
+    utils::{matches_glob, Scrubber, SENSITIVE_KEYS},
+    Expunge,
+};
+
+/// Scrubs events & breadcrumbs. See the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct EventScrubber {
+    keys: Vec<String>,
+    policy: Policy,
+    scrubber: Scrubber,
+}
+
+impl EventScrubber {
+    /// A scrubber that expunges the values of the built-in key patterns, e.g. `*password*` &
+    /// `*token*`, and scrubs text with the built-in patterns of [Scrubber]
+    pub fn new() -> Self {
+        Self {
+            keys: SENSITIVE_KEYS.iter().map(ToString::to_string).collect(),
+            policy: Policy::new(),
+            scrubber: Scrubber::new(),
+        }
+    }
+
+    /// Also expunge the values of keys that match one of the patterns, ignoring ASCII case. `*`
+    /// matches any number of characters, e.g. `*email*`.
+    pub fn redact_keys<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.keys.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Apply a policy to structured data before it's scrubbed, e.g. to hash some keys
+    pub fn policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Use a different scrubber for text, e.g. with custom patterns
+    pub fn scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    pub fn scrub_event(&self, mut event: Event<'static>) -> Event<'static> {
+        event.message = event.message.map(|message| self.scrubber.scrub(message));
+        if let Some(logentry) = &mut event.logentry {
+            logentry.message = self.scrubber.scrub(std::mem::take(&mut logentry.message));
+            logentry.params = std::mem::take(&mut logentry.params)
+                .into_iter()
+                .map(|param| self.scrub_value(param))
+                .collect();
+        }
+        for exception in &mut event.exception.values {
+            exception.value = exception
+                .value
+                .take()
+                .map(|value| self.scrubber.scrub(value));
+        }
+        event.breadcrumbs.values = std::mem::take(&mut event.breadcrumbs.values)
+            .into_iter()
+            .map(|breadcrumb| self.scrub_breadcrumb(breadcrumb))
+            .collect();
+        event.tags = self.scrub_tags(std::mem::take(&mut event.tags));
+        event.extra = self.scrub_map(std::mem::take(&mut event.extra));
+        for context in event.contexts.values_mut() {
+            if let Context::Other(map) = context {
+                *map = self.scrub_map(std::mem::take(map));
+            }
+        }
+        event.request = event.request.map(|request| self.scrub_request(request));
+        event.user = event.user.map(|user| self.scrub_user(user));
+        let stacktraces = event
+            .exception
+            .values
+            .iter_mut()
+            .flat_map(|exception| [&mut exception.stacktrace, &mut exception.raw_stacktrace])
+            .chain(
+                event
+                    .threads
+                    .values
+                    .iter_mut()
+                    .flat_map(|thread| [&mut thread.stacktrace, &mut thread.raw_stacktrace]),
+            )
+            .chain([&mut event.stacktrace]);
+        for stacktrace in stacktraces.flatten() {
+            self.scrub_stacktrace(stacktrace);
+        }
+        event
+    }
+
+    fn scrub_request(&self, mut request: Request) -> Request {
+        if let Some(url) = &mut request.url {
+            if let Some(query) = url.query().map(|query| self.scrub_pairs(query, '&')) {
+                url.set_query(Some(&query));
+            }
+        }
+        // it's the value of the `Cookie` header, which is expunged as well
+        request.cookies = request.cookies.map(Expunge::expunge);
+        request.data = request.data.map(|data| match serde_json::from_str(&data) {
+            Ok(value) => self.scrub_value(value).to_string(),
+            // e.g. a form
+            Err(_) => self.scrub_pairs(&data, '&'),
+        });
+        request.query_string = request
+            .query_string
+            .map(|query| self.scrub_pairs(&query, '&'));
+        request.headers = self.scrub_tags(std::mem::take(&mut request.headers));
+        request.env = self.scrub_tags(std::mem::take(&mut request.env));
+        request
+    }
+
+    /// The id is kept, since it's needed to tell users apart, but every other field identifies
+    /// the user directly
+    fn scrub_user(&self, mut user: User) -> User {
+        user.email = None;
+        user.username = None;
+        user.ip_address = None;
+        user.other = self.scrub_map(std::mem::take(&mut user.other));
+        user
+    }
+
+    fn scrub_stacktrace(&self, stacktrace: &mut Stacktrace) {
+        for frame in &mut stacktrace.frames {
+            frame.vars = self.scrub_map(std::mem::take(&mut frame.vars));
+        }
+    }
+
+    pub fn scrub_breadcrumb(&self, mut breadcrumb: Breadcrumb) -> Breadcrumb {
+        breadcrumb.message = breadcrumb
+            .message
+            .map(|message| self.scrubber.scrub(message));
+        breadcrumb.data = self.scrub_map(std::mem::take(&mut breadcrumb.data));
+        breadcrumb
+    }
+
+    /// A hook for [ClientOptions::before_send](sentry_core::ClientOptions::before_send)
+    pub fn before_send(self) -> BeforeCallback<Event<'static>> {
+        Arc::new(move |event| Some(self.scrub_event(event)))
+    }
+
+    /// A hook for [ClientOptions::before_breadcrumb](sentry_core::ClientOptions::before_breadcrumb)
+    pub fn before_breadcrumb(self) -> BeforeCallback<Breadcrumb> {
+        Arc::new(move |breadcrumb| Some(self.scrub_breadcrumb(breadcrumb)))
+    }
+
+    fn scrub_map(&self, map: Map<String, Value>) -> Map<String, Value> {
+        // applied to the whole map, so that its keys are matched too
+        match self.scrub_value(Value::Object(map.into_iter().collect())) {
+            Value::Object(map) => map.into_iter().collect(),
+            _ => unreachable!("the policy keeps objects as objects"),
+        }
+    }
+
+    fn scrub_tags(&self, tags: Map<String, String>) -> Map<String, String> {
+        let tags = tags
+            .into_iter()
+            .map(|(key, value)| (key, Value::String(value)))
+            .collect();
+        self.scrub_map(tags)
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => (key, value),
+                value => (key, value.to_string()),
+            })
+            .collect()
+    }
+
+    /// Scrubs `key=value` pairs, e.g. a query string, as if they were the entries of a map. Pairs
+    /// without a `=` are scrubbed as text.
+    fn scrub_pairs(&self, pairs: &str, separator: char) -> String {
+        let pairs: Vec<_> = pairs
+            .split(separator)
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => {
+                    let entry = Map::from([(key.to_string(), Value::from(value))]);
+                    match self.scrub_map(entry).remove(key) {
+                        Some(Value::String(value)) => format!("{key}={value}"),
+                        Some(value) => format!("{key}={value}"),
+                        None => unreachable!("the policy keeps the keys of objects"),
+                    }
+                }
+                None => self.scrubber.scrub(pair.to_string()),
+            })
+            .collect();
+        pairs.join(&separator.to_string())
+    }
+
+    /// Applies the policy, then expunges the values of matching keys & scrubs every string that's
+    /// left
+    fn scrub_value(&self, value: Value) -> Value {
+        self.scrub_strings(self.policy.apply(value))
+    }
+
+    fn scrub_strings(&self, value: Value) -> Value {
+        match value {
+            Value::String(value) => Value::String(self.scrubber.scrub(value)),
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.scrub_strings(value))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let value = if self.keys.iter().any(|pattern| matches_glob(pattern, &key)) {
+                            value.expunge()
+                        } else {
+                            self.scrub_strings(value)
+                        };
+                        (key, value)
+                    })
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+}
+
+impl Default for EventScrubber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scrubs the event with the built-in rules of [EventScrubber]
+pub fn scrub_event(event: Event<'static>) -> Event<'static> {
+    // compiling the patterns of the scrubber is much slower than scrubbing an event
+    static SCRUBBER: OnceLock<EventScrubber> = OnceLock::new();
+    SCRUBBER.get_or_init(EventScrubber::new).scrub_event(event)
+}
+
+/// A hook for [ClientOptions::before_send](sentry_core::ClientOptions::before_send) that scrubs
+/// events with the built-in rules of [EventScrubber]
+pub fn before_send() -> BeforeCallback<Event<'static>> {
+    EventScrubber::new().before_send()
+}
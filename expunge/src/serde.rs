@@ -0,0 +1,133 @@
+//! Modules for `#[serde(with = "...")]` that redact individual fields when they're serialized,
+//! without deriving `Expunge` for the whole type or writing a custom `Serialize`.
+//!
+//! - [redacted] serializes the field in its expunged form
+//! - [masked] replaces every character of a string with `*`
+//! - [hashed] serializes the SHA-256 digest of a string, with the `hash` feature
+//! - [skip_serialize] serializes the default value of the field in place of the value
+//!
+//! Each module deserializes the field as-is, so that redacted types can still be read back, e.g.
+//! from a request.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Signup {
+//!     username: String,
+//!     #[serde(with = "expunge::serde::masked")]
+//!     phone: String,
+//!     #[serde(with = "expunge::serde::skip_serialize")]
+//!     password: String,
+//!     #[serde(with = "expunge::serde::redacted")]
+//!     tags: Vec<String>,
+//! }
+//!
+//! let signup = Signup {
+//!     username: "jane".to_string(),
+//!     phone: "07700 900123".to_string(),
+//!     password: "hunter2".to_string(),
+//!     tags: vec!["beta".to_string()],
+//! };
+//! assert_eq!(
+//!     r#"{"username":"jane","phone":"************","password":"","tags":[""]}"#,
+//!     serde_json::to_string(&signup).unwrap()
+//! );
+//! ```
+
+#[doc(hidden)]
+pub use ::serde::*;
+
+/// Serializes the expunged form of a clone of the field
+pub mod redacted {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Expunge;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone + Expunge + Serialize,
+        S: Serializer,
+    {
+        value.clone().expunge().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Serializes a string with every character replaced by `*`, keeping its length. See
+/// [utils::mask](crate::utils::mask) to keep the ends of the string.
+pub mod masked {
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::utils::mask;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<str>,
+        S: Serializer,
+    {
+        serializer.serialize_str(&mask(value.as_ref().to_string(), 0, 0))
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Serializes the hex encoded SHA-256 digest of a string, the same as `#[expunge(hash)]`, so that
+/// equal values can still be correlated
+#[cfg(feature = "hash")]
+pub mod hashed {
+    use ::serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::utils::hash;
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<str>,
+        S: Serializer,
+    {
+        serializer.serialize_str(&hash(value.as_ref().to_string(), None))
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
+
+/// Serializes the default value of the field, e.g. an empty string, so that the field is still
+/// present. Use `#[serde(skip_serializing)]` to leave it out entirely.
+pub mod skip_serialize {
+    use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T, S>(_value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Default + Serialize,
+        S: Serializer,
+    {
+        T::default().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer)
+    }
+}
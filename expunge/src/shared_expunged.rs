@@ -0,0 +1,107 @@
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+use crate::{Expunge, Expunged};
+
+/// A cheaply cloneable, thread-safe handle to a sensitive value that expunges a copy of it the
+/// first time it's needed & caches it, e.g. for config that's logged frequently.
+///
+/// The raw value is still available with [SharedExpunged::raw], while formatting & serializing
+/// always use the expunged copy.
+///
+/// ```rust
+/// use expunge::{Expunge, SharedExpunged};
+///
+/// #[derive(Clone, Expunge)]
+/// struct DatabaseConfig {
+///     #[expunge(skip)]
+///     host: String,
+///     password: String,
+/// }
+///
+/// let config = SharedExpunged::new(DatabaseConfig {
+///     host: "localhost".to_string(),
+///     password: "hunter2".to_string(),
+/// });
+///
+/// for _ in 0..3 {
+///     // only expunged once
+///     assert_eq!("", config.expunged().password);
+/// }
+/// assert_eq!("hunter2", config.raw().password);
+/// ```
+pub struct SharedExpunged<T>(Arc<Shared<T>>);
+
+struct Shared<T> {
+    raw: T,
+    expunged: OnceLock<Expunged<T>>,
+}
+
+impl<T> SharedExpunged<T> {
+    pub fn new(raw: T) -> Self {
+        Self(Arc::new(Shared {
+            raw,
+            expunged: OnceLock::new(),
+        }))
+    }
+
+    /// The original value
+    pub fn raw(&self) -> &T {
+        &self.0.raw
+    }
+
+    /// The expunged copy of the value, which is only computed on the first call
+    pub fn expunged(&self) -> &Expunged<T>
+    where
+        T: Expunge + Clone,
+    {
+        self.0
+            .expunged
+            .get_or_init(|| Expunged::new(self.0.raw.clone()))
+    }
+}
+
+impl<T> Clone for SharedExpunged<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<T> for SharedExpunged<T> {
+    fn from(raw: T) -> Self {
+        Self::new(raw)
+    }
+}
+
+impl<T> fmt::Display for SharedExpunged<T>
+where
+    T: fmt::Display + Expunge + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expunged().fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for SharedExpunged<T>
+where
+    T: fmt::Debug + Expunge + Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.expunged().fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for SharedExpunged<T>
+where
+    T: serde::Serialize + Expunge + Clone,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.expunged().serialize(serializer)
+    }
+}
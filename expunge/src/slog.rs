@@ -0,0 +1,268 @@
+//! Logging with `slog` without cloning the value.
+//!
+//! `#[expunge(slog)]` implements `slog::Value` & [SerializeExpunged], which serializes the value in
+//! its expunged form while borrowing it. Skipped fields are serialized as-is, fields that are
+//! expunged by their type, e.g. `Vec<String>` or other types deriving `slog`, are expunged on the
+//! fly, and only fields with their own transformation, e.g. `with`, are cloned & expunged.
+//!
+//! The serialized form follows the type's `#[serde(...)]` attributes, the same as its `Serialize`
+//! implementation.
+//!
+//! ```rust
+//! use expunge::{slog::ExpungedRef, Expunge};
+//! use serde::Serialize;
+//!
+//! // doesn't need to implement `Clone`
+//! #[derive(Expunge, Serialize)]
+//! #[expunge(slog)]
+//! struct Upload {
+//!     #[expunge(skip)]
+//!     name: String,
+//!     #[serde(rename = "owner")]
+//!     owner_email: String,
+//!     #[expunge(as = "<redacted>".to_string())]
+//!     contents: String,
+//! }
+//!
+//! let upload = Upload {
+//!     name: "report.pdf".to_string(),
+//!     owner_email: "jane@example.com".to_string(),
+//!     contents: "...".to_string(),
+//! };
+//! assert_eq!(
+//!     r#"{"name":"report.pdf","owner":"","contents":"<redacted>"}"#,
+//!     serde_json::to_string(&ExpungedRef(&upload)).unwrap()
+//! );
+//! ```
+
+use std::collections::VecDeque;
+
+use serde::{Serialize, Serializer};
+
+use crate::Expunge;
+
+/// Serializes the value in its expunged form, without consuming or cloning it. It's implemented
+/// by `#[expunge(slog)]`.
+pub trait SerializeExpunged {
+    fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer;
+}
+
+/// Serializes the borrowed value in its expunged form, e.g. to log it as a nested value
+pub struct ExpungedRef<'a, T: ?Sized>(pub &'a T);
+
+impl<T> Serialize for ExpungedRef<'_, T>
+where
+    T: SerializeExpunged + ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize_expunged(serializer)
+    }
+}
+
+impl<T> slog::Value for ExpungedRef<'_, T>
+where
+    T: SerializeExpunged + ?Sized,
+{
+    fn serialize(
+        &self,
+        _record: &slog::Record,
+        key: slog::Key,
+        serializer: &mut dyn slog::Serializer,
+    ) -> slog::Result {
+        serializer.emit_serde(key, self)
+    }
+}
+
+impl<T> slog::SerdeValue for ExpungedRef<'_, T>
+where
+    T: SerializeExpunged + ?Sized,
+{
+    fn as_serde(&self) -> &dyn erased_serde::Serialize {
+        self
+    }
+
+    /// Only async drains need an owned value, so they're given a copy of the serialized form
+    /// rather than requiring the value to implement `Clone`
+    fn to_sendable(&self) -> Box<dyn slog::SerdeValue + Send + 'static> {
+        let value = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        Box::new(slog::Serde(value))
+    }
+}
+
+#[doc(hidden)]
+macro_rules! serialize_default {
+    ($($typ:ty),+) => {
+        $(
+            impl SerializeExpunged for $typ {
+                fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    <$typ>::default().serialize(serializer)
+                }
+            }
+        )+
+    };
+}
+
+serialize_default!(
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    bool,
+    (),
+    String,
+    &str
+);
+
+impl<T> SerializeExpunged for Option<T>
+where
+    T: SerializeExpunged,
+{
+    fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_ref().map(ExpungedRef).serialize(serializer)
+    }
+}
+
+impl<T> SerializeExpunged for Box<T>
+where
+    T: SerializeExpunged + ?Sized,
+{
+    fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize_expunged(serializer)
+    }
+}
+
+impl<T> SerializeExpunged for Vec<T>
+where
+    T: SerializeExpunged,
+{
+    fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter().map(ExpungedRef))
+    }
+}
+
+impl<T> SerializeExpunged for VecDeque<T>
+where
+    T: SerializeExpunged,
+{
+    fn serialize_expunged<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.iter().map(ExpungedRef))
+    }
+}
+
+/// A field that's already expunged, emitted as its own key-value pair by the derived `slog::KV`
+#[doc(hidden)]
+pub struct KvValue<'a, T: ?Sized>(pub &'a T);
+
+impl<T> Serialize for KvValue<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T> slog::Value for KvValue<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn serialize(
+        &self,
+        _record: &slog::Record,
+        key: slog::Key,
+        serializer: &mut dyn slog::Serializer,
+    ) -> slog::Result {
+        serializer.emit_serde(key, self)
+    }
+}
+
+impl<T> slog::SerdeValue for KvValue<'_, T>
+where
+    T: Serialize + ?Sized,
+{
+    fn as_serde(&self) -> &dyn erased_serde::Serialize {
+        self
+    }
+
+    fn to_sendable(&self) -> Box<dyn slog::SerdeValue + Send + 'static> {
+        let value = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        Box::new(slog::Serde(value))
+    }
+}
+
+/// Wraps a borrowed field that's expunged by its type, so that it can be serialized without
+/// cloning it when possible.
+///
+/// [ViaSerializeExpunged] & [ViaClone] must be in scope, and the method must be called on
+/// `&&SlogField(..)`, so that the most specific implementation takes precedence.
+#[doc(hidden)]
+pub struct SlogField<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait ViaSerializeExpunged {
+    type Value;
+
+    fn expunged(&self) -> Self::Value;
+}
+
+impl<'a, T> ViaSerializeExpunged for &SlogField<'a, T>
+where
+    T: SerializeExpunged,
+{
+    type Value = ExpungedRef<'a, T>;
+
+    fn expunged(&self) -> ExpungedRef<'a, T> {
+        ExpungedRef(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaClone {
+    type Value;
+
+    fn expunged(&self) -> Self::Value;
+}
+
+impl<T> ViaClone for SlogField<'_, T>
+where
+    T: Clone + Expunge,
+{
+    type Value = T;
+
+    fn expunged(&self) -> T {
+        self.0.clone().expunge()
+    }
+}
@@ -0,0 +1,226 @@
+//! Bind parameters that are sent to the database as-is, but expunged whenever they're printed.
+//!
+//! Database drivers & applications log queries along with their bind parameters using their
+//! `Debug` implementations, e.g. `diesel::debug_query`. Wrapping a sensitive value in [Sensitive]
+//! before binding it means that only the expunged value can end up in those logs, while the
+//! database still receives the original.
+//!
+//! With the `sqlx` feature, [Sensitive] implements `Type`, `Encode` & `Decode` for every database
+//! that the wrapped type does. With the `diesel` feature, it implements `AsExpression`, `ToSql` &
+//! `FromSql`.
+//!
+//! ```rust
+//! use expunge::{sql::Sensitive, Expunge};
+//!
+//! #[derive(Debug, Clone, Expunge)]
+//! #[expunge(allow_debug)]
+//! struct Email(#[expunge(as = "<email>".to_string())] String);
+//!
+//! let email = Sensitive(Email("jane@example.com".to_string()));
+//! assert_eq!(r#"Email("<email>")"#, format!("{email:?}"));
+//! assert_eq!("jane@example.com", email.0 .0);
+//! ```
+
+use std::fmt::{self, Debug, Formatter};
+
+use crate::{Expunge, Expunged};
+
+/// Wraps a bind parameter so that its `Debug` implementation prints the expunged value. See the
+/// [module docs](self).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Sensitive<T>(pub T);
+
+impl<T> Sensitive<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Sensitive<T> {
+    fn from(value: T) -> Self {
+        Sensitive(value)
+    }
+}
+
+impl<T> Debug for Sensitive<T>
+where
+    T: Expunge + Clone + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Expunged::new(self.0.clone()).fmt(f)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+mod sqlx {
+    use sqlx_core::{
+        database::Database,
+        decode::Decode,
+        encode::{Encode, IsNull},
+        error::BoxDynError,
+        types::Type,
+    };
+
+    use super::Sensitive;
+
+    impl<T, DB> Type<DB> for Sensitive<T>
+    where
+        T: Type<DB>,
+        DB: Database,
+    {
+        fn type_info() -> DB::TypeInfo {
+            T::type_info()
+        }
+
+        fn compatible(ty: &DB::TypeInfo) -> bool {
+            T::compatible(ty)
+        }
+    }
+
+    impl<'q, T, DB> Encode<'q, DB> for Sensitive<T>
+    where
+        T: Encode<'q, DB>,
+        DB: Database,
+    {
+        fn encode(self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            self.0.encode(buf)
+        }
+
+        fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+            self.0.encode_by_ref(buf)
+        }
+
+        fn produces(&self) -> Option<DB::TypeInfo> {
+            self.0.produces()
+        }
+
+        fn size_hint(&self) -> usize {
+            self.0.size_hint()
+        }
+    }
+
+    impl<'r, T, DB> Decode<'r, DB> for Sensitive<T>
+    where
+        T: Decode<'r, DB>,
+        DB: Database,
+    {
+        fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+            T::decode(value).map(Sensitive)
+        }
+    }
+}
+
+/// The expression that a [Sensitive] value is bound as with `diesel`, whose bind parameter is
+/// printed by `diesel::debug_query` in its expunged form
+#[cfg(feature = "diesel")]
+#[derive(Debug, Clone, Copy)]
+pub struct SensitiveBind<ST, T> {
+    item: T,
+    sql_type: std::marker::PhantomData<ST>,
+}
+
+#[cfg(feature = "diesel")]
+mod diesel {
+    use std::{fmt::Debug, marker::PhantomData};
+
+    use diesel::{
+        backend::Backend,
+        deserialize::{self, FromSql},
+        expression::{
+            is_aggregate, AppearsOnTable, AsExpression, Expression, SelectableExpression,
+            TypedExpressionType, ValidGrouping,
+        },
+        query_builder::{AstPass, QueryFragment, QueryId},
+        result::QueryResult,
+        serialize::{self, Output, ToSql},
+        sql_types::{HasSqlType, SqlType},
+    };
+
+    use super::{Sensitive, SensitiveBind};
+
+    impl<ST, T> SensitiveBind<ST, T> {
+        fn new(item: T) -> Self {
+            Self {
+                item,
+                sql_type: PhantomData,
+            }
+        }
+    }
+
+    impl<T, ST> AsExpression<ST> for Sensitive<T>
+    where
+        ST: SqlType + TypedExpressionType,
+    {
+        type Expression = SensitiveBind<ST, Self>;
+
+        fn as_expression(self) -> Self::Expression {
+            SensitiveBind::new(self)
+        }
+    }
+
+    impl<T, ST> AsExpression<ST> for &Sensitive<T>
+    where
+        ST: SqlType + TypedExpressionType,
+    {
+        type Expression = SensitiveBind<ST, Self>;
+
+        fn as_expression(self) -> Self::Expression {
+            SensitiveBind::new(self)
+        }
+    }
+
+    impl<ST, T> Expression for SensitiveBind<ST, T>
+    where
+        ST: SqlType + TypedExpressionType,
+    {
+        type SqlType = ST;
+    }
+
+    impl<ST, T, DB> QueryFragment<DB> for SensitiveBind<ST, T>
+    where
+        DB: Backend + HasSqlType<ST>,
+        T: ToSql<ST, DB>,
+    {
+        fn walk_ast<'b>(&'b self, mut pass: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+            pass.push_bind_param(&self.item)
+        }
+    }
+
+    impl<ST, T> QueryId for SensitiveBind<ST, T>
+    where
+        ST: QueryId,
+    {
+        type QueryId = SensitiveBind<ST::QueryId, ()>;
+
+        const HAS_STATIC_QUERY_ID: bool = ST::HAS_STATIC_QUERY_ID;
+    }
+
+    impl<ST, T, QS> AppearsOnTable<QS> for SensitiveBind<ST, T> where Self: Expression {}
+
+    impl<ST, T, QS> SelectableExpression<QS> for SensitiveBind<ST, T> where Self: AppearsOnTable<QS> {}
+
+    impl<ST, T, GB> ValidGrouping<GB> for SensitiveBind<ST, T> {
+        type IsAggregate = is_aggregate::Never;
+    }
+
+    impl<T, ST, DB> ToSql<ST, DB> for Sensitive<T>
+    where
+        T: ToSql<ST, DB>,
+        DB: Backend,
+        Self: Debug,
+    {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+            self.0.to_sql(out)
+        }
+    }
+
+    impl<T, ST, DB> FromSql<ST, DB> for Sensitive<T>
+    where
+        T: FromSql<ST, DB>,
+        DB: Backend,
+    {
+        fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+            T::from_sql(bytes).map(Sensitive)
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Scrubs log lines as they're written to a sink, so that legacy `println!`-style logging can be
+//! sanitized without touching the call sites.
+//!
+//! [ScrubbingWriter] buffers what's written to it until a line is complete, then writes the line
+//! scrubbed by a [Scrubber]. It implements [std::io::Write], and `tokio::io::AsyncWrite` with the
+//! `tokio` feature.
+//!
+//! ```rust
+//! use std::io::Write;
+//! use expunge::stream::ScrubbingWriter;
+//!
+//! let mut writer = ScrubbingWriter::new(vec![]);
+//! write!(writer, "login by jane@")?;
+//! writeln!(writer, "example.com")?;
+//!
+//! assert_eq!(b"login by <email>\n", writer.get_ref().as_slice());
+//! # Ok::<_, std::io::Error>(())
+//! ```
+//!
+//! Flushing writes the incomplete line as well, so a value that's split across a flush isn't
+//! scrubbed. Lines longer than [ScrubbingWriter::max_line] are also written in parts. Call
+//! [flush](std::io::Write::flush) before dropping the writer, or the incomplete line is lost.
+
+use std::io;
+
+use crate::utils::Scrubber;
+
+/// Incomplete lines longer than this are written in parts by default
+pub const DEFAULT_MAX_LINE: usize = 64 * 1024;
+
+/// A writer that scrubs each line before it's written to the inner writer. See the
+/// [module docs](self).
+#[derive(Debug)]
+pub struct ScrubbingWriter<W> {
+    inner: W,
+    scrubber: Scrubber,
+    max_line: usize,
+    // the incomplete line
+    pending: Vec<u8>,
+    // scrubbed output that hasn't been written to the inner writer yet
+    output: Vec<u8>,
+}
+
+impl<W> ScrubbingWriter<W> {
+    /// Scrubs lines with the built-in patterns of [Scrubber]
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            scrubber: Scrubber::new(),
+            max_line: DEFAULT_MAX_LINE,
+            pending: vec![],
+            output: vec![],
+        }
+    }
+
+    /// Use a different scrubber, e.g. with custom patterns
+    pub fn scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    /// The length in bytes of the longest incomplete line that will be buffered. Defaults to
+    /// [DEFAULT_MAX_LINE].
+    pub fn max_line(mut self, max_line: usize) -> Self {
+        self.max_line = max_line;
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns the inner writer, discarding an incomplete line that hasn't been flushed
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Buffers the bytes, moving every complete line to the output
+    fn push(&mut self, buf: &[u8]) {
+        self.pending.extend_from_slice(buf);
+        if let Some(end) = self.pending.iter().rposition(|&b| b == b'\n') {
+            let lines: Vec<u8> = self.pending.drain(..=end).collect();
+            for line in lines.split_inclusive(|&b| b == b'\n') {
+                self.scrub(line);
+            }
+        }
+        if self.pending.len() > self.max_line {
+            self.take_pending();
+        }
+    }
+
+    /// Moves the incomplete line to the output
+    fn take_pending(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        if !pending.is_empty() {
+            self.scrub(&pending);
+        }
+    }
+
+    fn scrub(&mut self, text: &[u8]) {
+        let text = String::from_utf8_lossy(text).into_owned();
+        self.output
+            .extend_from_slice(self.scrubber.scrub(text).as_bytes());
+    }
+}
+
+impl<W> io::Write for ScrubbingWriter<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        let output = std::mem::take(&mut self.output);
+        self.inner.write_all(&output)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.take_pending();
+        let output = std::mem::take(&mut self.output);
+        self.inner.write_all(&output)?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio {
+    use std::{
+        io,
+        pin::Pin,
+        task::{ready, Context, Poll},
+    };
+
+    use tokio::io::AsyncWrite;
+
+    use super::ScrubbingWriter;
+
+    impl<W> ScrubbingWriter<W>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        /// Writes the scrubbed output to the inner writer
+        fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            while !self.output.is_empty() {
+                let n = ready!(Pin::new(&mut self.inner).poll_write(cx, &self.output))?;
+                if n == 0 {
+                    return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+                }
+                self.output.drain(..n);
+            }
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl<W> AsyncWrite for ScrubbingWriter<W>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let this = self.get_mut();
+            // the previous output must be written first, so that it doesn't grow without bound
+            ready!(this.poll_drain(cx))?;
+            this.push(buf);
+            // the bytes have been accepted, so the output is written by the next call
+            let _ = this.poll_drain(cx)?;
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            let this = self.get_mut();
+            this.take_pending();
+            ready!(this.poll_drain(cx))?;
+            Pin::new(&mut this.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            let this = self.get_mut();
+            this.take_pending();
+            ready!(this.poll_drain(cx))?;
+            Pin::new(&mut this.inner).poll_shutdown(cx)
+        }
+    }
+}
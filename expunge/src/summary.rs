@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// How many of a value's fields are expunged. See [Expunged::summary](crate::Expunged::summary).
+///
+/// Only the value's own fields are counted, not those of nested values. For enums, only the fields
+/// of the current variant are counted. Fields with `if` or `unless` conditions are counted as
+/// expunged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub type_name: &'static str,
+    /// The number of fields that are expunged
+    pub redacted: usize,
+    /// The total number of fields
+    pub total: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expunged<{}>: {}/{} fields redacted",
+            self.type_name, self.redacted, self.total
+        )
+    }
+}
+
+/// Describes which fields are expunged. This is implemented by `#[derive(Expunge)]`.
+pub trait Summarize {
+    fn summarize(&self) -> Summary;
+}
@@ -0,0 +1,52 @@
+//! Helpers called by `#[derive(Expunge)]` for each field that's expunged.
+//!
+//! These always exist, and do nothing when the corresponding feature of `expunge` is disabled, so
+//! that the generated code compiles however features end up being unified between `expunge` &
+//! `expunge_derive`.
+
+use crate::Classification;
+
+/// Whether the field should be reported to observers, checked before building its class
+#[cfg(feature = "observer")]
+#[inline(always)]
+pub fn is_observed() -> bool {
+    crate::observer::is_enabled()
+}
+
+#[cfg(not(feature = "observer"))]
+#[inline(always)]
+pub fn is_observed() -> bool {
+    false
+}
+
+/// Notifies the registered observers
+#[inline]
+pub fn observe(
+    type_name: &'static str,
+    variant: Option<&'static str>,
+    field: &'static str,
+    class: Option<Classification>,
+) {
+    #[cfg(feature = "observer")]
+    {
+        let severity = crate::observer::Severity::for_class(class.as_ref());
+        crate::observer::notify(&crate::observer::EventMeta {
+            type_name,
+            variant,
+            field,
+            class,
+            severity,
+        });
+    }
+    #[cfg(not(feature = "observer"))]
+    let _ = (type_name, variant, field, class);
+}
+
+/// Increments the counter of expunged fields, if the `metrics` feature is enabled
+#[inline]
+pub fn count(type_name: &'static str, field: &'static str) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::increment(type_name, field);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (type_name, field);
+}
@@ -0,0 +1,223 @@
+//! Helpers for testing that types are expunged thoroughly.
+//!
+//! [assert_no_leak] serializes an original & an expunged instance, and fails if any string that
+//! was changed by expunging still appears anywhere in the expunged output, e.g. because it was
+//! copied into another field or only partially masked.
+//!
+//! ```rust
+//! use expunge::{testing, Expunge};
+//! use serde::Serialize;
+//!
+//! #[derive(Clone, Serialize, Expunge)]
+//! struct User {
+//!     #[expunge(skip)]
+//!     username: String,
+//!     #[expunge(keep_last = 4)]
+//!     phone: String,
+//!     email: String,
+//! }
+//!
+//! let user = User {
+//!     username: "jane_doe".to_string(),
+//!     phone: "+44 20 7946 0958".to_string(),
+//!     email: "jane@example.com".to_string(),
+//! };
+//! testing::assert_no_leak(&user, &user.clone().expunge());
+//! ```
+//!
+//! Strings that are left as-is, e.g. skipped fields, aren't sensitive, and neither are strings
+//! shorter than [MIN_LEN] characters, which would match by coincidence. Other values, e.g.
+//! numbers, aren't checked.
+//!
+//! With the `proptest` feature, [assert_no_leak_for] checks every value generated by a strategy.
+//!
+//! [to_redacted_json] expunges a value & serializes it for snapshot tests, e.g. with `insta`:
+//!
+//! ```rust
+//! use expunge::{testing, Expunge};
+//! use serde::Serialize;
+//! use std::collections::HashMap;
+//!
+//! #[derive(Serialize, Expunge)]
+//! struct Session {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     #[expunge(skip)]
+//!     tags: HashMap<String, u32>,
+//!     token: String,
+//! }
+//!
+//! let session = Session {
+//!     id: 7,
+//!     tags: HashMap::from([("web".to_string(), 1), ("eu".to_string(), 2)]),
+//!     token: "secret".to_string(),
+//! };
+//! assert_eq!(
+//!     r#"{
+//!   "id": 7,
+//!   "tags": {
+//!     "eu": 2,
+//!     "web": 1
+//!   },
+//!   "token": ""
+//! }"#,
+//!     testing::to_redacted_json(session)
+//! );
+//! ```
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::Expunge;
+
+/// Strings shorter than this many characters aren't checked for leaks
+pub const MIN_LEN: usize = 4;
+
+/// A sensitive string that still appears in the expunged output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    /// Where the string is in the original, e.g. `address.lines[0]`
+    pub path: String,
+    pub value: String,
+}
+
+/// Finds every string in the original that was changed by expunging, but still appears somewhere
+/// in the serialized expunged value
+pub fn leaks<T>(original: &T, expunged: &T) -> Vec<Leak>
+where
+    T: Serialize + ?Sized,
+{
+    let original = serde_json::to_value(original).expect("the original should serialize");
+    let expunged = serde_json::to_value(expunged).expect("the expunged value should serialize");
+    let output = expunged.to_string();
+
+    let mut sensitive = vec![];
+    collect_sensitive(String::new(), &original, Some(&expunged), &mut sensitive);
+    sensitive
+        .into_iter()
+        .filter(|(_, value)| value.chars().count() >= MIN_LEN)
+        .filter(|(_, value)| {
+            // compared as it's escaped in the output
+            let escaped = Value::String(value.clone()).to_string();
+            output.contains(&escaped[1..escaped.len() - 1])
+        })
+        .map(|(path, value)| Leak { path, value })
+        .collect()
+}
+
+/// Panics if any string that was changed by expunging still appears in the expunged value. See
+/// [leaks].
+#[track_caller]
+pub fn assert_no_leak<T>(original: &T, expunged: &T)
+where
+    T: Serialize + ?Sized,
+{
+    let leaks = leaks(original, expunged);
+    if !leaks.is_empty() {
+        let paths: Vec<_> = leaks.iter().map(|leak| leak.path.as_str()).collect();
+        panic!(
+            "sensitive values leaked into the expunged output from: {}",
+            paths.join(", ")
+        );
+    }
+}
+
+/// Expunges each value generated by the strategy & asserts that none of them leak. See [leaks].
+#[cfg(feature = "proptest")]
+#[track_caller]
+pub fn assert_no_leak_for<S>(strategy: S)
+where
+    S: proptest::strategy::Strategy,
+    S::Value: Clone + Expunge + Serialize,
+{
+    use proptest::test_runner::{TestCaseError, TestRunner};
+
+    let result = TestRunner::default().run(&strategy, |value| {
+        let leaks = leaks(&value, &value.clone().expunge());
+        if leaks.is_empty() {
+            Ok(())
+        } else {
+            let paths: Vec<_> = leaks.iter().map(|leak| leak.path.as_str()).collect();
+            Err(TestCaseError::fail(format!(
+                "sensitive values leaked into the expunged output from: {}",
+                paths.join(", ")
+            )))
+        }
+    });
+    if let Err(err) = result {
+        panic!("{err}");
+    }
+}
+
+/// Expunges the value & serializes it as pretty JSON, with the keys of every object sorted so that
+/// the output is stable, e.g. for snapshot tests
+pub fn to_redacted_json<T>(value: T) -> String
+where
+    T: Expunge + Serialize,
+{
+    let value = serde_json::to_value(value.expunge()).expect("the expunged value should serialize");
+    serde_json::to_string_pretty(&sorted(value)).expect("a JSON value always serializes")
+}
+
+/// Sorts the keys of every object, since maps such as `HashMap` serialize in an arbitrary order
+fn sorted(value: Value) -> Value {
+    match value {
+        Value::Array(values) => Value::Array(values.into_iter().map(sorted).collect()),
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sorted(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Collects the strings of the original that differ from the expunged value at the same path
+fn collect_sensitive(
+    path: String,
+    original: &Value,
+    expunged: Option<&Value>,
+    sensitive: &mut Vec<(String, String)>,
+) {
+    if expunged == Some(original) {
+        return;
+    }
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match original {
+        Value::String(value) => sensitive.push((path, value.clone())),
+        Value::Array(values) => {
+            let expunged = expunged.and_then(Value::as_array);
+            for (i, value) in values.iter().enumerate() {
+                collect_sensitive(
+                    format!("{path}[{i}]"),
+                    value,
+                    expunged.and_then(|expunged| expunged.get(i)),
+                    sensitive,
+                );
+            }
+        }
+        Value::Object(map) => {
+            let expunged = expunged.and_then(Value::as_object);
+            for (key, value) in map {
+                collect_sensitive(
+                    join(key),
+                    value,
+                    expunged.and_then(|expunged| expunged.get(key)),
+                    sensitive,
+                );
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
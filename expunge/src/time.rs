@@ -0,0 +1,77 @@
+use super::{utils::TruncateDate, Expunge};
+use ::time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+/// Dates & times are expunged as the unix epoch, i.e. 1970-01-01T00:00:00Z
+impl Expunge for Date {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        OffsetDateTime::UNIX_EPOCH.date()
+    }
+}
+
+impl Expunge for Time {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        Time::MIDNIGHT
+    }
+}
+
+impl Expunge for PrimitiveDateTime {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        PrimitiveDateTime::new(OffsetDateTime::UNIX_EPOCH.date(), Time::MIDNIGHT)
+    }
+}
+
+/// The offset is left as-is
+impl Expunge for OffsetDateTime {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        OffsetDateTime::UNIX_EPOCH.to_offset(self.offset())
+    }
+}
+
+impl TruncateDate for Date {
+    fn truncate_to_year(self) -> Self {
+        Date::from_calendar_date(self.year(), Month::January, 1)
+            .expect("the first day of the year is valid")
+    }
+
+    fn truncate_to_month(self) -> Self {
+        Date::from_calendar_date(self.year(), self.month(), 1)
+            .expect("the first day of the month is valid")
+    }
+}
+
+impl TruncateDate for PrimitiveDateTime {
+    fn truncate_to_year(self) -> Self {
+        PrimitiveDateTime::new(self.date().truncate_to_year(), Time::MIDNIGHT)
+    }
+
+    fn truncate_to_month(self) -> Self {
+        PrimitiveDateTime::new(self.date().truncate_to_month(), Time::MIDNIGHT)
+    }
+}
+
+/// Truncation happens in UTC, the same as for `chrono`'s `DateTime`. The offset is left as-is.
+impl TruncateDate for OffsetDateTime {
+    fn truncate_to_year(self) -> Self {
+        let utc = self.to_offset(UtcOffset::UTC);
+        let utc = PrimitiveDateTime::new(utc.date(), utc.time()).truncate_to_year();
+        utc.assume_utc().to_offset(self.offset())
+    }
+
+    fn truncate_to_month(self) -> Self {
+        let utc = self.to_offset(UtcOffset::UTC);
+        let utc = PrimitiveDateTime::new(utc.date(), utc.time()).truncate_to_month();
+        utc.assume_utc().to_offset(self.offset())
+    }
+}
@@ -0,0 +1,244 @@
+//! Reversible redaction, which replaces values with opaque tokens that can be resolved again by
+//! a [Tokenizer], e.g. so that support staff can look up the original value of a specific record.
+//!
+//! Fields annotated with `#[expunge(tokenize)]` are stored in the registered tokenizer and replaced
+//! with its token. Without a registered tokenizer, or if the value can't be stored, the field is
+//! expunged as usual instead, so values are never left as-is.
+//!
+//! ```rust
+//! use expunge::{tokenize::{self, MemoryTokenizer}, Expunge};
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Expunge)]
+//! struct Ticket {
+//!     #[expunge(tokenize)]
+//!     email: String,
+//!     #[expunge(skip)]
+//!     subject: String,
+//! }
+//!
+//! tokenize::register(MemoryTokenizer::new());
+//!
+//! let audit = Arc::new(Mutex::new(vec![]));
+//! tokenize::on_detokenize({
+//!     let audit = audit.clone();
+//!     move |event: &tokenize::Detokenization| {
+//!         audit.lock().unwrap().push(format!("{} by {}", event.token, event.requested_by))
+//!     }
+//! });
+//!
+//! let ticket = Ticket {
+//!     email: "jane@example.com".to_string(),
+//!     subject: "Can't log in".to_string(),
+//! }
+//! .expunge();
+//! assert_eq!("tok_1", ticket.email);
+//!
+//! let email = tokenize::detokenize(&ticket.email, "support@example.com", "ticket #42")?;
+//! assert_eq!(Some("jane@example.com".to_string()), email);
+//! assert_eq!(vec!["tok_1 by support@example.com"], *audit.lock().unwrap());
+//! # Ok::<_, tokenize::TokenizeError>(())
+//! ```
+//!
+//! The tokenizer & audit hook are global, so they're typically registered once at startup. Tokens
+//! can't be resolved until an audit hook has been registered.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex, PoisonError, RwLock},
+};
+
+/// Stores values & issues opaque tokens for them, e.g. backed by a vault service
+pub trait Tokenizer: Send + Sync {
+    /// Stores the value and returns its token. Storing the same value again may return the same
+    /// token.
+    fn store(&self, value: &str) -> Result<String, TokenizeError>;
+
+    /// The value of a token, if it was issued by this tokenizer
+    fn resolve(&self, token: &str) -> Result<Option<String>, TokenizeError>;
+}
+
+/// The tokenizer couldn't store or resolve a value, e.g. because the vault service is unavailable,
+/// or a token was resolved without an audit hook
+#[derive(Debug)]
+pub struct TokenizeError(Kind);
+
+#[derive(Debug)]
+enum Kind {
+    Tokenizer(Box<dyn std::error::Error + Send + Sync>),
+    NoAuditHook,
+}
+
+impl TokenizeError {
+    pub fn new(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self(Kind::Tokenizer(err.into()))
+    }
+}
+
+impl fmt::Display for TokenizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Kind::Tokenizer(err) => write!(f, "tokenizer failed: {err}"),
+            Kind::NoAuditHook => f.write_str(
+                "tokens can't be resolved until an audit hook is registered with `on_detokenize`",
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TokenizeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.0 {
+            Kind::Tokenizer(err) => Some(&**err),
+            Kind::NoAuditHook => None,
+        }
+    }
+}
+
+/// A request to resolve a token, which is passed to the hook registered with [on_detokenize]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Detokenization<'a> {
+    pub token: &'a str,
+    /// Who resolved the token, e.g. the support agent's email
+    pub requested_by: &'a str,
+    /// Why the token was resolved, e.g. a ticket number
+    pub reason: &'a str,
+    /// Whether the token was resolved to a value
+    pub resolved: bool,
+}
+
+type AuditHook = dyn Fn(&Detokenization<'_>) + Send + Sync;
+
+static TOKENIZER: RwLock<Option<Arc<dyn Tokenizer>>> = RwLock::new(None);
+
+static AUDIT: RwLock<Option<Arc<AuditHook>>> = RwLock::new(None);
+
+/// Registers the tokenizer that `#[expunge(tokenize)]` fields are stored in, replacing any
+/// previously registered tokenizer
+pub fn register(tokenizer: impl Tokenizer + 'static) {
+    *TOKENIZER.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(tokenizer));
+}
+
+/// Unregisters the tokenizer, so that `#[expunge(tokenize)]` fields are expunged as usual
+pub fn unregister() {
+    *TOKENIZER.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+fn tokenizer() -> Option<Arc<dyn Tokenizer>> {
+    TOKENIZER
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Registers a hook that's called whenever a token is resolved with [detokenize], e.g. to write
+/// an audit log. It replaces any previously registered hook.
+pub fn on_detokenize(hook: impl Fn(&Detokenization<'_>) + Send + Sync + 'static) {
+    *AUDIT.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(hook));
+}
+
+/// Stores the value in the registered tokenizer & returns its token. Returns `None` if there's
+/// no registered tokenizer.
+pub fn store(value: &str) -> Option<Result<String, TokenizeError>> {
+    tokenizer().map(|tokenizer| tokenizer.store(value))
+}
+
+/// Resolves a token with the registered tokenizer, recording who resolved it & why with the hook
+/// registered by [on_detokenize]. Returns `None` without a registered tokenizer, or if the token
+/// wasn't issued by it.
+///
+/// # Errors
+///
+/// If no audit hook has been registered, so that tokens can't be resolved without a record, or if
+/// the tokenizer fails. Failed attempts are still recorded.
+pub fn detokenize(
+    token: &str,
+    requested_by: &str,
+    reason: &str,
+) -> Result<Option<String>, TokenizeError> {
+    let Some(hook) = AUDIT.read().unwrap_or_else(PoisonError::into_inner).clone() else {
+        return Err(TokenizeError(Kind::NoAuditHook));
+    };
+    let value = tokenizer()
+        .map(|tokenizer| tokenizer.resolve(token))
+        .transpose()
+        .map(Option::flatten);
+    hook(&Detokenization {
+        token,
+        requested_by,
+        reason,
+        resolved: matches!(value, Ok(Some(_))),
+    });
+    value
+}
+
+/// Values that can be replaced with a token by `#[expunge(tokenize)]`
+pub trait Tokenize {
+    fn tokenize(self) -> Self
+    where
+        Self: Sized;
+}
+
+impl Tokenize for String {
+    fn tokenize(self) -> Self {
+        match store(&self) {
+            Some(Ok(token)) => token,
+            // the value is never left as-is
+            _ => String::new(),
+        }
+    }
+}
+
+impl Tokenize for Cow<'_, str> {
+    fn tokenize(self) -> Self {
+        Cow::Owned(self.into_owned().tokenize())
+    }
+}
+
+impl<T> Tokenize for Option<T>
+where
+    T: Tokenize,
+{
+    fn tokenize(self) -> Self {
+        self.map(Tokenize::tokenize)
+    }
+}
+
+/// An in-memory [Tokenizer] that issues sequential tokens, e.g. `tok_1`, for tests & local
+/// development. Values are lost when it's dropped.
+#[derive(Debug, Default)]
+pub struct MemoryTokenizer {
+    tokens: Mutex<Tokens>,
+}
+
+#[derive(Debug, Default)]
+struct Tokens {
+    by_value: HashMap<String, String>,
+    by_token: HashMap<String, String>,
+}
+
+impl MemoryTokenizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Tokenizer for MemoryTokenizer {
+    fn store(&self, value: &str) -> Result<String, TokenizeError> {
+        let mut tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(token) = tokens.by_value.get(value) {
+            return Ok(token.clone());
+        }
+        let token = format!("tok_{}", tokens.by_token.len() + 1);
+        tokens.by_value.insert(value.to_string(), token.clone());
+        tokens.by_token.insert(token.clone(), value.to_string());
+        Ok(token)
+    }
+
+    fn resolve(&self, token: &str) -> Result<Option<String>, TokenizeError> {
+        let tokens = self.tokens.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(tokens.by_token.get(token).cloned())
+    }
+}
@@ -0,0 +1,194 @@
+//! Logs gRPC messages with [tonic], expunging them first.
+//!
+//! [ExpungeHandlerLayer] wraps a handler, i.e. a service of typed `tonic` requests & responses,
+//! and passes an expunged copy of each message to a logging function before the original is
+//! handled. The messages themselves are left untouched.
+//!
+//! It works at the handler level only. Generated `tonic` servers & clients are services of
+//! `http::Request`s, whose bodies are still encoded, so it can't be layered onto them, e.g. with
+//! `Server::builder().layer(..)`. Wrap the handler instead, or log from the handler with
+//! [ExpungedMessage].
+//!
+//! ```rust
+//! use expunge::{tonic::{Event, ExpungeHandlerLayer}, Expunge};
+//! use tonic::{Request, Response, Status};
+//! use tower_layer::Layer;
+//!
+//! #[derive(Clone, Expunge)]
+//! struct Login {
+//!     #[expunge(skip)]
+//!     username: String,
+//!     password: String,
+//! }
+//!
+//! let service = tower::service_fn(|request: Request<Login>| async move {
+//!     Ok::<_, Status>(Response::new(request.into_inner().username))
+//! });
+//!
+//! let service = ExpungeHandlerLayer::new(|event: Event<'_>| println!("{event:?}")).layer(service);
+//! ```
+
+use std::{
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tonic::{Request, Response, Status};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{Expunge, Expunged};
+
+/// Passed to the logging function of [ExpungeHandlerLayer]
+#[derive(Debug, Clone, Copy)]
+pub enum Event<'a> {
+    /// The expunged request message
+    Request(&'a dyn Debug),
+    /// The expunged response message
+    Response(&'a dyn Debug),
+    /// The service returned an error
+    Error(&'a Status),
+}
+
+/// A [Layer] that logs an expunged copy of each request & response message. See the
+/// [module docs](self).
+pub struct ExpungeHandlerLayer<F> {
+    log: Arc<F>,
+}
+
+impl<F> ExpungeHandlerLayer<F>
+where
+    F: Fn(Event<'_>),
+{
+    /// Calls `log` with each expunged message, e.g. to record it with `tracing`
+    pub fn new(log: F) -> Self {
+        Self { log: Arc::new(log) }
+    }
+}
+
+impl<F> Clone for ExpungeHandlerLayer<F> {
+    fn clone(&self) -> Self {
+        Self {
+            log: self.log.clone(),
+        }
+    }
+}
+
+impl<S, F> Layer<S> for ExpungeHandlerLayer<F> {
+    type Service = ExpungeHandlerService<S, F>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExpungeHandlerService {
+            inner,
+            log: self.log.clone(),
+        }
+    }
+}
+
+/// The service created by [ExpungeHandlerLayer]
+pub struct ExpungeHandlerService<S, F> {
+    inner: S,
+    log: Arc<F>,
+}
+
+impl<S, F> Clone for ExpungeHandlerService<S, F>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            log: self.log.clone(),
+        }
+    }
+}
+
+impl<S, F, M, R> Service<Request<M>> for ExpungeHandlerService<S, F>
+where
+    S: Service<Request<M>, Response = Response<R>, Error = Status>,
+    F: Fn(Event<'_>),
+    M: Expunge + Clone + Debug,
+    R: Expunge + Clone + Debug,
+{
+    type Response = Response<R>;
+    type Error = Status;
+    type Future = ExpungeHandlerFuture<S::Future, F>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<M>) -> Self::Future {
+        (self.log)(Event::Request(&request.expunged()));
+        ExpungeHandlerFuture {
+            inner: Box::pin(self.inner.call(request)),
+            log: self.log.clone(),
+        }
+    }
+}
+
+/// The response future of [ExpungeHandlerService]
+pub struct ExpungeHandlerFuture<Fut, F> {
+    inner: Pin<Box<Fut>>,
+    log: Arc<F>,
+}
+
+impl<Fut, F, R> Future for ExpungeHandlerFuture<Fut, F>
+where
+    Fut: Future<Output = Result<Response<R>, Status>>,
+    F: Fn(Event<'_>),
+    R: Expunge + Clone + Debug,
+{
+    type Output = Fut::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = std::task::ready!(self.inner.as_mut().poll(cx));
+        match &result {
+            Ok(response) => (self.log)(Event::Response(&response.expunged())),
+            Err(status) => (self.log)(Event::Error(status)),
+        }
+        Poll::Ready(result)
+    }
+}
+
+/// Expunges a copy of the message in a `tonic` request or response, e.g. to log it from a handler.
+/// The metadata isn't included, since it can contain credentials.
+///
+/// ```rust
+/// use expunge::{tonic::ExpungedMessage, Expunge};
+///
+/// #[derive(Debug, Clone, Expunge)]
+/// #[expunge(allow_debug)]
+/// struct Login {
+///     password: String,
+/// }
+///
+/// let request = tonic::Request::new(Login { password: "hunter2".to_string() });
+///
+/// assert_eq!(r#"Login { password: "" }"#, format!("{:?}", request.expunged()));
+/// assert_eq!("hunter2", request.get_ref().password);
+/// ```
+pub trait ExpungedMessage<T> {
+    fn expunged(&self) -> Expunged<T>;
+}
+
+impl<T> ExpungedMessage<T> for Request<T>
+where
+    T: Expunge + Clone,
+{
+    fn expunged(&self) -> Expunged<T> {
+        Expunged::new(self.get_ref().clone())
+    }
+}
+
+impl<T> ExpungedMessage<T> for Response<T>
+where
+    T: Expunge + Clone,
+{
+    fn expunged(&self) -> Expunged<T> {
+        Expunged::new(self.get_ref().clone())
+    }
+}
@@ -0,0 +1,447 @@
+//! Scrubs what [tracing_subscriber] layers record, for sensitive data in fields & free text.
+//!
+//! [ExpungeLayer] wraps a layer, e.g. [tracing_subscriber::fmt::Layer], and rewrites the fields of
+//! each event & span before they reach it. This covers crates that log sensitive fields which
+//! can't be annotated with `#[derive(Expunge)]`:
+//!
+//! - the values of fields whose names match a pattern, e.g. `*password*`, are replaced with
+//!   `<expunged>`
+//! - every other string, `Debug` or error value, including the message, is scrubbed by a
+//!   [Scrubber]
+//!
+//! ```rust
+//! use expunge::tracing::ExpungeLayer;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let subscriber = tracing_subscriber::registry()
+//!     .with(ExpungeLayer::new(tracing_subscriber::fmt::layer()).deny(["*card_number*"]));
+//! ```
+//!
+//! Other layers that are registered alongside the wrapper still see the raw values, so each layer
+//! that writes output should be wrapped.
+//!
+//! `tracing`'s [Value] trait is sealed, so [Expunged](crate::Expunged) can't be recorded as a
+//! field directly. [value] expunges a value & records it with its `Debug` implementation instead:
+//!
+//! ```rust
+//! use expunge::Expunge;
+//!
+//! #[derive(Debug, Expunge)]
+//! #[expunge(allow_debug)]
+//! struct User {
+//!     #[expunge(skip)]
+//!     id: u64,
+//!     email: String,
+//! }
+//!
+//! let user = User { id: 1, email: "jane@example.com".to_string() };
+//! tracing::info!(user = expunge::tracing::value(user), "logged in");
+//! ```
+//!
+//! Span names are static in `tracing`, so they can't be rewritten by a layer. Instead,
+//! [ScrubWriter] wraps the writer of the `fmt` layer, and applies a [Scrubber] to each formatted
+//! line before it's written, including the names of the spans it was recorded in.
+//!
+//! ```rust
+//! use expunge::{tracing::ScrubWriter, utils::Scrubber};
+//!
+//! let scrubber = Scrubber::new()
+//!     .with_pattern("user_id", r"user-\d+")
+//!     .expect("the pattern should be valid");
+//!
+//! let subscriber = tracing_subscriber::fmt()
+//!     .with_writer(ScrubWriter::new(std::io::stdout).scrubber(scrubber).targets(["app::users"]))
+//!     .finish();
+//! ```
+
+use std::{any::TypeId, fmt, io};
+
+use tracing_core::{
+    field::{debug, display, DebugValue, DisplayValue, Field, Value, Visit},
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+    Dispatch, Event, LevelFilter, Metadata, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::MakeWriter,
+    layer::{Context, Layer},
+    registry::LookupSpan,
+};
+
+use crate::{
+    utils::{matches_glob, Scrubber, SENSITIVE_KEYS},
+    Expunge,
+};
+
+/// Expunges a value to record it as a field of a span or event, with its `Debug` implementation.
+/// See the [module docs](self).
+pub fn value<T>(value: T) -> DebugValue<T>
+where
+    T: Expunge + fmt::Debug,
+{
+    debug(value.expunge())
+}
+
+/// Wraps a [Layer] to expunge fields by name, and scrub the values of the others. See the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct ExpungeLayer<L> {
+    inner: L,
+    deny: Vec<String>,
+    scrubber: Option<Scrubber>,
+}
+
+impl<L> ExpungeLayer<L> {
+    /// Expunges the fields with the built-in name patterns, e.g. `*password*` & `*token*`, and
+    /// scrubs the others with the built-in patterns of [Scrubber]
+    pub fn new(inner: L) -> Self {
+        Self {
+            inner,
+            deny: SENSITIVE_KEYS.iter().map(ToString::to_string).collect(),
+            scrubber: Some(Scrubber::new()),
+        }
+    }
+
+    /// Passes every field through as-is, until names are denied or a scrubber is set
+    pub fn empty(inner: L) -> Self {
+        Self {
+            inner,
+            deny: vec![],
+            scrubber: None,
+        }
+    }
+
+    /// Expunges the values of fields whose names match one of the patterns, ignoring ASCII case.
+    /// `*` matches any number of characters, e.g. `user.*`.
+    pub fn deny<I>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.deny.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Scrub the values of fields with a different scrubber, e.g. with custom patterns
+    pub fn scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = Some(scrubber);
+        self
+    }
+
+    /// Whether the value of the field would be expunged entirely
+    pub fn is_denied(&self, name: &str) -> bool {
+        self.deny.iter().any(|pattern| matches_glob(pattern, name))
+    }
+
+    fn scrub(&self, value: String) -> String {
+        match &self.scrubber {
+            Some(scrubber) => scrubber.scrub(value),
+            None => value,
+        }
+    }
+
+    /// Records the values of the fields, expunged & scrubbed, by their index in the field set
+    fn expunged(
+        &self,
+        fields: usize,
+        record: impl FnOnce(&mut dyn Visit),
+    ) -> Vec<Option<Recorded>> {
+        let mut visitor = Expunged {
+            layer: self,
+            values: std::iter::repeat_with(|| None).take(fields).collect(),
+        };
+        record(&mut visitor);
+        visitor.values
+    }
+}
+
+/// An owned copy of a recorded value
+enum Recorded {
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Debug(DisplayValue<String>),
+    // denied fields borrow the placeholder, so that they don't allocate
+    Placeholder(DisplayValue<&'static str>),
+}
+
+impl Recorded {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Recorded::I64(value) => value,
+            Recorded::U64(value) => value,
+            Recorded::I128(value) => value,
+            Recorded::U128(value) => value,
+            Recorded::F64(value) => value,
+            Recorded::Bool(value) => value,
+            Recorded::Str(value) => value,
+            Recorded::Debug(value) => value,
+            Recorded::Placeholder(value) => value,
+        }
+    }
+}
+
+fn as_values(recorded: &[Option<Recorded>]) -> Vec<Option<&dyn Value>> {
+    recorded
+        .iter()
+        .map(|value| value.as_ref().map(Recorded::as_value))
+        .collect()
+}
+
+struct Expunged<'a, L> {
+    layer: &'a ExpungeLayer<L>,
+    values: Vec<Option<Recorded>>,
+}
+
+impl<L> Expunged<'_, L> {
+    /// The value is only recorded if the field isn't denied, so that denied fields are never
+    /// formatted or scrubbed
+    fn set(&mut self, field: &Field, value: impl FnOnce() -> Recorded) {
+        let value = if self.layer.is_denied(field.name()) {
+            Recorded::Placeholder(display("<expunged>"))
+        } else {
+            value()
+        };
+        if let Some(slot) = self.values.get_mut(field.index()) {
+            *slot = Some(value);
+        }
+    }
+}
+
+impl<L> Visit for Expunged<'_, L> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, || Recorded::F64(value))
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, || Recorded::I64(value))
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(field, || Recorded::U64(value))
+    }
+
+    fn record_i128(&mut self, field: &Field, value: i128) {
+        self.set(field, || Recorded::I128(value))
+    }
+
+    fn record_u128(&mut self, field: &Field, value: u128) {
+        self.set(field, || Recorded::U128(value))
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, || Recorded::Bool(value))
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let layer = self.layer;
+        self.set(field, || Recorded::Str(layer.scrub(value.to_string())))
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        let layer = self.layer;
+        self.set(field, || {
+            Recorded::Debug(display(layer.scrub(value.to_string())))
+        })
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let layer = self.layer;
+        self.set(field, || {
+            Recorded::Debug(display(layer.scrub(format!("{value:?}"))))
+        })
+    }
+}
+
+impl<S, L> Layer<S> for ExpungeLayer<L>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    L: Layer<S>,
+{
+    fn on_register_dispatch(&self, subscriber: &Dispatch) {
+        self.inner.on_register_dispatch(subscriber)
+    }
+
+    fn on_layer(&mut self, subscriber: &mut S) {
+        self.inner.on_layer(subscriber)
+    }
+
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.inner.register_callsite(metadata)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.enabled(metadata, ctx)
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let metadata = attrs.metadata();
+        let recorded = self.expunged(metadata.fields().len(), |visitor| attrs.record(visitor));
+        let values = as_values(&recorded);
+        let values = metadata.fields().value_set_all(&values);
+        let attrs = match attrs.parent() {
+            Some(parent) => Attributes::child_of(parent.clone(), metadata, &values),
+            None if attrs.is_root() => Attributes::new_root(metadata, &values),
+            None => Attributes::new(metadata, &values),
+        };
+        self.inner.on_new_span(&attrs, id, ctx)
+    }
+
+    fn on_record(&self, span: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        // the fields of a record are those of the span, which only the registry knows
+        let Some(metadata) = ctx.metadata(span) else {
+            return self.inner.on_record(span, values, ctx);
+        };
+        let recorded = self.expunged(metadata.fields().len(), |visitor| values.record(visitor));
+        let values = as_values(&recorded);
+        let values = metadata.fields().value_set_all(&values);
+        self.inner.on_record(span, &Record::new(&values), ctx)
+    }
+
+    fn on_follows_from(&self, span: &Id, follows: &Id, ctx: Context<'_, S>) {
+        self.inner.on_follows_from(span, follows, ctx)
+    }
+
+    fn event_enabled(&self, event: &Event<'_>, ctx: Context<'_, S>) -> bool {
+        self.inner.event_enabled(event, ctx)
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let recorded = self.expunged(metadata.fields().len(), |visitor| event.record(visitor));
+        let values = as_values(&recorded);
+        let values = metadata.fields().value_set_all(&values);
+        let event = if event.is_contextual() {
+            Event::new(metadata, &values)
+        } else {
+            Event::new_child_of(event.parent().cloned(), metadata, &values)
+        };
+        self.inner.on_event(&event, ctx)
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_enter(id, ctx)
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        self.inner.on_exit(id, ctx)
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        self.inner.on_close(id, ctx)
+    }
+
+    fn on_id_change(&self, old: &Id, new: &Id, ctx: Context<'_, S>) {
+        self.inner.on_id_change(old, new, ctx)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.inner.max_level_hint()
+    }
+
+    #[doc(hidden)]
+    unsafe fn downcast_raw(&self, id: TypeId) -> Option<*const ()> {
+        if id == TypeId::of::<Self>() {
+            return Some(self as *const _ as *const ());
+        }
+        // lets per-layer filters & other downcasts reach the inner layer
+        unsafe { self.inner.downcast_raw(id) }
+    }
+}
+
+/// Wraps a [MakeWriter] to scrub everything that's written for the selected targets. See the
+/// [module docs](self).
+#[derive(Debug, Clone)]
+pub struct ScrubWriter<M> {
+    inner: M,
+    scrubber: Scrubber,
+    targets: Vec<String>,
+}
+
+impl<M> ScrubWriter<M> {
+    /// Scrubs everything with the built-in patterns of [Scrubber]
+    pub fn new(inner: M) -> Self {
+        Self {
+            inner,
+            scrubber: Scrubber::new(),
+            targets: vec![],
+        }
+    }
+
+    /// Use a different scrubber, e.g. with custom patterns
+    pub fn scrubber(mut self, scrubber: Scrubber) -> Self {
+        self.scrubber = scrubber;
+        self
+    }
+
+    /// Only scrub output for targets starting with one of these prefixes, e.g. `my_app::users`.
+    /// Output for every target is scrubbed by default.
+    pub fn targets<I>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.targets.extend(prefixes.into_iter().map(Into::into));
+        self
+    }
+
+    fn is_selected(&self, target: &str) -> bool {
+        self.targets.is_empty()
+            || self
+                .targets
+                .iter()
+                .any(|prefix| target.starts_with(prefix.as_str()))
+    }
+}
+
+impl<'a, M> MakeWriter<'a> for ScrubWriter<M>
+where
+    M: MakeWriter<'a>,
+{
+    type Writer = Scrubbed<'a, M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        Scrubbed {
+            inner: self.inner.make_writer(),
+            scrubber: Some(&self.scrubber),
+        }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        Scrubbed {
+            inner: self.inner.make_writer_for(meta),
+            scrubber: self.is_selected(meta.target()).then_some(&self.scrubber),
+        }
+    }
+}
+
+/// A writer that scrubs everything written to it. See [ScrubWriter].
+#[derive(Debug)]
+pub struct Scrubbed<'a, W> {
+    inner: W,
+    scrubber: Option<&'a Scrubber>,
+}
+
+impl<W> io::Write for Scrubbed<'_, W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.scrubber {
+            Some(scrubber) => {
+                let text = String::from_utf8_lossy(buf).into_owned();
+                self.inner.write_all(scrubber.scrub(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            None => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
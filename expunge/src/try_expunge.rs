@@ -0,0 +1,77 @@
+use crate::Expunged;
+
+/// Trait for expunging values where some fields can fail to be expunged, e.g. because they're
+/// encrypted with a key that may be missing. Unlike [Expunge](crate::Expunge), the value is
+/// returned as an error rather than being left unexpunged.
+///
+/// ```rust
+/// use expunge::{Expunged, TryExpunge};
+///
+/// struct Card {
+///     number: String,
+/// }
+///
+/// impl TryExpunge for Card {
+///     type Error = &'static str;
+///
+///     fn try_expunge(self) -> Result<Self, Self::Error> {
+///         match self.number.len() {
+///             16 => Ok(Card { number: format!("tok_{}", &self.number[12..]) }),
+///             _ => Err("not a card number"),
+///         }
+///     }
+/// }
+///
+/// let card = Expunged::try_new(Card { number: "4242424242424242".to_string() }).unwrap();
+/// assert_eq!("tok_4242", card.number);
+///
+/// assert!(Expunged::try_new(Card { number: "42".to_string() }).is_err());
+/// ```
+pub trait TryExpunge: Sized {
+    type Error;
+
+    fn try_expunge(self) -> Result<Self, Self::Error>;
+}
+
+impl<T> TryExpunge for Option<T>
+where
+    T: TryExpunge,
+{
+    type Error = T::Error;
+
+    fn try_expunge(self) -> Result<Self, Self::Error> {
+        self.map(TryExpunge::try_expunge).transpose()
+    }
+}
+
+impl<T> TryExpunge for Box<T>
+where
+    T: TryExpunge,
+{
+    type Error = T::Error;
+
+    fn try_expunge(self) -> Result<Self, Self::Error> {
+        (*self).try_expunge().map(Box::new)
+    }
+}
+
+impl<T> TryExpunge for Vec<T>
+where
+    T: TryExpunge,
+{
+    type Error = T::Error;
+
+    fn try_expunge(self) -> Result<Self, Self::Error> {
+        self.into_iter().map(TryExpunge::try_expunge).collect()
+    }
+}
+
+impl<T> Expunged<T>
+where
+    T: TryExpunge,
+{
+    /// Expunges the value with [TryExpunge], or returns the error if it couldn't be expunged
+    pub fn try_new(value: T) -> Result<Self, T::Error> {
+        value.try_expunge().map(Expunged)
+    }
+}
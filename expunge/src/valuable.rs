@@ -0,0 +1,36 @@
+use super::Expunge;
+use ::valuable::Value;
+
+/// Primitive values are expunged as their default values, the same as their std counterparts.
+///
+/// Borrowed values (strings & paths) are replaced with empty values. Errors and structured values
+/// (lists, maps, structs, enums & tuples) are opaque trait objects which cannot be rebuilt, so
+/// they are replaced entirely with [Value::Unit].
+impl<'a> Expunge for Value<'a> {
+    fn expunge(self) -> Self
+    where
+        Self: Sized,
+    {
+        match self {
+            Value::Bool(_) => Value::Bool(false),
+            Value::Char(_) => Value::Char(char::default()),
+            Value::F32(_) => Value::F32(0.0),
+            Value::F64(_) => Value::F64(0.0),
+            Value::I8(_) => Value::I8(0),
+            Value::I16(_) => Value::I16(0),
+            Value::I32(_) => Value::I32(0),
+            Value::I64(_) => Value::I64(0),
+            Value::I128(_) => Value::I128(0),
+            Value::Isize(_) => Value::Isize(0),
+            Value::U8(_) => Value::U8(0),
+            Value::U16(_) => Value::U16(0),
+            Value::U32(_) => Value::U32(0),
+            Value::U64(_) => Value::U64(0),
+            Value::U128(_) => Value::U128(0),
+            Value::Usize(_) => Value::Usize(0),
+            Value::String(_) => Value::String(""),
+            Value::Path(_) => Value::Path(std::path::Path::new("")),
+            _ => Value::Unit,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use expunge::Expunge;
+use std::marker::PhantomData;
+
+// `T` is only used by a skipped field, so it doesn't need to implement `Expunge`
+#[derive(Expunge)]
+struct Tagged<T> {
+    #[expunge(skip)]
+    tag: PhantomData<T>,
+    name: String,
+}
+
+trait Secret {
+    type Value;
+}
+
+// the inferred bound would be `S: Expunge`, rather than the associated type
+#[derive(Expunge)]
+#[expunge(bound = "S::Value: Expunge")]
+struct Stored<S: Secret> {
+    value: S::Value,
+}
+
+struct Password;
+
+impl Secret for Password {
+    type Value = String;
+}
+
+#[test]
+fn bound() {
+    struct NotExpunge;
+
+    let tagged = Tagged::<NotExpunge> {
+        tag: PhantomData,
+        name: "Jane".to_string(),
+    };
+    assert_eq!("", tagged.expunge().name);
+
+    let stored = Stored::<Password> {
+        value: "hunter2".to_string(),
+    };
+    assert_eq!("", stored.expunge().value);
+}
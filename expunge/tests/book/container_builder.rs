@@ -0,0 +1,45 @@
+use expunge::{Error, Expunge};
+
+#[derive(Debug, Expunge)]
+#[expunge(allow_debug, builder)]
+struct LoginEvent {
+    #[expunge(skip)]
+    user_id: u64,
+    #[expunge(keep_last = 4)]
+    phone: String,
+    ip: Option<String>,
+}
+
+#[test]
+fn builder() {
+    let event = LoginEvent::builder()
+        .user_id(101u64)
+        .phone("07700900123")
+        .build()
+        .unwrap();
+    assert_eq!(101, event.user_id);
+    assert_eq!("*******0123", event.phone);
+    assert_eq!(None, event.ip);
+
+    let event = LoginEvent::builder()
+        .user_id(101u64)
+        .phone("07700900123")
+        .ip("127.0.0.1".to_string())
+        .build()
+        .unwrap()
+        .into_inner();
+    assert_eq!(Some("".to_string()), event.ip);
+
+    let err = LoginEvent::builder().user_id(101u64).build().unwrap_err();
+    assert!(matches!(
+        err,
+        Error::MissingField {
+            container: "LoginEvent",
+            field: "phone"
+        }
+    ));
+    assert_eq!(
+        "`LoginEvent.phone` must be set before building",
+        err.to_string()
+    );
+}
@@ -0,0 +1,46 @@
+use expunge::Expunge;
+
+const EU: &[&str] = &["DE", "FR", "IE", "NL"];
+
+fn clear_eu_address(mut user: User) -> User {
+    if EU.contains(&user.country.as_str()) {
+        user.address.clear();
+    }
+    user
+}
+
+#[derive(Debug, PartialEq, Eq, Expunge)]
+#[expunge(container_with = clear_eu_address, allow_debug)]
+struct User {
+    email: String,
+    #[expunge(skip)]
+    country: String,
+    #[expunge(skip)]
+    address: String,
+}
+
+#[test]
+fn container_container_with() {
+    let user = |country: &str| User {
+        email: "jane@example.com".to_string(),
+        country: country.to_string(),
+        address: "1 Main St".to_string(),
+    };
+
+    assert_eq!(
+        User {
+            email: "".to_string(),
+            country: "DE".to_string(),
+            address: "".to_string(),
+        },
+        user("DE").expunge()
+    );
+    assert_eq!(
+        User {
+            email: "".to_string(),
+            country: "US".to_string(),
+            address: "1 Main St".to_string(),
+        },
+        user("US").expunge()
+    );
+}
@@ -0,0 +1,26 @@
+use expunge::Expunge;
+
+// adding a `secret` field without `hash` or `encrypt` is a compile error
+#[derive(Expunge)]
+#[expunge(db_write_safe)]
+struct NewUser {
+    #[expunge(class = pii)]
+    email: String,
+    #[expunge(class = secret, hash)]
+    password: String,
+    #[expunge(class = secret, encrypt)]
+    recovery_code: String,
+}
+
+#[test]
+fn db_write_safe() {
+    let user = NewUser {
+        email: "jane@example.com".to_string(),
+        password: "hunter2".to_string(),
+        recovery_code: "7F3K-92LD".to_string(),
+    }
+    .expunge();
+    assert_eq!("", user.email);
+    assert_eq!(64, user.password.len());
+    assert_ne!("7F3K-92LD", user.recovery_code);
+}
@@ -0,0 +1,48 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(deny_unannotated)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge]
+    email: String,
+    #[expunge(keep_last = 4)]
+    phone: String,
+}
+
+#[derive(Expunge)]
+#[expunge(deny_unannotated)]
+enum Event {
+    // a variant's attribute covers each of its fields
+    #[expunge(skip)]
+    Logout(u64),
+    Login {
+        #[expunge(skip)]
+        id: u64,
+        #[expunge(as = "<ip>".to_string())]
+        ip: String,
+    },
+}
+
+#[test]
+fn deny_unannotated() {
+    let user = User {
+        id: 101,
+        email: "jane@example.com".to_string(),
+        phone: "07700900123".to_string(),
+    }
+    .expunge();
+    assert_eq!(101, user.id);
+    assert_eq!("", user.email);
+    assert_eq!("*******0123", user.phone);
+
+    let Event::Login { ip, .. } = Event::Login {
+        id: 101,
+        ip: "127.0.0.1".to_string(),
+    }
+    .expunge() else {
+        unreachable!()
+    };
+    assert_eq!("<ip>", ip);
+}
@@ -0,0 +1,44 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(error)]
+enum LoginError {
+    #[expunge(error = "no user with email {email}")]
+    UnknownUser { email: String },
+    #[expunge(error = "wrong password for user {0}")]
+    WrongPassword(#[expunge(skip)] u64, String),
+    #[expunge(error = "couldn't load user {id}")]
+    Load {
+        #[expunge(skip)]
+        id: u64,
+        source: DbError,
+    },
+}
+
+#[derive(Expunge)]
+#[expunge(error = "query failed: {query}")]
+struct DbError {
+    query: String,
+}
+
+#[test]
+fn container_error() {
+    let err = LoginError::UnknownUser {
+        email: "jane@example.com".to_string(),
+    };
+    assert_eq!("no user with email <expunged>", err.to_string());
+
+    let err = LoginError::WrongPassword(101, "hunter2".to_string());
+    assert_eq!("wrong password for user 101", err.to_string());
+
+    let err = LoginError::Load {
+        id: 101,
+        source: DbError {
+            query: "SELECT * FROM users WHERE email = 'jane@example.com'".to_string(),
+        },
+    };
+    assert_eq!("couldn't load user 101", err.to_string());
+
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!("query failed: <expunged>", source.to_string());
+}
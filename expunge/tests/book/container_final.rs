@@ -0,0 +1,10 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(as = "<redacted>".to_string(), final)]
+struct ConnectionInfo {
+    username: String,
+    password: String,
+    // #[expunge(skip)] <- this would be a compile error
+    host: String,
+}
@@ -0,0 +1,30 @@
+use expunge::Expunge;
+
+#[derive(Clone, Expunge)]
+#[expunge(in_place)]
+struct LogRecord {
+    #[expunge(skip)]
+    level: String,
+    message: String,
+    tags: Vec<String>,
+    #[expunge(as = "<user>".to_string())]
+    user: String,
+}
+
+#[test]
+fn in_place() {
+    let record = LogRecord {
+        level: "info".to_string(),
+        message: "signed in from 203.0.113.7".to_string(),
+        tags: vec!["web".to_string()],
+        user: "jane".to_string(),
+    };
+
+    let expunged = record.expunge();
+    assert_eq!("info", expunged.level);
+    assert_eq!("", expunged.message);
+    // the allocation is kept, so it can be reused
+    assert!(expunged.message.capacity() > 0);
+    assert_eq!(vec![String::new()], expunged.tags);
+    assert_eq!("<user>", expunged.user);
+}
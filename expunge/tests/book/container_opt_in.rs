@@ -0,0 +1,26 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(opt_in, as = "<redacted>".to_string())]
+struct Order {
+    // left as-is
+    id: String,
+    sku: String,
+    // expunged with the container's transformation
+    #[expunge]
+    email: String,
+    #[expunge(keep_last = 4)]
+    card_number: String,
+}
+
+#[derive(Expunge)]
+#[expunge(opt_in)]
+enum Event {
+    Viewed {
+        // left as-is
+        sku: String,
+    },
+    // annotating the variant opts in each of its fields
+    #[expunge(all)]
+    Purchased { email: String },
+}
@@ -0,0 +1,25 @@
+use expunge::{reject::Unredacted, Expunge};
+use serde::Deserialize;
+
+#[derive(Expunge, Deserialize)]
+#[expunge(reject_redacted_input)]
+struct UpdateUser {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge(as = "<redacted>".to_string())]
+    email: String,
+}
+
+#[test]
+fn reject_redacted_input() {
+    // e.g. a request body that was copied from a log
+    let body = r#"{"id":7,"email":"<redacted>"}"#;
+
+    let err = serde_json::from_str::<Unredacted<UpdateUser>>(body)
+        .err()
+        .unwrap();
+    assert_eq!(
+        "`email` is the redaction placeholder `<redacted>`",
+        err.to_string()
+    );
+}
@@ -0,0 +1,45 @@
+use expunge::Expunge;
+
+// e.g. a type from another crate, which can't be annotated
+mod billing {
+    #[derive(Debug, PartialEq)]
+    pub struct Card {
+        pub number: String,
+        pub holder: String,
+    }
+}
+
+#[derive(Expunge)]
+#[expunge(remote = "billing::Card")]
+struct CardDef {
+    #[expunge(keep_last = 4)]
+    number: String,
+    holder: String,
+}
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(PartialEq, Debug), expunge(allow_debug))]
+struct Customer {
+    #[expunge(with = CardDef::expunge)]
+    card: billing::Card,
+}
+
+#[test]
+fn container_remote() {
+    let customer = Customer {
+        card: billing::Card {
+            number: "4242424242424242".to_string(),
+            holder: "Jane Doe".to_string(),
+        },
+    };
+
+    assert_eq!(
+        Customer {
+            card: billing::Card {
+                number: "************4242".to_string(),
+                holder: "".to_string(),
+            },
+        },
+        customer.expunge()
+    );
+}
@@ -0,0 +1,40 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(skip_if = self.is_test_account)]
+struct Account {
+    #[expunge(skip)]
+    is_test_account: bool,
+    email: String,
+}
+
+#[derive(Expunge)]
+#[expunge(skip_if = cfg!(debug_assertions))]
+struct DebugOnly {
+    email: String,
+}
+
+#[test]
+fn skip_if() {
+    let test_account = Account {
+        is_test_account: true,
+        email: "test@example.com".to_string(),
+    };
+    assert_eq!("test@example.com", test_account.expunge().email);
+
+    let account = Account {
+        is_test_account: false,
+        email: "jane@example.com".to_string(),
+    };
+    assert_eq!("", account.expunge().email);
+
+    let debug_only = DebugOnly {
+        email: "jane@example.com".to_string(),
+    }
+    .expunge();
+    if cfg!(debug_assertions) {
+        assert_eq!("jane@example.com", debug_only.email);
+    } else {
+        assert_eq!("", debug_only.email);
+    }
+}
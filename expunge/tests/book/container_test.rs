@@ -0,0 +1,45 @@
+use expunge::Expunge;
+
+#[derive(Clone, Expunge)]
+#[expunge(test)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    #[expunge(keep_last = 4)]
+    phone: String,
+    email: String,
+    login: Login,
+}
+
+#[derive(Clone, Debug, Expunge)]
+#[expunge(allow_debug, test)]
+enum Login {
+    Password(String),
+    Sso {
+        provider: String,
+        #[expunge(as = "<token>".to_string())]
+        token: String,
+    },
+}
+
+// the default instance holds realistic values, since fields that are already equal to their
+// expunged value can't be told apart from leaks
+impl Default for User {
+    fn default() -> Self {
+        Self {
+            id: 101,
+            phone: "+44 20 7946 0958".to_string(),
+            email: "jane@example.com".to_string(),
+            login: Login::default(),
+        }
+    }
+}
+
+impl Default for Login {
+    fn default() -> Self {
+        Self::Sso {
+            provider: "okta".to_string(),
+            token: "eyJhbGciOi".to_string(),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use expunge::Expunge;
+
+#[derive(Clone, Expunge)]
+#[expunge(transparent, as = "<email>".to_string())]
+struct Email(String);
+
+#[derive(Clone, Expunge)]
+#[expunge(transparent)]
+struct UserId {
+    #[expunge(skip)]
+    id: u64,
+}
+
+#[derive(Clone, Expunge)]
+struct User {
+    id: UserId,
+    email: Email,
+}
+
+#[test]
+fn transparent() {
+    let user = User {
+        id: UserId { id: 101 },
+        email: Email("jane@example.com".to_string()),
+    };
+    // printed as the inner value, rather than `UserId { id: 101 }`
+    assert_eq!("101", format!("{:?}", user.id));
+    assert_eq!("<expunged>", format!("{:?}", user.email));
+
+    let expunged = user.expunge();
+    assert_eq!(101, expunged.id.id);
+    assert_eq!("<email>", expunged.email.0);
+}
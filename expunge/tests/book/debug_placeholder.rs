@@ -0,0 +1,32 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(debug_placeholder = "***")]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    email: String,
+}
+
+#[derive(Expunge)]
+#[expunge(debug = "placeholder")]
+struct ApiKey {
+    #[expunge(skip)]
+    name: String,
+    key: String,
+}
+
+#[test]
+fn debug_placeholder() {
+    let user = User {
+        id: 101,
+        email: "jane@example.com".to_string(),
+    };
+    assert_eq!("User { id: 101, email: *** }", format!("{user:?}"));
+
+    let key = ApiKey {
+        name: "ci".to_string(),
+        key: "sk_live_1234".to_string(),
+    };
+    assert_eq!("<expunged>", format!("{key:?}"));
+}
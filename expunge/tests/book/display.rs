@@ -0,0 +1,18 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(display)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    email: String,
+}
+
+#[test]
+fn display() {
+    let user = User {
+        id: 101,
+        email: "jane@example.com".to_string(),
+    };
+    assert_eq!(r#"User { id: 101, email: <expunged> }"#, format!("{user}"));
+}
@@ -0,0 +1,27 @@
+use expunge::Expunge;
+use std::{borrow::Cow, sync::Arc};
+
+const REDACTED: &str = "<redacted>";
+
+#[derive(Expunge)]
+struct LogLine {
+    #[expunge(as_str = REDACTED)]
+    user: Cow<'static, str>,
+    #[expunge(as_str = "<session>")]
+    session: Arc<str>,
+    #[expunge(skip)]
+    message: String,
+}
+
+#[test]
+fn as_str() {
+    let line = LogLine {
+        user: Cow::Owned("jane".to_string()),
+        session: Arc::from("c0ffee"),
+        message: "logged in".to_string(),
+    }
+    .expunge();
+
+    assert!(matches!(line.user, Cow::Borrowed("<redacted>")));
+    assert_eq!("<session>", &*line.session);
+}
@@ -0,0 +1,32 @@
+use expunge::{
+    crypto::{self, Key},
+    Expunge,
+};
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
+struct Order {
+    #[expunge(encrypt)]
+    address: String,
+    #[expunge(skip)]
+    id: u64,
+}
+
+#[test]
+fn field_encrypt() {
+    crypto::set_key(Key::new([7; 32]));
+
+    let order = Order {
+        address: "1 Main St".to_string(),
+        id: 101,
+    }
+    .expunge();
+
+    assert_ne!("1 Main St", order.address);
+    assert_eq!(101, order.id);
+    // e.g. by an authorized job
+    assert_eq!(
+        "1 Main St",
+        crypto::decrypt(&order.address, "Order.address").unwrap()
+    );
+}
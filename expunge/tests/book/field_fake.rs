@@ -0,0 +1,26 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct Customer {
+    #[expunge(fake = "name")]
+    name: String,
+    #[expunge(fake = "email")]
+    email: String,
+    #[expunge(fake = "phone")]
+    phone: String,
+}
+
+#[test]
+fn field_fake() {
+    let customer = Customer {
+        name: "Jane Doe".to_string(),
+        email: "jane@example.org".to_string(),
+        phone: "+49 30 1234567".to_string(),
+    }
+    .expunge();
+
+    // e.g. "Alex Smith", "alex.smith42@example.com" & "+1 555-0142"
+    assert_ne!("Jane Doe", customer.name);
+    assert!(customer.email.ends_with("@example.com"));
+    assert!(customer.phone.starts_with("+1 555-01"));
+}
@@ -0,0 +1,52 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
+struct Customer {
+    #[expunge(group = "gdpr")]
+    name: String,
+    #[expunge(group = ["gdpr", "pci"], keep_last = 4)]
+    card_number: String,
+    #[expunge(group = "pci", as = 0)]
+    cvv: u16,
+    country: String,
+}
+
+#[test]
+fn field_group() {
+    let customer = || Customer {
+        name: "Jane Doe".to_string(),
+        card_number: "4111111111111111".to_string(),
+        cvv: 123,
+        country: "DE".to_string(),
+    };
+
+    assert_eq!(
+        Customer {
+            name: "".to_string(),
+            card_number: "************1111".to_string(),
+            cvv: 123,
+            country: "DE".to_string(),
+        },
+        customer().expunge_group("gdpr")
+    );
+    assert_eq!(
+        Customer {
+            name: "Jane Doe".to_string(),
+            card_number: "************1111".to_string(),
+            cvv: 0,
+            country: "DE".to_string(),
+        },
+        customer().expunge_group("pci")
+    );
+    // `expunge` ignores groups
+    assert_eq!(
+        Customer {
+            name: "".to_string(),
+            card_number: "************1111".to_string(),
+            cvv: 0,
+            country: "".to_string(),
+        },
+        customer().expunge()
+    );
+}
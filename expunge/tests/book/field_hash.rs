@@ -0,0 +1,36 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct Customer {
+    // expunged after `email` has been hashed with the original value
+    tenant_id: u64,
+    #[expunge(hash(salt_field = tenant_id))]
+    email: String,
+    #[expunge(hash)]
+    username: String,
+}
+
+#[test]
+fn hash() {
+    let customer = |tenant_id| Customer {
+        tenant_id,
+        email: "jane@example.com".to_string(),
+        username: "jane".to_string(),
+    };
+
+    let first = customer(1).expunge();
+    let second = customer(2).expunge();
+
+    assert_eq!(0, first.tenant_id);
+    assert_eq!(64, first.email.len());
+    assert_ne!(
+        first.email, second.email,
+        "the same email should produce different digests for different tenants"
+    );
+    assert_eq!(first.username, second.username);
+    assert_eq!(
+        first.email,
+        customer(1).expunge().email,
+        "digests should be deterministic"
+    );
+}
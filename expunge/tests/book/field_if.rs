@@ -0,0 +1,33 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Debug), expunge(allow_debug))]
+struct Patient {
+    #[expunge(skip)]
+    age: u8,
+    #[expunge(if = self.age < 18)]
+    name: String,
+    #[expunge(unless = self.age < 18)]
+    insurance_number: String,
+}
+
+#[test]
+fn conditional() {
+    let minor = Patient {
+        age: 12,
+        name: "Tim".to_string(),
+        insurance_number: "AB123456C".to_string(),
+    }
+    .expunge();
+    assert_eq!("", minor.name);
+    assert_eq!("AB123456C", minor.insurance_number);
+
+    let adult = Patient {
+        age: 40,
+        name: "Jane".to_string(),
+        insurance_number: "AB123456C".to_string(),
+    }
+    .expunge();
+    assert_eq!("Jane", adult.name);
+    assert_eq!("", adult.insurance_number);
+}
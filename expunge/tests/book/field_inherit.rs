@@ -0,0 +1,27 @@
+use expunge::Expunge;
+
+fn redact_first_char(mut s: String) -> String {
+    s.replace_range(0..1, "*");
+    s
+}
+
+#[derive(Expunge)]
+#[expunge(with = sha256::digest)]
+struct User {
+    username: String,
+    // the first char is redacted, then the result is hashed
+    #[expunge(with = redact_first_char, inherit)]
+    last_name: String,
+}
+
+#[test]
+fn inherit() {
+    let user = User {
+        username: "some_user_123".to_string(),
+        last_name: "Doe".to_string(),
+    };
+
+    let expunged = user.expunge();
+    assert_eq!(sha256::digest("some_user_123"), expunged.username);
+    assert_eq!(sha256::digest("*oe"), expunged.last_name);
+}
@@ -0,0 +1,21 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct Payment {
+    #[expunge(keep_first = 2)]
+    cardholder: String,
+    #[expunge(keep_last = 4)]
+    card_number: String,
+}
+
+#[test]
+fn keep() {
+    let payment = Payment {
+        cardholder: "Jane Doe".to_string(),
+        card_number: "4111111111111111".to_string(),
+    };
+
+    let expunged = payment.expunge();
+    assert_eq!("Ja******", expunged.cardholder);
+    assert_eq!("************1111", expunged.card_number);
+}
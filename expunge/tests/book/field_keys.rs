@@ -0,0 +1,32 @@
+use expunge::Expunge;
+use std::collections::HashMap;
+
+#[derive(Expunge)]
+struct Activity {
+    // only the values are expunged by default
+    by_region: HashMap<String, String>,
+    #[expunge(keys)]
+    by_email: HashMap<String, u32>,
+    #[expunge(entries)]
+    notes_by_user: HashMap<String, String>,
+}
+
+#[test]
+fn keys() {
+    let activity = Activity {
+        by_region: HashMap::from([("eu".to_string(), "jane".to_string())]),
+        by_email: HashMap::from([("jane@example.com".to_string(), 3)]),
+        notes_by_user: HashMap::from([("jane".to_string(), "vip".to_string())]),
+    }
+    .expunge();
+
+    assert_eq!(
+        HashMap::from([("eu".to_string(), "".to_string())]),
+        activity.by_region
+    );
+    assert_eq!(HashMap::from([("".to_string(), 3)]), activity.by_email);
+    assert_eq!(
+        HashMap::from([("".to_string(), "".to_string())]),
+        activity.notes_by_user
+    );
+}
@@ -0,0 +1,22 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct Upload {
+    #[expunge(max_len = 1024, with = expunge::utils::scrub)]
+    body: String,
+}
+
+#[test]
+fn max_len() {
+    let upload = Upload {
+        body: "jane@example.com".repeat(1024 * 1024),
+    }
+    .expunge();
+    assert_eq!("<expunged: 16MB blob>", upload.body);
+
+    let upload = Upload {
+        body: "from jane@example.com".to_string(),
+    }
+    .expunge();
+    assert_eq!("from <email>", upload.body);
+}
@@ -0,0 +1,30 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Debug), expunge(allow_debug))]
+struct Profile {
+    #[expunge(none)]
+    nickname: Option<String>,
+    #[expunge(clear)]
+    tags: Vec<String>,
+    #[expunge(clear)]
+    aliases: Option<Vec<String>>, // Some(["a", "b"]) -> Some([])
+    #[expunge(none)]
+    previous_aliases: Option<Vec<String>>, // Some(["c"]) -> None
+}
+
+#[test]
+fn none_clear() {
+    let profile = Profile {
+        nickname: Some("Bobby".to_string()),
+        tags: vec!["admin".to_string()],
+        aliases: Some(vec!["a".to_string(), "b".to_string()]),
+        previous_aliases: Some(vec!["c".to_string()]),
+    }
+    .expunge();
+
+    assert_eq!(None, profile.nickname);
+    assert!(profile.tags.is_empty());
+    assert_eq!(Some(vec![]), profile.aliases);
+    assert_eq!(None, profile.previous_aliases);
+}
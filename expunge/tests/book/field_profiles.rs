@@ -0,0 +1,36 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(profile(prod = hash, staging = mask(keep_last = 4), debug = skip))]
+    email: String,
+    #[expunge(skip, profile(prod = expunge))]
+    last_login: String,
+}
+
+#[test]
+fn profiles() {
+    let user = || User {
+        email: "jane@example.com".to_string(),
+        last_login: "2024-01-01".to_string(),
+    };
+
+    expunge::profile::set("prod");
+    let expunged = user().expunge();
+    assert_eq!(64, expunged.email.len());
+    assert_eq!("", expunged.last_login);
+
+    expunge::profile::set("staging");
+    let expunged = user().expunge();
+    assert_eq!("************.com", expunged.email);
+    assert_eq!("2024-01-01", expunged.last_login);
+
+    expunge::profile::set("debug");
+    assert_eq!("jane@example.com", user().expunge().email);
+
+    // without a profile, fields are expunged by their other attributes
+    expunge::profile::clear();
+    let expunged = user().expunge();
+    assert_eq!("", expunged.email);
+    assert_eq!("2024-01-01", expunged.last_login);
+}
@@ -0,0 +1,25 @@
+use expunge::Expunge;
+use secrecy::{ExposeSecret, SecretString};
+
+#[derive(Expunge)]
+struct Credentials {
+    username: String,
+    // replaced with "<expunged>" & zeroized
+    #[expunge(secret)]
+    password: String,
+    // `secrecy` types are left as-is, since they can't be exposed by accident
+    token: SecretString,
+}
+
+#[test]
+fn secret() {
+    let credentials = Credentials {
+        username: "jane".to_string(),
+        password: "hunter2".to_string(),
+        token: SecretString::new("sk_live_1234".to_string()),
+    }
+    .expunge();
+    assert_eq!("", credentials.username);
+    assert_eq!("<expunged>", credentials.password);
+    assert_eq!("sk_live_1234", credentials.token.expose_secret());
+}
@@ -0,0 +1,21 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    // expunged, but printed as-is by `Debug`
+    #[expunge(show_in_debug)]
+    id: u64,
+    name: String,
+}
+
+#[test]
+fn show_in_debug() {
+    let user = User {
+        id: 42,
+        name: "Jane".to_string(),
+    };
+    assert_eq!(r#"User { id: 42, name: <expunged> }"#, format!("{user:?}"));
+
+    let user = user.expunge();
+    assert_eq!(0, user.id);
+}
@@ -0,0 +1,37 @@
+use expunge::{
+    tokenize::{self, MemoryTokenizer},
+    Expunge,
+};
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
+struct Ticket {
+    #[expunge(tokenize)]
+    email: String,
+    #[expunge(skip)]
+    subject: String,
+}
+
+#[test]
+fn field_tokenize() {
+    tokenize::register(MemoryTokenizer::new());
+    tokenize::on_detokenize(|event| println!("{} resolved {}", event.requested_by, event.token));
+
+    let ticket = Ticket {
+        email: "jane@example.com".to_string(),
+        subject: "Can't log in".to_string(),
+    }
+    .expunge();
+
+    assert_eq!(
+        Ticket {
+            email: "tok_1".to_string(),
+            subject: "Can't log in".to_string(),
+        },
+        ticket
+    );
+    assert_eq!(
+        Some("jane@example.com".to_string()),
+        tokenize::detokenize(&ticket.email, "support@example.com", "ticket #42").unwrap()
+    );
+}
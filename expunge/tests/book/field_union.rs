@@ -0,0 +1,16 @@
+use expunge::Expunge;
+
+#[repr(C)]
+#[derive(Clone, Copy, Expunge)]
+union Credential {
+    pin: u32,
+    #[expunge(as = [0; 4])]
+    key: [u8; 4],
+}
+
+#[test]
+fn union() {
+    let credential = Credential { pin: 1234 }.expunge();
+    assert_eq!([0; 4], unsafe { credential.key });
+    assert_eq!("Credential { .. }", format!("{credential:?}"));
+}
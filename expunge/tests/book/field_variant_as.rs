@@ -0,0 +1,24 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
+enum Diagnosis {
+    #[expunge(variant_as = Other)]
+    Hiv {
+        since: u16,
+    },
+    #[expunge(variant_as = Other)]
+    Cancer(String),
+    Healthy,
+    Other,
+}
+
+#[test]
+fn field_variant_as() {
+    assert_eq!(Diagnosis::Other, Diagnosis::Hiv { since: 2019 }.expunge());
+    assert_eq!(
+        Diagnosis::Other,
+        Diagnosis::Cancer("stage 2".to_string()).expunge()
+    );
+    assert_eq!(Diagnosis::Healthy, Diagnosis::Healthy.expunge());
+}
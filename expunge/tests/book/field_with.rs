@@ -9,6 +9,19 @@ fn char_count_of(s: String) -> String {
     s.len().to_string()
 }
 
+fn keep_last<const N: usize>(s: String) -> String {
+    expunge::utils::mask(s, 0, N)
+}
+
+fn trim(s: String) -> String {
+    s.trim().to_string()
+}
+
+fn truncate_8(mut s: String) -> String {
+    s.truncate(8);
+    s
+}
+
 #[derive(Expunge)]
 #[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
 struct User {
@@ -19,6 +32,12 @@ struct User {
     last_name: String,
     #[expunge(with = sha256::digest)]
     password: String,
+    #[expunge(with = |s: String| s.to_uppercase())]
+    city: String,
+    #[expunge(with = keep_last::<2>)]
+    postcode: String,
+    #[expunge(with = [trim, sha256::digest, truncate_8])]
+    api_key: String,
 }
 
 #[test]
@@ -28,6 +47,9 @@ fn field_with() {
         first_name: "Jane".to_string(),
         last_name: "Doe".to_string(),
         password: "password123".to_string(),
+        city: "Berlin".to_string(),
+        postcode: "10115".to_string(),
+        api_key: " sk_live_123\n".to_string(),
     };
 
     assert_eq!(
@@ -37,6 +59,9 @@ fn field_with() {
             last_name: "*oe".to_string(),
             password: "ef92b778bafe771e89245b89ecbc08a44a4e166c06659911881f383d4473e94f"
                 .to_string(),
+            city: "BERLIN".to_string(),
+            postcode: "***15".to_string(),
+            api_key: "46e8faff".to_string(),
         },
         user.expunge()
     );
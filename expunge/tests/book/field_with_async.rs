@@ -0,0 +1,31 @@
+use expunge::{Expunge, ExpungeAsync};
+
+/// Stands in for a request to a tokenization service
+async fn tokenize(card_number: String) -> String {
+    format!("tok_{}", &card_number[card_number.len() - 4..])
+}
+
+#[derive(Expunge)]
+#[cfg_attr(test, derive(Eq, PartialEq, Debug), expunge(allow_debug))]
+struct Payment {
+    #[expunge(with_async = tokenize)]
+    card_number: String,
+    #[expunge(skip)]
+    amount: u64,
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn field_with_async() {
+    let payment = Payment {
+        card_number: "4242424242424242".to_string(),
+        amount: 100,
+    };
+
+    assert_eq!(
+        Payment {
+            card_number: "tok_4242".to_string(),
+            amount: 100,
+        },
+        payment.expunge_async().await
+    );
+}
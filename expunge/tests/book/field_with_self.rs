@@ -0,0 +1,38 @@
+use expunge::Expunge;
+
+#[derive(Clone, PartialEq)]
+enum AccountType {
+    Personal,
+    Business,
+}
+
+fn mask_account_number(number: String, account: &Account) -> String {
+    match account.account_type {
+        // business account numbers are public
+        AccountType::Business => number,
+        AccountType::Personal => expunge::utils::mask(number, 0, 4),
+    }
+}
+
+#[derive(Expunge)]
+struct Account {
+    #[expunge(skip)]
+    account_type: AccountType,
+    #[expunge(with_self = mask_account_number)]
+    number: String,
+}
+
+#[test]
+fn with_self() {
+    let personal = Account {
+        account_type: AccountType::Personal,
+        number: "12345678".to_string(),
+    };
+    assert_eq!("****5678", personal.expunge().number);
+
+    let business = Account {
+        account_type: AccountType::Business,
+        number: "12345678".to_string(),
+    };
+    assert_eq!("12345678", business.expunge().number);
+}
@@ -1,8 +1,11 @@
 use expunge::Expunge;
+use zeroize::Zeroizing;
 
 #[derive(Expunge)]
 struct UserLogin {
     username: String,
     #[expunge(as = "<redacted>".to_string(), zeroize)]
     password: String, // password will be scrubbed from memory after expunging
+    #[expunge(with = |email| sha256::digest(&*Zeroizing::new(email)), zeroize)]
+    email: String, // the email & the copy that's hashed will be scrubbed from memory
 }
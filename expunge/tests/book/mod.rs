@@ -2,13 +2,49 @@
 
 mod allow_debug;
 mod container_as;
+mod container_bound;
+mod container_builder;
+mod container_container_with;
+mod container_db_write_safe;
 mod container_default;
+mod container_deny_unannotated;
+mod container_error;
+mod container_final;
+mod container_in_place;
+mod container_opt_in;
+mod container_reject_redacted_input;
+mod container_remote;
+mod container_skip_if;
+mod container_test;
+mod container_transparent;
 mod container_with;
+mod debug_placeholder;
+mod display;
 
 mod field_as;
+mod field_as_str;
 mod field_default;
+mod field_encrypt;
+mod field_fake;
+mod field_group;
+mod field_hash;
+mod field_if;
+mod field_inherit;
+mod field_keep;
+mod field_keys;
+mod field_max_len;
+mod field_none_clear;
+mod field_profiles;
+mod field_secret;
+mod field_show_in_debug;
 mod field_skip;
+mod field_tokenize;
+mod field_union;
+mod field_variant_as;
 mod field_with;
+mod field_with_async;
+mod field_with_self;
 mod field_zeroize;
 
 mod slog;
+mod slog_kv;
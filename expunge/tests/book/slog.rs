@@ -4,7 +4,7 @@ use slog::{info, o};
 use slog::{Drain, Logger};
 use std::sync::Mutex;
 
-#[derive(Clone, Expunge, Deserialize, Serialize, PartialEq, Eq)] // must implement Serialize
+#[derive(Expunge, Deserialize, Serialize, PartialEq, Eq)] // must implement Serialize
 #[expunge(slog)]
 #[serde(rename_all = "snake_case")]
 enum LocationType {
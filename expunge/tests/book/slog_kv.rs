@@ -0,0 +1,32 @@
+use expunge::Expunge;
+use serde::Serialize;
+use slog::{info, o};
+use slog::{Drain, Logger};
+use std::sync::Mutex;
+
+#[derive(Expunge, Serialize)]
+#[expunge(slog(kv))]
+struct Request {
+    #[expunge(skip)]
+    method: String,
+    #[expunge(skip)]
+    path: String,
+    #[expunge(as = "<expunged>".to_string())]
+    authorization: String,
+}
+
+fn main() {
+    let buf = vec![];
+    let drain = Mutex::new(slog_json::Json::default(buf)).fuse();
+    let logger = Logger::root(drain, o!());
+
+    let request = Request {
+        method: "GET".to_string(),
+        path: "/orders".to_string(),
+        authorization: "Bearer abc123".to_string(),
+    };
+    // each field is its own key-value pair on the record
+    info!(logger, "request received"; request);
+
+    // {"msg":"request received","level":"INFO","ts":"2024-02-04T12:55:28.627592Z","method":"GET","path":"/orders","authorization":"<expunged>"}
+}
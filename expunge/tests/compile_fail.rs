@@ -0,0 +1,7 @@
+/// Misuse of the derive should fail with stable, readable errors that point at the attribute.
+/// Run with `TRYBUILD=overwrite` to update the expected output after changing an error.
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(all)]
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+enum Event {
+    #[expunge(all, skip)]
+    Login { email: String },
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(as = 0)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(builder)]
+enum Event {
+    Login(String),
+}
+
+fn main() {}
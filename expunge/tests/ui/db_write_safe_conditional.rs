@@ -0,0 +1,12 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(db_write_safe)]
+struct Credential {
+    #[expunge(skip)]
+    rotated: bool,
+    #[expunge(class = secret, hash, if = self.rotated)]
+    password: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(db_write_safe)]
+struct NewUser {
+    #[expunge(class = pii)]
+    email: String,
+    #[expunge(class = secret)]
+    password: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(db_write_safe)]
+enum Credential {
+    Password {
+        #[expunge(class = secret, hash)]
+        hash: String,
+    },
+    ApiKey(#[expunge(class = secret, skip)] String),
+}
+
+fn main() {}
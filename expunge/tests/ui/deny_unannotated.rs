@@ -0,0 +1,11 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(deny_unannotated)]
+struct User {
+    #[expunge(skip)]
+    id: u64,
+    email: String,
+}
+
+fn main() {}
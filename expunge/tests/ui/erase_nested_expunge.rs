@@ -0,0 +1,17 @@
+use std::collections::HashMap;
+
+use expunge::{Erase, Expunge};
+
+#[derive(Expunge)]
+struct Address {
+    #[expunge(keep_last = 3)]
+    postcode: String,
+}
+
+#[derive(Expunge, Erase)]
+struct User {
+    address: Option<Address>,
+    by_key: HashMap<String, Address>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use expunge::{expunge_fn, Expunge};
+
+#[derive(Clone, Expunge)]
+struct User {
+    name: String,
+}
+
+#[expunge_fn]
+#[tracing::instrument]
+fn handle((user, id): (User, u64)) -> String {
+    format!("{} {id}", user.name)
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use expunge::{expunge_fn, Expunge};
+
+#[derive(Clone, Expunge)]
+struct User {
+    name: String,
+}
+
+#[expunge_fn]
+#[tracing::instrument(ret)]
+fn handle(user: User) -> String {
+    user.name
+}
+
+fn main() {}
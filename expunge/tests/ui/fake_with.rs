@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(fake = "email", with = str::to_uppercase)]
+    email: String,
+}
+
+fn main() {}
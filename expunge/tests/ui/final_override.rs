@@ -0,0 +1,10 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(final, as = String::new())]
+struct User {
+    #[expunge(skip)]
+    name: String,
+}
+
+fn main() {}
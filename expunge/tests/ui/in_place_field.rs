@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(in_place)]
+    name: String,
+}
+
+fn main() {}
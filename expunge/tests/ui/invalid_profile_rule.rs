@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(profile(prod = encrypt))]
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(as = String::new(), keep_last = 4)]
+    card_number: String,
+}
+
+fn main() {}
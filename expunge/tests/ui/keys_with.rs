@@ -0,0 +1,10 @@
+use expunge::Expunge;
+use std::collections::HashMap;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(keys, with = |m| m)]
+    attributes: HashMap<String, String>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(skip)]
+    #[expunge(as = String::new())]
+    name: String,
+}
+
+fn main() {}
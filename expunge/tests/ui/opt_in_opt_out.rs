@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(opt_in, opt_out)]
+struct User {
+    email: String,
+}
+
+fn main() {}
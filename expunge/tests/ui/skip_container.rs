@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(skip)]
+struct User {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Clone, Expunge, serde::Serialize)]
+#[expunge(slog)]
+struct Borrowed<'a> {
+    name: &'a str,
+}
+
+fn main() {}
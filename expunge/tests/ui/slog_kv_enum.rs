@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge, serde::Serialize)]
+#[expunge(slog(kv))]
+enum Payment {
+    Card { number: String },
+}
+
+fn main() {}
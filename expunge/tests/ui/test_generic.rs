@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Clone, Default, Expunge)]
+#[expunge(test)]
+struct Wrapper<T> {
+    value: T,
+}
+
+fn main() {}
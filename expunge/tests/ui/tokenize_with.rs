@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(tokenize, with = str::to_uppercase)]
+    name: String,
+}
+
+fn main() {}
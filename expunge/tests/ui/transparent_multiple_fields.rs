@@ -0,0 +1,7 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+#[expunge(transparent)]
+struct Name(String, String);
+
+fn main() {}
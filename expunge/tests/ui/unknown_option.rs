@@ -0,0 +1,15 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(redact)]
+    name: String,
+}
+
+#[derive(Expunge)]
+struct Card {
+    #[expunge(kep_last = 4)]
+    number: String,
+}
+
+fn main() {}
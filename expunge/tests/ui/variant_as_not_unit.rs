@@ -0,0 +1,10 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+enum Diagnosis {
+    #[expunge(variant_as = Other)]
+    Hiv,
+    Other(String),
+}
+
+fn main() {}
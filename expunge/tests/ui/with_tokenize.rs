@@ -0,0 +1,9 @@
+use expunge::Expunge;
+
+#[derive(Expunge)]
+struct User {
+    #[expunge(with = str::to_uppercase, tokenize)]
+    name: String,
+}
+
+fn main() {}
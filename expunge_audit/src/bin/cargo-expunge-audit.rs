@@ -0,0 +1,81 @@
+//! Prints the findings of [expunge_audit] as JSON lines, exiting with status 1 if there are any.
+//!
+//! ```sh
+//! cargo expunge-audit [PATH...] [--pattern PATTERN...] [--no-default-patterns]
+//! ```
+//!
+//! `PATH` defaults to `src`.
+
+use std::process::ExitCode;
+
+use expunge_audit::Auditor;
+
+const USAGE: &str =
+    "usage: cargo expunge-audit [PATH...] [--pattern PATTERN...] [--no-default-patterns]";
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).peekable();
+    // `cargo expunge-audit` passes the subcommand name as the first argument
+    if args.peek().map(String::as_str) == Some("expunge-audit") {
+        args.next();
+    }
+
+    let mut paths = vec![];
+    let mut patterns = vec![];
+    let mut default_patterns = true;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pattern" | "-p" => match args.next() {
+                Some(pattern) => patterns.push(pattern),
+                None => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::from(2);
+                }
+            },
+            "--no-default-patterns" => default_patterns = false,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("unknown option `{arg}`\n{USAGE}");
+                return ExitCode::from(2);
+            }
+            _ => paths.push(arg),
+        }
+    }
+    if paths.is_empty() {
+        paths.push("src".to_string());
+    }
+
+    let auditor = if default_patterns {
+        Auditor::new()
+    } else {
+        Auditor::empty()
+    }
+    .patterns(patterns);
+
+    let mut found = false;
+    for path in paths {
+        let findings = match auditor.audit_path(&path) {
+            Ok(findings) => findings,
+            Err(err) => {
+                eprintln!("{err}");
+                return ExitCode::from(2);
+            }
+        };
+        for finding in findings {
+            found = true;
+            println!(
+                "{}",
+                serde_json::to_string(&finding).expect("findings are serializable")
+            );
+        }
+    }
+
+    if found {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
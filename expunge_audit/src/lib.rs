@@ -0,0 +1,366 @@
+//! Finds struct & enum fields that look sensitive but aren't expunged, so that coverage can be
+//! enforced in CI.
+//!
+//! Source files are parsed with [syn], and a field is reported when its name matches one of the
+//! sensitive patterns, e.g. `password` or `email`, and either:
+//!
+//! - its type doesn't derive `Expunge` ([Kind::NotExpunged])
+//! - it's annotated with `#[expunge(skip)]` ([Kind::Skipped])
+//!
+//! Names are split into words on `_`, and a pattern matches when its words appear in the name in
+//! order, so `user_email` & `email_address` match `email`, while `emails_sent` doesn't.
+//!
+//! ```rust
+//! use expunge_audit::{Auditor, Kind};
+//!
+//! let source = r#"
+//!     struct User {
+//!         name: String,
+//!         user_password: String,
+//!     }
+//! "#;
+//!
+//! let findings = Auditor::new().audit_source("src/user.rs", source)?;
+//! assert_eq!(1, findings.len());
+//! assert_eq!("user_password", findings[0].field);
+//! assert_eq!(Kind::NotExpunged, findings[0].kind);
+//! # Ok::<_, expunge_audit::Error>(())
+//! ```
+//!
+//! The `cargo-expunge-audit` binary prints the findings for a directory as JSON lines, and exits
+//! with a non-zero status if there are any:
+//!
+//! ```sh
+//! cargo expunge-audit src --pattern iban
+//! ```
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use syn::{visit::Visit, Attribute, Fields};
+
+/// Field names matching these are reported by [Auditor::new]
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    "password", "passwd", "secret", "token", "api_key", "ssn", "email",
+];
+
+/// Why a field was reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// The type doesn't derive `Expunge`
+    NotExpunged,
+    /// The field is annotated with `#[expunge(skip)]`
+    Skipped,
+}
+
+/// A field that looks sensitive but isn't expunged
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    /// The struct or enum, with the variant for enums, e.g. `Event::Login`
+    pub container: String,
+    pub field: String,
+    /// The pattern that the field's name matched
+    pub pattern: String,
+    pub kind: Kind,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, syn::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "couldn't read {}: {err}", path.display()),
+            Error::Parse(path, err) => {
+                let start = err.span().start();
+                write!(
+                    f,
+                    "couldn't parse {}:{}:{}: {err}",
+                    path.display(),
+                    start.line,
+                    start.column + 1
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(_, err) => Some(err),
+            Error::Parse(_, err) => Some(err),
+        }
+    }
+}
+
+/// Finds sensitive fields that aren't expunged
+#[derive(Debug, Clone)]
+pub struct Auditor {
+    patterns: Vec<String>,
+}
+
+impl Default for Auditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Auditor {
+    /// An auditor with the [DEFAULT_PATTERNS]
+    pub fn new() -> Self {
+        Self::empty().patterns(DEFAULT_PATTERNS.iter().copied())
+    }
+
+    /// An auditor without any patterns
+    pub fn empty() -> Self {
+        Self { patterns: vec![] }
+    }
+
+    /// Also report fields matching the pattern, e.g. `iban` or `date_of_birth`
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into().to_lowercase());
+        self
+    }
+
+    pub fn patterns<I, S>(self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        patterns.into_iter().fold(self, Self::pattern)
+    }
+
+    /// The pattern that the field's name matches, if any
+    pub fn matches(&self, field: &str) -> Option<&str> {
+        let words: Vec<_> = field
+            .to_lowercase()
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect();
+        self.patterns
+            .iter()
+            .find(|pattern| {
+                let pattern: Vec<_> = pattern.split('_').filter(|w| !w.is_empty()).collect();
+                !pattern.is_empty() && words.windows(pattern.len()).any(|window| window == pattern)
+            })
+            .map(String::as_str)
+    }
+
+    /// Audits the source of a single file. The path is only used for the findings.
+    pub fn audit_source(
+        &self,
+        path: impl AsRef<Path>,
+        source: &str,
+    ) -> Result<Vec<Finding>, Error> {
+        let path = path.as_ref();
+        let file = syn::parse_file(source).map_err(|err| Error::Parse(path.to_path_buf(), err))?;
+        let mut visitor = Visitor {
+            auditor: self,
+            path,
+            findings: vec![],
+        };
+        visitor.visit_file(&file);
+        Ok(visitor.findings)
+    }
+
+    /// Audits every `.rs` file in the directory & its subdirectories, except for `target` and
+    /// hidden directories. A path to a single file is audited as well.
+    pub fn audit_path(&self, path: impl AsRef<Path>) -> Result<Vec<Finding>, Error> {
+        let mut files = vec![];
+        collect_files(path.as_ref(), &mut files)?;
+        files.sort();
+
+        let mut findings = vec![];
+        for file in files {
+            let source = fs::read_to_string(&file).map_err(|err| Error::Io(file.clone(), err))?;
+            findings.extend(self.audit_source(&file, &source)?);
+        }
+        Ok(findings)
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let io_err = |err| Error::Io(path.to_path_buf(), err);
+    if !fs::metadata(path).map_err(io_err)?.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path).map_err(io_err)? {
+        let entry = entry.map_err(io_err)?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type().map_err(io_err)?.is_dir() {
+            if name != "target" && !name.starts_with('.') {
+                collect_files(&path, files)?;
+            }
+        } else if name.ends_with(".rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct Visitor<'a> {
+    auditor: &'a Auditor,
+    path: &'a Path,
+    findings: Vec<Finding>,
+}
+
+impl Visitor<'_> {
+    /// Fields of a skipped variant are skipped, unless they specify their own transformation, and
+    /// so are unannotated fields when `opt_in` is set
+    fn audit_fields(
+        &mut self,
+        container: String,
+        derives_expunge: bool,
+        variant_skipped: bool,
+        opt_in: bool,
+        fields: &Fields,
+    ) {
+        for field in fields {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let name = ident.to_string();
+            let Some(pattern) = self.auditor.matches(&name) else {
+                continue;
+            };
+            let kind = if !derives_expunge {
+                Kind::NotExpunged
+            } else if (variant_skipped && !is_transformed(&field.attrs))
+                || (opt_in && !is_annotated(&field.attrs))
+                || is_skipped(&field.attrs)
+            {
+                Kind::Skipped
+            } else {
+                continue;
+            };
+            let start = ident.span().start();
+            self.findings.push(Finding {
+                file: self.path.to_path_buf(),
+                line: start.line,
+                column: start.column + 1,
+                container: container.clone(),
+                field: name,
+                pattern: pattern.to_string(),
+                kind,
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Visitor<'_> {
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        let derives_expunge = derives_expunge(&item.attrs);
+        let opt_in = has_option(&item.attrs, &["opt_in"]);
+        self.audit_fields(
+            item.ident.to_string(),
+            derives_expunge,
+            false,
+            opt_in,
+            &item.fields,
+        );
+        syn::visit::visit_item_struct(self, item);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast syn::ItemEnum) {
+        let derives_expunge = derives_expunge(&item.attrs);
+        let opt_in = has_option(&item.attrs, &["opt_in"]);
+        for variant in &item.variants {
+            // annotating a variant opts in its fields
+            self.audit_fields(
+                format!("{}::{}", item.ident, variant.ident),
+                derives_expunge,
+                is_skipped(&variant.attrs),
+                opt_in && !is_annotated(&variant.attrs),
+                &variant.fields,
+            );
+        }
+        syn::visit::visit_item_enum(self, item);
+    }
+}
+
+/// Whether the attributes include `#[derive(Expunge)]`, by any path
+fn derives_expunge(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("derive"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= meta
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Expunge");
+                Ok(())
+            });
+            found
+        })
+}
+
+/// Whether the attributes include `#[expunge]` or `#[expunge_field]`, with or without options
+fn is_annotated(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("expunge") || attr.path().is_ident("expunge_field"))
+}
+
+/// Whether the attributes include `#[expunge(skip)]`
+fn is_skipped(attrs: &[Attribute]) -> bool {
+    has_option(attrs, &["skip"])
+}
+
+/// Whether the attributes specify a transformation, which takes precedence over the variant's
+/// `skip`
+fn is_transformed(attrs: &[Attribute]) -> bool {
+    has_option(
+        attrs,
+        &[
+            "as",
+            "with",
+            "with_self",
+            "none",
+            "clear",
+            "hash",
+            "keep_first",
+            "keep_last",
+            "secret",
+        ],
+    )
+}
+
+/// Whether the `expunge` attributes include any of the options
+fn has_option(attrs: &[Attribute], options: &[&str]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("expunge") || attr.path().is_ident("expunge_field"))
+        .any(|attr| {
+            let mut found = false;
+            // `#[expunge]` on its own has no options
+            let _ = attr.parse_nested_meta(|meta| {
+                found |= options.iter().any(|option| meta.path.is_ident(option));
+                // the values of other options are ignored
+                if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if !meta.input.is_empty() && !meta.input.peek(syn::Token![,]) {
+                    meta.input.parse::<proc_macro2::TokenTree>()?;
+                }
+                Ok(())
+            });
+            found
+        })
+}
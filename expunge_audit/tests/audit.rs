@@ -0,0 +1,109 @@
+use expunge_audit::{Auditor, Finding, Kind};
+
+const SOURCE: &str = r#"
+use expunge::Expunge;
+
+#[derive(Debug)]
+struct Unannotated {
+    id: u64,
+    email_address: String,
+    emails_sent: u32,
+}
+
+#[derive(Clone, expunge::Expunge)]
+struct Annotated {
+    password: String,
+    #[expunge(as = "<token>".to_string(), zeroize)]
+    api_token: String,
+    #[expunge(skip)]
+    ssn: String,
+}
+
+mod nested {
+    #[derive(Expunge)]
+    enum Event {
+        Login { user_email: String },
+        #[expunge(skip)]
+        Reset { token: String, #[expunge(hash)] refresh_token: String },
+    }
+
+    struct Iban(String);
+}
+"#;
+
+fn finding(
+    (line, column): (usize, usize),
+    container: &str,
+    field: &str,
+    pattern: &str,
+    kind: Kind,
+) -> Finding {
+    Finding {
+        file: "src/lib.rs".into(),
+        line,
+        column,
+        container: container.to_string(),
+        field: field.to_string(),
+        pattern: pattern.to_string(),
+        kind,
+    }
+}
+
+#[test]
+fn it_reports_sensitive_fields_that_arent_expunged() {
+    let findings = Auditor::new().audit_source("src/lib.rs", SOURCE).unwrap();
+    assert_eq!(
+        vec![
+            finding(
+                (7, 5),
+                "Unannotated",
+                "email_address",
+                "email",
+                Kind::NotExpunged
+            ),
+            finding((17, 5), "Annotated", "ssn", "ssn", Kind::Skipped),
+            finding((25, 17), "Event::Reset", "token", "token", Kind::Skipped),
+        ],
+        findings
+    );
+
+    let json = serde_json::to_value(&findings[0]).unwrap();
+    assert_eq!("not_expunged", json["kind"]);
+    assert_eq!("src/lib.rs", json["file"]);
+}
+
+#[test]
+fn it_matches_custom_patterns() {
+    let auditor = Auditor::empty().pattern("sent").pattern("API_TOKEN");
+    assert_eq!(Some("sent"), auditor.matches("emails_sent"));
+    assert_eq!(Some("api_token"), auditor.matches("api_token"));
+    assert_eq!(None, auditor.matches("email_address"));
+    assert_eq!(None, auditor.matches("token"));
+
+    assert!(Auditor::new()
+        .audit_source("src/lib.rs", "struct {")
+        .is_err());
+}
+
+#[test]
+fn it_reports_unannotated_fields_of_opt_in_containers() {
+    let source = r#"
+#[derive(Expunge)]
+#[expunge(opt_in)]
+struct Account {
+    #[expunge]
+    email: String,
+    password: String,
+}
+"#;
+    assert_eq!(
+        vec![finding(
+            (7, 5),
+            "Account",
+            "password",
+            "password",
+            Kind::Skipped
+        )],
+        Auditor::new().audit_source("src/lib.rs", source).unwrap()
+    );
+}
@@ -0,0 +1,118 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Data, Fields, Generics, Visibility};
+
+use crate::{wrapper, BUILDER};
+
+/// Generates `{Name}Builder`, whose `build` method only returns the value once it's expunged.
+/// Unset `Option` fields are `None`, and other unset fields are an error.
+pub fn derive(
+    name: &Ident,
+    vis: &Visibility,
+    generics: &Generics,
+    bounded: &Generics,
+    data: &Data,
+) -> Result<TokenStream, syn::Error> {
+    let fields = match data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return Err(syn::Error::new(
+                    s.fields.span(),
+                    format!("`{BUILDER}` is only supported on structs with named fields"),
+                ))
+            }
+        },
+        Data::Enum(e) => {
+            return Err(syn::Error::new(
+                e.enum_token.span(),
+                format!("`{BUILDER}` is only supported on structs with named fields"),
+            ))
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span(),
+                format!("`{BUILDER}` is only supported on structs with named fields"),
+            ))
+        }
+    };
+
+    let builder_name = format_ident!("{name}Builder");
+    let container = name.to_string();
+    let value = Ident::new("value", Span::mixed_site());
+
+    let idents: Vec<_> = fields
+        .iter()
+        .map(|field| field.ident.as_ref().expect("fields are named"))
+        .collect();
+    let types = fields.iter().map(|field| &field.ty);
+    let setters = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let ty = &field.ty;
+        let vis = &field.vis;
+        quote! {
+            #vis fn #ident(mut self, #value: impl ::std::convert::Into<#ty>) -> Self {
+                self.#ident = ::std::option::Option::Some(::std::convert::Into::into(#value));
+                self
+            }
+        }
+    });
+    let values = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().expect("fields are named");
+        let field_name = ident.to_string();
+        if matches!(wrapper(&field.ty), Some(("Option", _))) {
+            quote! {
+                #ident: ::std::option::Option::flatten(self.#ident),
+            }
+        } else {
+            quote! {
+                #ident: self.#ident.ok_or(::expunge::Error::MissingField {
+                    container: #container,
+                    field: #field_name,
+                })?,
+            }
+        }
+    });
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (bounded_impl_generics, _, bounded_where_clause) = bounded.split_for_impl();
+    let doc = format!(
+        "Builds a [{name}] that can only be obtained once it's expunged, so that it can't be logged by mistake"
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[must_use]
+        #vis struct #builder_name #generics #where_clause {
+            #(#idents: ::std::option::Option<#types>,)*
+        }
+
+        impl #impl_generics ::std::default::Default for #builder_name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#idents: ::std::option::Option::None,)*
+                }
+            }
+        }
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            #(#setters)*
+        }
+
+        impl #bounded_impl_generics #builder_name #ty_generics #bounded_where_clause {
+            /// Builds the value & expunges it, or returns an error if a field that isn't an
+            /// `Option` wasn't set
+            #vis fn build(self) -> ::std::result::Result<::expunge::Expunged<#name #ty_generics>, ::expunge::Error> {
+                ::std::result::Result::Ok(::expunge::Expunged::new(#name {
+                    #(#values)*
+                }))
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #vis fn builder() -> #builder_name #ty_generics {
+                ::std::default::Default::default()
+            }
+        }
+    })
+}
@@ -0,0 +1,149 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+    parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, FnArg, ItemFn, Meta, Pat,
+    Token, Type,
+};
+
+use crate::{ATTRIBUTES, SKIP};
+
+/// An argument that `#[instrument]` will record
+struct Arg {
+    ident: Ident,
+    is_ref: bool,
+    skip: bool,
+}
+
+/// Rewrites the `#[instrument]` attribute on the function so that its arguments are recorded in
+/// their expunged form. Arguments marked `#[expunge(skip)]` are recorded as they are.
+pub fn expand(args: TokenStream, mut item: ItemFn) -> Result<TokenStream, syn::Error> {
+    if !args.is_empty() {
+        return Err(syn::Error::new(
+            args.span(),
+            "`expunge_fn` doesn't take any arguments",
+        ));
+    }
+
+    let mut recorded = vec![];
+    for input in &mut item.sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            continue;
+        };
+        let skip = take_skip(&mut arg.attrs)?;
+        let Pat::Ident(pat) = arg.pat.as_ref() else {
+            return Err(syn::Error::new(
+                arg.pat.span(),
+                "`expunge_fn` can only record arguments that are bound to an identifier",
+            ));
+        };
+        recorded.push(Arg {
+            ident: pat.ident.clone(),
+            is_ref: matches!(arg.ty.as_ref(), Type::Reference(..)),
+            skip,
+        });
+    }
+
+    let instrument = item
+        .attrs
+        .iter_mut()
+        .find(|attr| {
+            attr.path()
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "instrument")
+        })
+        .ok_or(syn::Error::new(
+            item.sig.ident.span(),
+            "`expunge_fn` must be placed above `#[instrument]`",
+        ))?;
+
+    let options = match &instrument.meta {
+        Meta::Path(..) => Punctuated::new(),
+        Meta::List(list) => {
+            list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?
+        }
+        Meta::NameValue(meta) => {
+            return Err(syn::Error::new(
+                meta.span(),
+                "unsupported `instrument` attribute",
+            ))
+        }
+    };
+
+    let mut skipped = vec![];
+    let mut fields = TokenStream::default();
+    let mut kept = vec![];
+    for option in options {
+        match option {
+            // nothing's recorded, so there's nothing to expunge
+            Meta::Path(path) if path.is_ident("skip_all") => {
+                return Ok(quote! { #item });
+            }
+            Meta::List(list) if list.path.is_ident("skip") => {
+                skipped.extend(
+                    list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?,
+                );
+            }
+            Meta::List(list) if list.path.is_ident("fields") => {
+                fields = list.tokens;
+            }
+            // the return value & error would be recorded as they are
+            option if option.path().is_ident("ret") || option.path().is_ident("err") => {
+                return Err(syn::Error::new(
+                    option.span(),
+                    "`ret` & `err` aren't expunged, so they can't be used with `expunge_fn`",
+                ));
+            }
+            option => kept.push(option),
+        }
+    }
+
+    let recorded = recorded
+        .into_iter()
+        .filter(|arg| !skipped.contains(&arg.ident))
+        .map(|Arg { ident, is_ref, skip }| {
+            if skip {
+                quote! { #ident = ?#ident }
+            } else if is_ref {
+                quote! { #ident = ?::expunge::Expunged::new(::std::clone::Clone::clone(#ident)) }
+            } else {
+                quote! { #ident = ?::expunge::Expunged::new(::std::clone::Clone::clone(&#ident)) }
+            }
+        });
+    let fields = if fields.is_empty() {
+        quote! { #(#recorded),* }
+    } else {
+        quote! { #fields, #(#recorded),* }
+    };
+
+    let path = instrument.path().clone();
+    *instrument = parse_quote! {
+        #[#path(#(#kept,)* skip_all, fields(#fields))]
+    };
+
+    Ok(quote! { #item })
+}
+
+/// Removes `#[expunge(skip)]` from an argument, returning whether it was present
+fn take_skip(attrs: &mut Vec<Attribute>) -> Result<bool, syn::Error> {
+    let mut skip = false;
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)) {
+            return true;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident(SKIP) {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error(format!("only `{SKIP}` is supported on arguments")))
+            }
+        });
+        if let Err(err) = parsed {
+            result = Err(err);
+        }
+        false
+    });
+    result.map(|_| skip)
+}
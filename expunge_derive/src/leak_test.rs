@@ -0,0 +1,143 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{spanned::Spanned, Data, Fields, Generics, Index};
+
+use crate::{field_builder, is_marker, variant_as, variant_builder, Builder, TEST};
+
+/// Generates a `#[cfg(test)]` module with a test that expunges a clone of the `Default` instance,
+/// and asserts that no expunged field still has the same `Debug` output as before
+pub fn derive(
+    name: &Ident,
+    generics: &Generics,
+    data: &Data,
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new(
+            generics.span(),
+            format!("`{TEST}` is not supported on types with generic or lifetime parameters"),
+        ));
+    }
+
+    let original = Ident::new("original", Span::mixed_site());
+    let expunged = Ident::new("expunged", Span::mixed_site());
+    let checks = match data {
+        Data::Struct(s) => {
+            let checks =
+                checked_fields(&s.fields, parent)?
+                    .into_iter()
+                    .map(|(_, member, field_name)| {
+                        check(
+                            &format!("{name}.{field_name}"),
+                            quote! { &#original.#member },
+                            quote! { &#expunged.#member },
+                        )
+                    });
+            quote! { #(#checks)* }
+        }
+        Data::Enum(e) => {
+            let arms = e
+                .variants
+                .iter()
+                .map(|variant| {
+                    let parent = variant_builder(variant, parent)?;
+                    let ident = &variant.ident;
+                    // the variant is replaced as a whole, so none of its fields can leak
+                    if let Some(variant_as) = variant_as(e, variant, &parent)? {
+                        return Ok(quote! {
+                            (#name::#ident { .. }, #name::#variant_as) => {}
+                        });
+                    }
+                    let checked = checked_fields(&variant.fields, &parent)?;
+                    let bind = |prefix: &str| {
+                        let bindings = checked.iter().map(|(i, member, _)| {
+                            let binding = format_ident!("{prefix}{i}", span = Span::mixed_site());
+                            quote! { #member: #binding }
+                        });
+                        quote! { #name::#ident { #(#bindings,)* .. } }
+                    };
+                    let (before, after) = (bind("original_"), bind("expunged_"));
+                    let checks = checked.iter().map(|(i, _, field_name)| {
+                        let before = format_ident!("original_{i}", span = Span::mixed_site());
+                        let after = format_ident!("expunged_{i}", span = Span::mixed_site());
+                        check(
+                            &format!("{name}.{ident}.{field_name}"),
+                            quote! { #before },
+                            quote! { #after },
+                        )
+                    });
+                    Ok(quote! {
+                        (#before, #after) => {
+                            #(#checks)*
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+            quote! {
+                #[allow(unreachable_patterns)]
+                match (&#original, &#expunged) {
+                    #(#arms)*
+                    _ => ::std::panic!("expunging changed the variant"),
+                }
+            }
+        }
+        Data::Union(u) => {
+            return Err(syn::Error::new(
+                u.union_token.span(),
+                format!("`{TEST}` is not supported on unions"),
+            ))
+        }
+    };
+
+    let module = format_ident!("__expunge_test_{name}");
+    Ok(quote! {
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            use super::#name;
+
+            #[test]
+            fn expunged_fields_dont_leak() {
+                let #original = <#name as ::std::default::Default>::default();
+                let #expunged = ::expunge::Expunge::expunge(::std::clone::Clone::clone(&#original));
+                #checks
+            }
+        }
+    })
+}
+
+/// The index, member & name of each field that's always expunged, i.e. that isn't skipped or
+/// conditional
+fn checked_fields(
+    fields: &Fields,
+    parent: &Builder,
+) -> Result<Vec<(usize, TokenStream, String)>, syn::Error> {
+    let mut checked = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let builder = field_builder(field, parent)?;
+        if builder.skip || builder.condition.is_some() || is_marker(&field.ty) {
+            continue;
+        }
+        let (member, field_name) = match &field.ident {
+            Some(ident) => (ident.to_token_stream(), ident.to_string()),
+            None => (Index::from(i).to_token_stream(), i.to_string()),
+        };
+        checked.push((i, member, field_name));
+    }
+    Ok(checked)
+}
+
+fn check(path: &str, before: TokenStream, after: TokenStream) -> TokenStream {
+    quote! {
+        {
+            let before = ::std::format!("{:?}", #before);
+            ::std::assert_ne!(
+                before,
+                ::std::format!("{:?}", #after),
+                "`{}` still formats as `{}` after it's expunged",
+                #path,
+                before,
+            );
+        }
+    }
+}
@@ -3,11 +3,23 @@ extern crate proc_macro;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
 use syn::{
-    parse_macro_input, parse_quote, spanned::Spanned, Attribute, Data, DataEnum, DataStruct,
-    DeriveInput, Expr, Field, Fields, GenericParam, Generics, Index, Meta,
+    meta::ParseNestedMeta,
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+    Attribute, Data, DataEnum, DataStruct, DataUnion, DeriveInput, Expr, ExprLit, Field, Fields,
+    GenericArgument, Generics, Index, Item, ItemFn, Lit, LitInt, LitStr, Meta, Path, PathArguments,
+    Stmt, Type, TypeParamBound, TypePath, Visibility, WherePredicate,
 };
 
-#[proc_macro_derive(Expunge, attributes(expunge))]
+mod builder;
+mod expunge_fn;
+mod leak_test;
+mod manifest;
+mod slog;
+
+#[proc_macro_derive(Expunge, attributes(expunge, expunge_field))]
 pub fn expunge_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     match try_expunge_derive(input) {
@@ -16,33 +28,313 @@ pub fn expunge_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     }
 }
 
+/// Derives `expunge::Erase`, which destroys the data of every field irrecoverably, e.g. for
+/// right-to-be-forgotten requests. It uses the same `#[expunge(...)]` attributes as `Expunge`, but
+/// only `skip`, `as` & `none` are respected.
+#[proc_macro_derive(Erase, attributes(expunge, expunge_field))]
+pub fn erase_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match try_erase_derive(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+/// Records the arguments of an `#[instrument]`ed function in their expunged form.
+///
+/// It must be placed above `#[instrument]`. Arguments are cloned & expunged before they're
+/// recorded, unless they're marked `#[expunge(skip)]`. Arguments skipped by `#[instrument]` aren't
+/// recorded at all.
+///
+/// The return value & error aren't expunged, so `ret` & `err` can't be passed to `#[instrument]`.
+/// Every argument must be bound to an identifier, rather than destructured, so that it can be
+/// recorded.
+///
+/// ```rust,ignore
+/// #[expunge_fn]
+/// #[tracing::instrument]
+/// fn handle(user: User, #[expunge(skip)] request_id: u64) {
+///     // ...
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn expunge_fn(
+    args: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    match expunge_fn::expand(args.into(), item) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
+
+fn try_erase_derive(input: DeriveInput) -> Result<TokenStream, syn::Error> {
+    let span = input.span();
+    let builder = Builder {
+        erasing: true,
+        ..parse_attributes(span, None, input.attrs)?.unwrap_or_default()
+    };
+    let generics = add_trait_bounds(input.generics, &input.data, &builder)?;
+    let impls = match input.data {
+        Data::Struct(s) => derive_struct(&input.ident, s, builder)?,
+        Data::Enum(e) => derive_enum(&input.ident, e, builder)?,
+        Data::Union(u) => derive_union(&input.ident, u, builder)?,
+    };
+    let name = input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::expunge::Erase for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn erase(self) -> Self {
+                #impls
+            }
+        }
+    })
+}
+
 fn try_expunge_derive(input: DeriveInput) -> Result<TokenStream, syn::Error> {
     let span = input.span();
-    let builder = parse_attributes(span, None, input.attrs)?.unwrap_or_default();
-    let slog_enabled = builder.slog;
+    let mut builder = parse_attributes(span, None, input.attrs.clone())?.unwrap_or_default();
+    let slog_impl = if builder.slog {
+        slog::derive(
+            &input.ident,
+            &input.attrs,
+            &input.generics,
+            &input.data,
+            &builder,
+        )?
+    } else {
+        TokenStream::default()
+    };
+    let skip_if = builder.skip_if.take().map(|skip_if| {
+        quote! {
+            if #skip_if {
+                return self;
+            }
+        }
+    });
+    let remote = builder.remote.take();
+    // runs over the result of the generated statements
+    let container_with = builder.container_with.take();
+    let then_container_with = |impls: TokenStream| match &container_with {
+        Some(container_with) => {
+            let expunged = Ident::new("expunged", Span::mixed_site());
+            quote! {
+                let #expunged = {
+                    #impls
+                };
+                (#container_with)(#expunged)
+            }
+        }
+        None => impls,
+    };
+    if let (Some(variant_as), false) = (&builder.variant_as, matches!(input.data, Data::Enum(_))) {
+        return Err(syn::Error::new(
+            variant_as.span(),
+            format!("`{VARIANT_AS}` is only permitted on enums & variants"),
+        ));
+    }
     let debug_allowed = builder.debug_allowed;
+    let error_enabled = builder.error;
+    let display_enabled = builder.display || error_enabled;
 
-    let impls = match input.data {
-        Data::Struct(s) => derive_struct(s, builder)?,
-        Data::Enum(e) => derive_enum(e, builder)?,
-        Data::Union(_) => {
-            return Err(syn::Error::new(
-                input.ident.span(),
-                "this trait cannot be derived for unions",
-            ))
+    let debug_body = if !debug_allowed || display_enabled {
+        derive_debug(&input.ident, &input.data, &builder)?
+    } else {
+        TokenStream::default()
+    };
+    check_error_attributes(&input.data, &builder)?;
+    let (display_body, source_body) = if error_enabled {
+        (
+            derive_error_display(&input.ident, &input.data, &builder)?,
+            derive_error_source(&input.data)?,
+        )
+    } else {
+        (debug_body.clone(), TokenStream::default())
+    };
+    let summary_body = derive_summary(&input.data, &builder)?;
+    if builder.db_write_safe {
+        check_db_write_safe(&input.data, &builder)?;
+    }
+    if builder.deny_unannotated {
+        check_annotated(&input.data)?;
+    }
+    if builder.transparent {
+        transparent_field(&input.data)?;
+    }
+    let reject_impl = if builder.reject_redacted_input {
+        let Redactions {
+            placeholders,
+            masks,
+            hashed,
+            nested,
+        } = redactions(&input.data, &builder)?;
+        let masks = masks
+            .into_iter()
+            .map(|(keep_first, keep_last)| quote! { (#keep_first, #keep_last) });
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::expunge::reject::RejectRedacted for #ident #ty_generics #where_clause {
+                const PLACEHOLDERS: &'static [&'static str] = &[#(#placeholders),*];
+                const MASKS: &'static [(usize, usize)] = &[#(#masks),*];
+                const HASHED: bool = #hashed;
+
+                fn collect_redactions(redactions: &mut ::expunge::reject::Redactions) {
+                    #[allow(unused_imports)]
+                    use ::expunge::reject::{ViaAccept, ViaRejectRedacted};
+
+                    if redactions.add::<Self>() {
+                        #((&&::expunge::reject::Nested::<#nested>::new()).collect_redactions(redactions);)*
+                    }
+                }
+            }
         }
+    } else {
+        TokenStream::default()
+    };
+
+    let debug_params = used_type_params(&input.generics, &input.data);
+    let bounded_generics = add_trait_bounds(input.generics.clone(), &input.data, &builder)?;
+    let builder_impl = if builder.expunged_builder {
+        builder::derive(
+            &input.ident,
+            &input.vis,
+            &input.generics,
+            &bounded_generics,
+            &input.data,
+        )?
+    } else {
+        TokenStream::default()
+    };
+    let remote = remote
+        .map(|remote| {
+            let conversions =
+                remote_conversions(&input.ident, &remote, &input.generics, &input.data)?;
+            Ok::<_, syn::Error>((remote, conversions))
+        })
+        .transpose()?;
+    let manifest = if cfg!(feature = "manifest-export") {
+        Some(manifest::describe(&input.ident, &input.data, &builder)?)
+    } else {
+        None
+    };
+
+    let async_impls = if uses_with_async(&input.data, &builder)? {
+        let parent = Builder {
+            asynchronous: true,
+            ..builder.clone()
+        };
+        Some(then_container_with(match input.data.clone() {
+            Data::Struct(s) => derive_struct(&input.ident, s, parent)?,
+            Data::Enum(e) => derive_enum(&input.ident, e, parent)?,
+            Data::Union(_) => unreachable!("unions don't support `{WITH_ASYNC}`"),
+        }))
+    } else {
+        None
+    };
+
+    let groups = group_fields(&input.ident, &input.data, &builder)?;
+    let leak_test = if builder.leak_test {
+        leak_test::derive(&input.ident, &input.generics, &input.data, &builder)?
+    } else {
+        TokenStream::default()
     };
+
+    let impls = then_container_with(match input.data {
+        Data::Struct(s) => derive_struct(&input.ident, s, builder)?,
+        Data::Enum(e) => derive_enum(&input.ident, e, builder)?,
+        Data::Union(u) => derive_union(&input.ident, u, builder)?,
+    });
     let name = input.ident;
 
-    let generics = add_trait_bounds(input.generics);
+    if let Some((remote, conversions)) = remote {
+        return Ok(derive_remote(
+            &name,
+            &input.vis,
+            &remote,
+            &bounded_generics,
+            skip_if,
+            impls,
+            conversions,
+        ));
+    }
+
+    let summary_impl = {
+        let type_name = name.to_string();
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::expunge::Summarize for #name #ty_generics #where_clause {
+                fn summarize(&self) -> ::expunge::Summary {
+                    let (redacted, total) = #summary_body;
+                    ::expunge::Summary {
+                        type_name: #type_name,
+                        redacted,
+                        total,
+                    }
+                }
+            }
+        }
+    };
+
+    // lets `Erase` require that fields of this type are erased rather than expunged
+    let derived_impl = {
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::expunge::erase::Derived for #name #ty_generics #where_clause {}
+        }
+    };
+
+    let manifest_impl = match manifest {
+        Some(manifest) => {
+            let type_name = name.to_string();
+            let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+            quote! {
+                impl #impl_generics ::expunge::manifest::Manifest for #name #ty_generics #where_clause {
+                    const MANIFEST: &'static str = #manifest;
+                }
+
+                ::expunge::__register_manifest!(#type_name, #manifest);
+            }
+        }
+        None => TokenStream::default(),
+    };
+
+    let group_impl = if groups.is_empty() {
+        TokenStream::default()
+    } else {
+        let vis = &input.vis;
+        let type_name = name.to_string();
+        let (paths, groups): (Vec<_>, Vec<_>) = groups.into_iter().unzip();
+        let (impl_generics, ty_generics, where_clause) = bounded_generics.split_for_impl();
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Expunges only the fields in the group, e.g. `gdpr`, leaving the others as-is
+                #vis fn expunge_group(self, group: &str) -> Self {
+                    ::expunge::policy::ExpungeWith::expunge_with(
+                        self,
+                        &::expunge::policy::Group::new(group, #type_name, &[#((#paths, &[#(#groups),*]),)*]),
+                    )
+                }
+            }
+        }
+    };
+
+    let generics = bounded_generics;
 
     let debug_impl = if !debug_allowed {
-        let generics = add_debug_trait_bounds(generics.clone());
+        let generics = add_debug_trait_bounds(generics.clone(), &debug_params);
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
         quote! {
             impl #impl_generics std::fmt::Debug for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    f.write_str("<expunged>")
+                    #[allow(unused_imports)]
+                    use ::expunge::debug::{Field, Placeholder, Text, ViaDebug, ViaPlaceholder};
+
+                    #debug_body
                 }
             }
 
@@ -51,56 +343,118 @@ fn try_expunge_derive(input: DeriveInput) -> Result<TokenStream, syn::Error> {
         TokenStream::default()
     };
 
-    let slog_impl = if slog_enabled {
-        let generics = add_slog_trait_bounds(generics.clone());
+    let display_impl = if display_enabled {
+        let generics = add_debug_trait_bounds(generics.clone(), &debug_params);
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
         quote! {
-                impl #impl_generics ::slog::Value for #name #ty_generics #where_clause {
-                    fn serialize(
-                        &self,
-                        record: &::slog::Record,
-                        key: ::slog::Key,
-                        serializer: &mut dyn ::slog::Serializer,
-                    ) -> slog::Result {
-                        use ::serde::Serialize;
-                        use ::slog_derive::SerdeValue;
-
-                        #[derive(Clone, SerdeValue)]
-                        struct _expunge_internal_Wrapped {
-                            #[slog]
-                            item: #name,
-                        }
+            impl #impl_generics std::fmt::Display for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    #[allow(unused_imports)]
+                    use ::expunge::debug::{Field, Placeholder, Text, ViaDebug, ViaPlaceholder};
 
-                        impl ::serde::Serialize for _expunge_internal_Wrapped {
-                            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-                            where
-                                S: ::serde::Serializer,
-                            {
-                                self.item.serialize(serializer)
-                            }
-                        }
+                    #display_body
+                }
+            }
+        }
+    } else {
+        TokenStream::default()
+    };
 
-                        let wrapped = _expunge_internal_Wrapped {
-                            item: self.clone().expunge(),
-                        };
-                        ::slog::Value::serialize(&wrapped, record, key, serializer)
-                    }
+    let error_impl = if error_enabled {
+        let generics = add_debug_trait_bounds(generics.clone(), &debug_params);
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics std::error::Error for #name #ty_generics #where_clause {
+                // every variant may have a source
+                #[allow(unreachable_patterns)]
+                fn source(&self) -> ::std::option::Option<&(dyn std::error::Error + 'static)> {
+                    #source_body
                 }
+            }
+
+            impl #impl_generics ::expunge::RedactedError for #name #ty_generics #where_clause {}
         }
     } else {
         TokenStream::default()
     };
 
+    let policy = Ident::new("policy", Span::mixed_site());
+    let policy_param = Ident::new("__ExpungePolicy", Span::mixed_site());
+    let mut policy_generics = generics.clone();
+    policy_generics
+        .params
+        .push(parse_quote!(#policy_param: ::expunge::policy::Policy + ?Sized));
+    let (policy_impl_generics, _, _) = policy_generics.split_for_impl();
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let async_impl = match async_impls {
+        Some(async_impls) => {
+            // the future holds the value across awaits, so it's only `Send` if the value is
+            let mut async_generics = generics.clone();
+            async_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote!(Self: ::std::marker::Send));
+            let (_, _, async_where_clause) = async_generics.split_for_impl();
+            quote! {
+                impl #impl_generics ::expunge::ExpungeAsync for #name #ty_generics #async_where_clause {
+                    #[allow(clippy::redundant_closure_call, unused_variables)]
+                    async fn expunge_async(self) -> Self {
+                        use ::expunge::Expunge as _;
+
+                        let #policy = &::expunge::policy::NoPolicy;
+
+                        #skip_if
+
+                        #async_impls
+                    }
+                }
+            }
+        }
+        None => TokenStream::default(),
+    };
+
     let expanded = quote! {
         #slog_impl
 
         #debug_impl
 
+        #display_impl
+
+        #error_impl
+
+        #summary_impl
+
+        #derived_impl
+
+        #manifest_impl
+
+        #reject_impl
+
+        #builder_impl
+
+        #group_impl
+
+        #leak_test
+
+        #async_impl
+
         impl #impl_generics expunge::Expunge for #name #ty_generics #where_clause {
             fn expunge(self) -> Self {
-                use ::expunge::*;
+                ::expunge::policy::ExpungeWith::expunge_with(self, &::expunge::policy::NoPolicy)
+            }
+        }
+
+        impl #policy_impl_generics ::expunge::policy::ExpungeWith<#policy_param> for #name #ty_generics #where_clause {
+            // `with` may be a closure, which is called where it's declared
+            #[allow(clippy::redundant_closure_call, unused_variables)]
+            fn expunge_with(self, #policy: &#policy_param) -> Self {
+                // a glob import of the crate would shadow names that are used in attributes, e.g.
+                // a type named `Error`
+                use ::expunge::Expunge as _;
+
+                #skip_if
 
                 #impls
             }
@@ -110,86 +464,544 @@ fn try_expunge_derive(input: DeriveInput) -> Result<TokenStream, syn::Error> {
     Ok(expanded)
 }
 
-fn add_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param.bounds.push(parse_quote!(expunge::Expunge));
+/// Generates `Def::expunge(value: Remote) -> Remote` for a definition of a type from another
+/// crate, whose fields must be public. The fields are expunged by implementing a private trait for
+/// the remote type, so that the generated statements can use `self` & `Self` as usual.
+fn derive_remote(
+    name: &Ident,
+    vis: &Visibility,
+    remote: &Path,
+    generics: &Generics,
+    skip_if: Option<TokenStream>,
+    impls: TokenStream,
+    conversions: TokenStream,
+) -> TokenStream {
+    let policy = Ident::new("policy", Span::mixed_site());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let doc = format!(
+        "Expunges [{}] with the attributes of this definition",
+        remote.to_token_stream().to_string().replace(' ', "")
+    );
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #vis fn expunge(value: #remote #ty_generics) -> #remote #ty_generics {
+                trait ExpungeRemote {
+                    fn expunge_remote(self) -> Self;
+                }
+
+                impl #impl_generics ExpungeRemote for #remote #ty_generics #where_clause {
+                    #[allow(clippy::redundant_closure_call, unused_variables)]
+                    fn expunge_remote(self) -> Self {
+                        use ::expunge::Expunge as _;
+
+                        let #policy = &::expunge::policy::NoPolicy;
+
+                        #skip_if
+
+                        #impls
+                    }
+                }
+
+                #conversions
+
+                ExpungeRemote::expunge_remote(value)
+            }
         }
     }
-    generics
 }
 
-fn add_debug_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param.bounds.push(parse_quote!(::std::fmt::Debug));
-            type_param.bounds.push(parse_quote!(Clone));
+/// Converts between the definition & the remote type, which checks that their fields match and
+/// stops the definition from being reported as unused
+fn remote_conversions(
+    name: &Ident,
+    remote: &Path,
+    generics: &Generics,
+    data: &Data,
+) -> Result<TokenStream, syn::Error> {
+    let pattern = |fields: &Fields| {
+        let bindings = fields.iter().enumerate().map(|(i, field)| {
+            field
+                .ident
+                .clone()
+                .unwrap_or_else(|| Ident::new(&format!("arg{i}"), Span::mixed_site()))
+        });
+        match fields {
+            Fields::Named(_) => quote! { { #(#bindings),* } },
+            Fields::Unnamed(_) => quote! { ( #(#bindings),* ) },
+            Fields::Unit => TokenStream::default(),
+        }
+    };
+    let convert = |from: &dyn ToTokens, to: &dyn ToTokens| match data {
+        Data::Struct(s) => {
+            let pattern = pattern(&s.fields);
+            Ok(quote! {
+                let #from #pattern = value;
+                #to #pattern
+            })
+        }
+        Data::Enum(e) => {
+            let arms = e.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let pattern = pattern(&variant.fields);
+                quote! { #from::#ident #pattern => #to::#ident #pattern, }
+            });
+            Ok(quote! {
+                match value {
+                    #(#arms)*
+                }
+            })
+        }
+        Data::Union(u) => Err(syn::Error::new(
+            u.union_token.span(),
+            format!("`{REMOTE}` is not supported on unions"),
+        )),
+    };
+    let from_remote = convert(remote, name)?;
+    let into_remote = convert(name, remote)?;
+    let (_, ty_generics, _) = generics.split_for_impl();
+    Ok(quote! {
+        let _ = |value: #remote #ty_generics| -> #name #ty_generics { #from_remote };
+        let _ = |value: #name #ty_generics| -> #remote #ty_generics { #into_remote };
+    })
+}
+
+/// Adds the bounds of the `Expunge` implementation: those given by `bound`, or `T: Expunge` for
+/// each type parameter that's used by a field that's expunged with `Expunge::expunge`
+fn add_trait_bounds(
+    mut generics: Generics,
+    data: &Data,
+    parent: &Builder,
+) -> Result<Generics, syn::Error> {
+    if let Some(bound) = &parent.bound {
+        generics
+            .make_where_clause()
+            .predicates
+            .extend(bound.clone());
+        return Ok(generics);
+    }
+
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let mut used = TypeParams {
+        params: &params,
+        used: vec![],
+    };
+    let mut predicates = vec![];
+    let mut visit = |fields: &Fields, parent: &Builder| -> Result<(), syn::Error> {
+        for field in fields {
+            let builder = field_builder(field, parent)?;
+            // erasing ignores every transformation other than `as` & `none`
+            let transformed = if parent.erasing {
+                builder.none || builder.expunge_as.is_some()
+            } else {
+                builder.has_transformation()
+            };
+            if let Some(bound) = builder.bound {
+                predicates.extend(bound);
+            } else if !builder.skip && !transformed {
+                used.visit_type(&field.ty);
+            }
+        }
+        Ok(())
+    };
+    match data {
+        Data::Struct(s) => visit(&s.fields, parent)?,
+        Data::Enum(e) => {
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                visit(&variant.fields, &parent)?;
+            }
+        }
+        // the whole union is replaced with `as`
+        Data::Union(_) => {}
+    }
+
+    let used = used.used;
+    let bound: TypeParamBound = if parent.erasing {
+        parse_quote!(expunge::Erase)
+    } else {
+        parse_quote!(expunge::Expunge)
+    };
+    for param in generics.type_params_mut() {
+        if used.contains(&param.ident) {
+            param.bounds.push(bound.clone());
         }
     }
-    generics
+    generics.make_where_clause().predicates.extend(predicates);
+    Ok(generics)
+}
+
+/// Finds the type parameters used by a type, except inside `PhantomData`
+struct TypeParams<'a> {
+    params: &'a [Ident],
+    used: Vec<Ident>,
+}
+
+impl<'ast> Visit<'ast> for TypeParams<'_> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if ty.qself.is_none() {
+            if let Some(first) = ty.path.segments.first() {
+                if self.params.contains(&first.ident) && !self.used.contains(&first.ident) {
+                    self.used.push(first.ident.clone());
+                }
+            }
+        }
+        if ty
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "PhantomData")
+        {
+            return;
+        }
+        visit::visit_type_path(self, ty);
+    }
 }
 
-fn add_slog_trait_bounds(mut generics: Generics) -> Generics {
-    for param in &mut generics.params {
-        if let GenericParam::Type(ref mut type_param) = *param {
-            type_param.bounds.push(parse_quote!(::serde::Serialize));
-            type_param.bounds.push(parse_quote!(Clone));
+/// Adds `T: Debug` for each type parameter that's used by a field, so that fields which are
+/// shown can be printed
+fn add_debug_trait_bounds(mut generics: Generics, used: &[Ident]) -> Generics {
+    for param in generics.type_params_mut() {
+        if used.contains(&param.ident) {
+            param.bounds.push(parse_quote!(::std::fmt::Debug));
         }
     }
     generics
 }
 
+/// The type parameters used by any field, except inside `PhantomData`
+fn used_type_params(generics: &Generics, data: &Data) -> Vec<Ident> {
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let mut visitor = TypeParams {
+        params: &params,
+        used: vec![],
+    };
+    let fields: Vec<&Field> = match data {
+        Data::Struct(s) => s.fields.iter().collect(),
+        Data::Enum(e) => e.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(u) => u.fields.named.iter().collect(),
+    };
+    for field in fields {
+        visitor.visit_type(&field.ty);
+    }
+    visitor.used
+}
+
 #[derive(Debug, Clone, Default)]
 struct Builder {
     // an expression to use as the expunged value
     expunge_as: Option<TokenStream>,
     // an function that takes the un-expunged value and returns an expunged value
     expunge_with: Option<TokenStream>,
+    // an function that takes the un-expunged value & a reference to the container and returns an
+    // expunged value
+    expunge_with_self: Option<TokenStream>,
+    // an async function that's awaited in place of the other transformations by `ExpungeAsync`
+    expunge_with_async: Option<TokenStream>,
+    // derive the body of `ExpungeAsync`, which awaits `with_async` functions
+    asynchronous: bool,
+    // derive the body of `Erase`, which ignores every transformation other than `as`
+    erasing: bool,
+    // set an `Option` to `None`
+    none: bool,
+    // clear a collection or string, composing through `Option` & `Box`
+    clear: bool,
+    // statements that clear the field in place through a `&mut` reference named `value`, resolved
+    // from the field's type when `clear` is set
+    clear_in_place: Option<TokenStream>,
+    // clear strings in place rather than replacing them with new ones, for fields that would
+    // otherwise fall back to `Expunge::expunge`, so that their allocations are reused
+    in_place: bool,
+    // replace the value with a digest of it
+    hash: bool,
+    // encrypt the value, with the type & field path as the context, resolved once the field is
+    // known
+    encrypt: bool,
+    // a sibling field to key the digest with, read before any field is expunged
+    hash_salt: Option<Ident>,
+    // values larger than this many bytes are replaced with a description of their size instead of
+    // being transformed
+    max_len: Option<usize>,
+    // the number of leading characters of a string to leave as-is
+    keep_first: Option<usize>,
+    // the number of trailing characters of a string to leave as-is
+    keep_last: Option<usize>,
     // skip this field
     skip: bool,
     // zeroize the memory when expunging (only the current copy)
     zeroize: bool,
     // implement slog::SerdeValue for this type, expunging the value before logging
     slog: bool,
+    // also implement slog::KV, logging each field as its own key-value pair
+    slog_kv: bool,
     // allow std::fmt::Debug to be derived/implemented. If this is not enabled then `Debug` is
     // implemented by this macro.
     debug_allowed: bool,
+    // printed in place of sensitive values by the generated `Debug` instead of `<expunged>`
+    debug_placeholder: Option<LitStr>,
+    // print only the placeholder from the generated `Debug`, rather than each field
+    debug_opaque: bool,
+    // print the field as-is in the generated `Debug`, even though it's expunged
+    show_in_debug: bool,
+    // implement std::fmt::Display, printing a placeholder for sensitive fields like `Debug`
+    display: bool,
+    // implement std::error::Error & a Display that formats the error message
+    error: bool,
+    // the message of a struct or variant, formatted with placeholders for sensitive fields
+    error_message: Option<LitStr>,
+    // the field returned by `std::error::Error::source`
+    source: bool,
+    // forbid fields & variants from overriding the container's attributes
+    is_final: bool,
+    // apply the parent's `with` after this field's own transformation
+    inherit: bool,
+    // the parent's `with` function, resolved when `inherit` is set
+    inherited_with: Option<TokenStream>,
+    // only expunge when this expression evaluates to true
+    condition: Option<TokenStream>,
+    // the `expunge::Classification` of the data
+    class: Option<Class>,
+    // turn warnings about questionable attribute usage into errors
+    strict: bool,
+    // require fields classified as `secret` to be hashed, for types that are persisted
+    db_write_safe: bool,
+    // require every field to be annotated, so that each one is an explicit decision
+    deny_unannotated: bool,
+    // implement `RejectRedacted`, listing the placeholders that expunged values are replaced with
+    reject_redacted_input: bool,
+    // leave unannotated fields as-is, rather than expunging them, unless their variant is annotated
+    opt_in: bool,
+    // a single-field newtype that's expunged & printed as its inner value
+    transparent: bool,
+    // where-clause predicates that replace the inferred bounds of the `Expunge` implementation
+    bound: Option<Vec<WherePredicate>>,
+    // leave the whole container as-is when this expression evaluates to true
+    skip_if: Option<TokenStream>,
+    // an `expunge::policy::Rule` for each runtime profile, which overrides the other attributes
+    // while that profile is selected
+    profiles: Vec<(Ident, TokenStream)>,
+    // generate a builder that only returns the value once it's expunged
+    expunged_builder: bool,
+    // a type from another crate that this definition mirrors, which is expunged by a generated
+    // function instead of an `Expunge` implementation
+    remote: Option<Path>,
+    // the named groups of fields, e.g. `gdpr`, that `expunge_group` expunges on their own
+    groups: Vec<String>,
+    // generate a test that checks that expunged fields of the `Default` instance don't leak
+    leak_test: bool,
+    // the unit variant that a variant is replaced with, since its name is sensitive too
+    variant_as: Option<Ident>,
+    // a function that takes the whole container once its fields have been expunged
+    container_with: Option<TokenStream>,
+    // expunge every field that doesn't specify its own transformation or `skip`, which is the
+    // default made explicit
+    all: bool,
 }
 
 impl Builder {
+    /// Whether a transformation has been specified, rather than using the parent's
+    fn has_transformation(&self) -> bool {
+        self.expunge_as.is_some()
+            || self.expunge_with.is_some()
+            || self.expunge_with_self.is_some()
+            || self.none
+            || self.clear
+            || self.hash
+            || self.encrypt
+            || self.keep_first.is_some()
+            || self.keep_last.is_some()
+    }
+
+    /// Resolves a field's attributes against those of its parent
+    fn resolve(mut self, parent: &Builder, span: Span) -> Result<Self, syn::Error> {
+        // without its own transformation the field already uses the parent's `with`
+        self.inherit = self.inherit && self.has_transformation();
+        if self.inherit {
+            self.inherited_with = Some(parent.expunge_with.clone().ok_or(syn::Error::new(
+                span,
+                format!("`{INHERIT}` requires the parent to specify `{WITH}`"),
+            ))?);
+        }
+        if !self.has_transformation() {
+            if self.expunge_with_async.is_none() {
+                self.expunge_with_async
+                    .clone_from(&parent.expunge_with_async);
+            }
+            self.expunge_as.clone_from(&parent.expunge_as);
+            self.expunge_with.clone_from(&parent.expunge_with);
+            self.none = parent.none;
+            self.clear = parent.clear;
+            self.hash = parent.hash;
+            self.encrypt = parent.encrypt;
+            self.hash_salt.clone_from(&parent.hash_salt);
+            self.keep_first = parent.keep_first;
+            self.keep_last = parent.keep_last;
+        }
+        if self.profiles.is_empty() {
+            self.profiles.clone_from(&parent.profiles);
+        }
+        // a field's own transformation takes precedence over its variant's `skip`
+        self.skip = self.skip || (parent.skip && !self.has_transformation());
+        self.zeroize = self.zeroize || parent.zeroize;
+        Ok(self)
+    }
+
     fn build(self, span: Span, ident: TokenStream) -> Result<TokenStream, syn::Error> {
         let Self {
             expunge_as,
             expunge_with,
+            expunge_with_self: _,
+            expunge_with_async: _,
+            asynchronous: _,
+            erasing: _,
+            none: _,
+            clear: _,
+            clear_in_place,
+            in_place: _,
+            hash: _,
+            encrypt: _,
+            hash_salt: _,
+            max_len: _,
+            keep_first,
+            keep_last,
             skip,
             zeroize,
             slog: _,
+            slog_kv: _,
             debug_allowed: _,
+            debug_placeholder: _,
+            debug_opaque: _,
+            show_in_debug: _,
+            display: _,
+            error: _,
+            error_message: _,
+            source: _,
+            is_final: _,
+            inherit: _,
+            inherited_with,
+            condition: _,
+            class: _,
+            groups: _,
+            leak_test: _,
+            variant_as: _,
+            container_with: _,
+            all: _,
+            strict: _,
+            db_write_safe: _,
+            reject_redacted_input: _,
+            deny_unannotated: _,
+            opt_in: _,
+            transparent: _,
+            bound: _,
+            skip_if: _,
+            profiles: _,
+            expunged_builder: _,
+            remote: _,
         } = self;
         if skip {
             return Ok(TokenStream::default());
         }
 
+        // the value is zeroized in place before it's replaced, which works for any `T: Zeroize`,
+        // including heap-backed types such as `String` & `Vec<u8>`
         let zeroizer = if zeroize {
             quote! {
-                use ::expunge::secrecy::Secret;
-                let _ = Secret::new(#ident);
+                ::expunge::zeroize::Zeroize::zeroize(&mut #ident);
             }
         } else {
             TokenStream::default()
         };
 
+        let then = inherited_with
+            .map(|inherited_with| {
+                quote_spanned! { span =>
+                    #ident = (#inherited_with)(#ident);
+                }
+            })
+            .unwrap_or_default();
+
+        // clearing leaves the old contents in spare capacity, so they're zeroized beforehand
+        if let Some(clear_in_place) = clear_in_place {
+            return Ok(quote_spanned! { span =>
+                #zeroizer
+                {
+                    let value = &mut #ident;
+                    #clear_in_place
+                }
+                #then
+            });
+        }
+
+        if keep_first.is_some() || keep_last.is_some() {
+            if expunge_as.is_some() || expunge_with.is_some() {
+                return Err(syn::Error::new(
+                    span,
+                    format!("`{KEEP_FIRST}` and `{KEEP_LAST}` cannot be combined with `{AS}` or `{WITH}`"),
+                ));
+            }
+            let keep_first = keep_first.unwrap_or_default();
+            let keep_last = keep_last.unwrap_or_default();
+            // as with `with`, the mask is computed from a copy before the original is zeroized
+            if zeroize {
+                let masked = Ident::new("masked", Span::mixed_site());
+                return Ok(quote_spanned! { span =>
+                    let #masked = ::expunge::utils::mask(
+                        ::std::clone::Clone::clone(&#ident),
+                        #keep_first,
+                        #keep_last,
+                    );
+                    #zeroizer
+                    #ident = #masked;
+                    #then
+                });
+            }
+            return Ok(quote_spanned! { span =>
+                #ident = ::expunge::utils::mask(#ident, #keep_first, #keep_last);
+                #then
+            });
+        }
+
         match (expunge_as, expunge_with) {
             (Some(expunge_as), None) => Ok(quote_spanned! { span =>
                 #zeroizer
                 #ident = #expunge_as;
+                #then
             }),
+            // the function consumes its argument, so it's given a copy & the original is zeroized
+            // once the new value has been computed. The copy can only be zeroized by the function,
+            // which is documented as its responsibility.
+            (None, Some(expunge_with)) if zeroize => {
+                let expunged = Ident::new("expunged", Span::mixed_site());
+                Ok(quote_spanned! { span =>
+                    let #expunged = (#expunge_with)(::std::clone::Clone::clone(&#ident));
+                    #zeroizer
+                    #ident = #expunged;
+                    #then
+                })
+            }
             (None, Some(expunge_with)) => Ok(quote_spanned! { span =>
-                #ident = #expunge_with(#ident);
-            }),
-            (None, None) => Ok(quote_spanned! { span =>
-                #ident = #ident.expunge();
+                #ident = (#expunge_with)(#ident);
+                #then
             }),
+            // the policy is passed on to nested types that implement `ExpungeWith`
+            (None, None) => {
+                let policy = Ident::new("policy", Span::mixed_site());
+                Ok(quote_spanned! { span =>
+                    #ident = {
+                        #[allow(unused_imports)]
+                        use ::expunge::policy::{PolicyField, ViaAttributes, ViaPolicy};
+
+                        (&&PolicyField::new(#ident)).expunge_nested(#policy)
+                    };
+                    #then
+                })
+            }
             _ => Err(syn::Error::new(
                 span,
                 "unsupported combination of attributes",
@@ -200,11 +1012,70 @@ impl Builder {
 
 const WITH: &str = "with";
 const AS: &str = "as";
+const AS_STR: &str = "as_str";
 const SKIP: &str = "skip";
 const ZEROIZE: &str = "zeroize";
+const SECRET: &str = "secret";
 const SLOG: &str = "slog";
+const KV: &str = "kv";
 const DEFAULT: &str = "default";
 const ALLOW_DEBUG: &str = "allow_debug";
+const DEBUG: &str = "debug";
+const DEBUG_PLACEHOLDER: &str = "debug_placeholder";
+const SHOW_IN_DEBUG: &str = "show_in_debug";
+const DISPLAY: &str = "display";
+const ERROR: &str = "error";
+const SOURCE: &str = "source";
+const KEEP_FIRST: &str = "keep_first";
+const KEEP_LAST: &str = "keep_last";
+const MAX_LEN: &str = "max_len";
+const FINAL: &str = "final";
+const INHERIT: &str = "inherit";
+const STRICT: &str = "strict";
+const DB_WRITE_SAFE: &str = "db_write_safe";
+const REJECT_REDACTED_INPUT: &str = "reject_redacted_input";
+const DENY_UNANNOTATED: &str = "deny_unannotated";
+const OPT_IN: &str = "opt_in";
+const OPT_OUT: &str = "opt_out";
+const TEST: &str = "test";
+const VARIANT_AS: &str = "variant_as";
+const CONTAINER_WITH: &str = "container_with";
+const ALL: &str = "all";
+const TRANSPARENT: &str = "transparent";
+const BOUND: &str = "bound";
+const WITH_SELF: &str = "with_self";
+const WITH_ASYNC: &str = "with_async";
+const IF: &str = "if";
+const UNLESS: &str = "unless";
+const CLASS: &str = "class";
+const GROUP: &str = "group";
+const NONE: &str = "none";
+const CLEAR: &str = "clear";
+const IN_PLACE: &str = "in_place";
+const HASH: &str = "hash";
+const TOKENIZE: &str = "tokenize";
+const ENCRYPT: &str = "encrypt";
+const FAKE: &str = "fake";
+const EXPUNGER: &str = "expunger";
+const PROFILE: &str = "profile";
+
+/// The kinds of `fake`, with their `expunge::fake::Kind` variants
+const FAKE_KINDS: &[(&str, &str)] = &[
+    ("name", "Name"),
+    ("first_name", "FirstName"),
+    ("last_name", "LastName"),
+    ("email", "Email"),
+    ("phone", "Phone"),
+];
+const KEYS: &str = "keys";
+const ENTRIES: &str = "entries";
+const SKIP_IF: &str = "skip_if";
+const SALT_FIELD: &str = "salt_field";
+const BUILDER: &str = "builder";
+const REMOTE: &str = "remote";
+
+/// `expunge_field` is an alias for macros that wrap this one and already use `#[expunge(...)]`
+const ATTRIBUTES: &[&str] = &["expunge", "expunge_field"];
 
 fn parse_attributes(
     span: Span,
@@ -213,7 +1084,7 @@ fn parse_attributes(
 ) -> Result<Option<Builder>, syn::Error> {
     let attrs: Vec<_> = attrs
         .into_iter()
-        .filter(|attr| attr.path().is_ident("expunge"))
+        .filter(|attr| ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
         .collect();
 
     let is_container = parent.is_none();
@@ -227,14 +1098,30 @@ fn parse_attributes(
                 return parent
                     .ok_or(syn::Error::new(
                         attr.meta.span(),
-                        "`#[expunge]` can only be used to mark fields & variants".to_string(),
+                        format!(
+                            "`#[{}]` can only be used to mark fields & variants",
+                            attr.path().to_token_stream()
+                        ),
                     ))
                     .map(Some);
             }
 
             let mut builder = Builder::default();
+            let mut secret = None;
+            let mut zeroize = None;
+            // whether `opt_in` or `opt_out` has been specified, & which one
+            let mut mode = None;
 
             attr.parse_nested_meta(|meta| {
+                if (meta.path.is_ident(AS) || meta.path.is_ident(AS_STR) || meta.path.is_ident(WITH))
+                    && builder.expunge_with_self.is_some()
+                {
+                    return Err(syn::Error::new(
+                        meta.path.span(),
+                        format!("`{WITH_SELF}` cannot be combined with `{AS}` or `{WITH}`"),
+                    ));
+                }
+
                 if meta.path.is_ident(AS) {
                     if builder.expunge_with.is_some() {
                         return Err(syn::Error::new(
@@ -245,6 +1132,22 @@ fn parse_attributes(
                     let expr: Expr = meta.value()?.parse()?;
                     builder.expunge_as = Some(expr.into_token_stream());
                     Ok(())
+                } else if meta.path.is_ident(AS_STR) {
+                    if builder.expunge_as.is_some() || builder.expunge_with.is_some() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{AS_STR}` cannot be combined with `{AS}` or `{WITH}`"),
+                        ));
+                    }
+                    // a static per use, so that the placeholder is only converted once for types
+                    // that own their data, e.g. `Arc<str>`
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.expunge_as = Some(quote_spanned! { expr.span() => {
+                        static PLACEHOLDER: ::expunge::placeholder::StaticPlaceholder =
+                            ::expunge::placeholder::StaticPlaceholder::new(#expr);
+                        ::expunge::placeholder::FromPlaceholder::from_placeholder(&PLACEHOLDER)
+                    }});
+                    Ok(())
                 } else if meta.path.is_ident(WITH) {
                     if builder.expunge_as.is_some() {
                         return Err(syn::Error::new(
@@ -252,32 +1155,208 @@ fn parse_attributes(
                             format!("`{WITH}` cannot be combined with `{AS}`"),
                         ));
                     }
+                    // `keys`, `tokenize`, `expunger` & `fake` are also implemented with
+                    // `expunge_with`, so they'd be silently replaced
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{WITH}` cannot be combined with other transformations"),
+                        ));
+                    }
                     let expr: Expr = meta.value()?.parse()?;
-                    builder.expunge_with = Some(expr.into_token_stream());
+                    builder.expunge_with = Some(chain_with(expr)?);
                     Ok(())
-                } else if meta.path.is_ident(SKIP) {
+                } else if meta.path.is_ident(WITH_SELF) {
                     if is_container {
                         return Err(syn::Error::new(
                             meta.path.span(),
-                            format!("`{SKIP}` is not permitted on containers"),
+                            format!("`{WITH_SELF}` is not permitted on containers"),
                         ));
                     }
-                    builder.skip = true;
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{WITH_SELF}` cannot be combined with `{AS}` or `{WITH}`"),
+                        ));
+                    }
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.expunge_with_self = Some(expr.into_token_stream());
                     Ok(())
-                } else if meta.path.is_ident(ZEROIZE) {
-                    if cfg!(feature = "zeroize") {
-                        if builder.expunge_with.is_some() {
-                            return Err(syn::Error::new(
-                                meta.path.span(),
-                                format!("`{ZEROIZE}` cannot be combined with `{WITH}`"),
-                            ));
-                        }
-                        if builder.expunge_as.is_none() {
-                            return Err(syn::Error::new(
-                                meta.path.span(),
-                                format!("`{ZEROIZE}` requires that `{AS}` be specified since it consumes the value"),
-                            ));
-                        }
+                } else if meta.path.is_ident(WITH_ASYNC) {
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.expunge_with_async = Some(expr.into_token_stream());
+                    Ok(())
+                } else if meta.path.is_ident(IF) || meta.path.is_ident(UNLESS) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{IF}` and `{UNLESS}` are not permitted on containers"),
+                        ));
+                    }
+                    let expr: Expr = meta.value()?.parse()?;
+                    let condition = if meta.path.is_ident(IF) {
+                        quote! { (#expr) }
+                    } else {
+                        quote! { !(#expr) }
+                    };
+                    builder.condition = Some(match builder.condition.take() {
+                        Some(existing) => quote! { #existing && #condition },
+                        None => condition,
+                    });
+                    Ok(())
+                } else if meta.path.is_ident(CONTAINER_WITH) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{CONTAINER_WITH}` is only permitted on containers, use `{WITH}` instead"),
+                        ));
+                    }
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.container_with = Some(expr.into_token_stream());
+                    Ok(())
+                } else if meta.path.is_ident(SKIP_IF) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{SKIP_IF}` is only permitted on containers, use `{IF}` or `{UNLESS}` instead"),
+                        ));
+                    }
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.skip_if = Some(expr.into_token_stream());
+                    Ok(())
+                } else if meta.path.is_ident(NONE) || meta.path.is_ident(CLEAR) {
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{NONE}` and `{CLEAR}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    if meta.path.is_ident(NONE) {
+                        builder.none = true;
+                    } else {
+                        builder.clear = true;
+                    }
+                    Ok(())
+                } else if meta.path.is_ident(KEYS) || meta.path.is_ident(ENTRIES) {
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{KEYS}` and `{ENTRIES}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    builder.expunge_with = Some(if meta.path.is_ident(KEYS) {
+                        quote! { ::expunge::utils::ExpungeKeys::expunge_keys }
+                    } else {
+                        quote! { ::expunge::utils::ExpungeKeys::expunge_entries }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident(HASH) {
+                    if !cfg!(feature = "hash") {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("the `{HASH}` feature must be enabled"),
+                        ));
+                    }
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{HASH}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    builder.hash = true;
+                    if meta.input.peek(syn::token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path.is_ident(SALT_FIELD) {
+                                builder.hash_salt = Some(meta.value()?.parse()?);
+                                Ok(())
+                            } else {
+                                Err(meta.error(format!("expected `{SALT_FIELD}`")))
+                            }
+                        })?;
+                    }
+                    Ok(())
+                } else if meta.path.is_ident(TOKENIZE) {
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{TOKENIZE}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    builder.expunge_with = Some(quote! { ::expunge::tokenize::Tokenize::tokenize });
+                    Ok(())
+                } else if meta.path.is_ident(ENCRYPT) {
+                    if !cfg!(feature = "crypto") {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            "the `crypto` feature must be enabled",
+                        ));
+                    }
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{ENCRYPT}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    builder.encrypt = true;
+                    Ok(())
+                } else if meta.path.is_ident(EXPUNGER) {
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{EXPUNGER}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.expunge_with = Some(quote! {
+                        |value| ::expunge::Expunger::apply(&(#expr), value)
+                    });
+                    Ok(())
+                } else if meta.path.is_ident(FAKE) {
+                    if !cfg!(feature = "fake") {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("the `{FAKE}` feature must be enabled"),
+                        ));
+                    }
+                    if builder.has_transformation() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{FAKE}` cannot be combined with other transformations"),
+                        ));
+                    }
+                    let kind: LitStr = meta.value()?.parse()?;
+                    let Some((_, variant)) = FAKE_KINDS.iter().find(|(name, _)| *name == kind.value()) else {
+                        let kinds: Vec<_> = FAKE_KINDS.iter().map(|(name, _)| format!("`{name}`")).collect();
+                        return Err(syn::Error::new(
+                            kind.span(),
+                            format!("unknown kind, expected one of {}", kinds.join(", ")),
+                        ));
+                    };
+                    let variant = Ident::new(variant, kind.span());
+                    builder.expunge_with = Some(quote! {
+                        |value| ::expunge::fake::Fake::fake(value, ::expunge::fake::Kind::#variant)
+                    });
+                    Ok(())
+                } else if meta.path.is_ident(CLASS) {
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.class = Some(parse_class(&expr)?);
+                    Ok(())
+                } else if meta.path.is_ident(GROUP) {
+                    let expr: Expr = meta.value()?.parse()?;
+                    builder.groups = parse_groups(&expr)?;
+                    Ok(())
+                } else if meta.path.is_ident(SKIP) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{SKIP}` is not permitted on containers"),
+                        ));
+                    }
+                    builder.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident(ZEROIZE) {
+                    if cfg!(feature = "zeroize") {
+                        zeroize = Some(meta.path.span());
                         builder.zeroize = true;
                         Ok(())
                     } else {
@@ -286,6 +1365,15 @@ fn parse_attributes(
                             format!("the `{ZEROIZE}` feature must be enabled"),
                         ))
                     }
+                } else if meta.path.is_ident(SECRET) {
+                    if !cfg!(feature = "zeroize") {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("the `{ZEROIZE}` feature must be enabled"),
+                        ));
+                    }
+                    secret = Some(meta.path.span());
+                    Ok(())
                 } else if meta.path.is_ident(SLOG) {
                     if cfg!(feature = "slog") {
                         if !is_container {
@@ -295,6 +1383,16 @@ fn parse_attributes(
                             ));
                         }
                         builder.slog = true;
+                        if meta.input.peek(syn::token::Paren) {
+                            meta.parse_nested_meta(|meta| {
+                                if meta.path.is_ident(KV) {
+                                    builder.slog_kv = true;
+                                    Ok(())
+                                } else {
+                                    Err(meta.error(format!("expected `{KV}`")))
+                                }
+                            })?;
+                        }
                         Ok(())
                     } else {
                         Err(syn::Error::new(
@@ -311,96 +1409,1241 @@ fn parse_attributes(
                     }
                     builder.debug_allowed = true;
                     Ok(())
+                } else if meta.path.is_ident(SHOW_IN_DEBUG) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{SHOW_IN_DEBUG}` is not permitted on containers, use `{ALLOW_DEBUG}` instead"),
+                        ));
+                    }
+                    builder.show_in_debug = true;
+                    Ok(())
+                } else if meta.path.is_ident(DEBUG) || meta.path.is_ident(DEBUG_PLACEHOLDER) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{DEBUG}` and `{DEBUG_PLACEHOLDER}` are not permitted on fields or variants"),
+                        ));
+                    }
+                    let value: LitStr = meta.value()?.parse()?;
+                    if meta.path.is_ident(DEBUG_PLACEHOLDER) {
+                        builder.debug_placeholder = Some(value);
+                        return Ok(());
+                    }
+                    builder.debug_opaque = match value.value().as_str() {
+                        "fields" => false,
+                        "placeholder" => true,
+                        _ => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                format!("expected `{DEBUG} = \"fields\"` or `{DEBUG} = \"placeholder\"`"),
+                            ))
+                        }
+                    };
+                    Ok(())
+                } else if meta.path.is_ident(DISPLAY) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{DISPLAY}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.display = true;
+                    Ok(())
+                } else if meta.path.is_ident(ERROR) {
+                    if is_container {
+                        builder.error = true;
+                        if meta.input.peek(syn::Token![=]) {
+                            builder.error_message = Some(meta.value()?.parse()?);
+                        }
+                    } else {
+                        builder.error_message = Some(meta.value()?.parse()?);
+                    }
+                    Ok(())
+                } else if meta.path.is_ident(SOURCE) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{SOURCE}` is not permitted on containers"),
+                        ));
+                    }
+                    builder.source = true;
+                    Ok(())
+                } else if meta.path.is_ident(KEEP_FIRST) || meta.path.is_ident(KEEP_LAST) {
+                    if builder.expunge_as.is_some() || builder.expunge_with.is_some() {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{KEEP_FIRST}` and `{KEEP_LAST}` cannot be combined with `{AS}` or `{WITH}`"),
+                        ));
+                    }
+                    let n: LitInt = meta.value()?.parse()?;
+                    let n = n.base10_parse::<usize>()?;
+                    if meta.path.is_ident(KEEP_FIRST) {
+                        builder.keep_first = Some(n);
+                    } else {
+                        builder.keep_last = Some(n);
+                    }
+                    Ok(())
+                } else if meta.path.is_ident(MAX_LEN) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{MAX_LEN}` is not permitted on containers"),
+                        ));
+                    }
+                    let n: LitInt = meta.value()?.parse()?;
+                    builder.max_len = Some(n.base10_parse::<usize>()?);
+                    Ok(())
                 } else if meta.path.is_ident(DEFAULT) {
                     builder.expunge_as = Some(quote!{ Default::default() });
                     Ok(())
+                } else if meta.path.is_ident(FINAL) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{FINAL}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.is_final = true;
+                    Ok(())
+                } else if meta.path.is_ident(STRICT) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{STRICT}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.strict = true;
+                    Ok(())
+                } else if meta.path.is_ident(DB_WRITE_SAFE) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{DB_WRITE_SAFE}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.db_write_safe = true;
+                    Ok(())
+                } else if meta.path.is_ident(REJECT_REDACTED_INPUT) {
+                    if !cfg!(feature = "json") {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            "the `json` feature must be enabled",
+                        ));
+                    }
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{REJECT_REDACTED_INPUT}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.reject_redacted_input = true;
+                    Ok(())
+                } else if meta.path.is_ident(DENY_UNANNOTATED) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{DENY_UNANNOTATED}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.deny_unannotated = true;
+                    Ok(())
+                } else if meta.path.is_ident(OPT_IN) || meta.path.is_ident(OPT_OUT) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!(
+                                "`{}` is not permitted on fields or variants",
+                                meta.path.to_token_stream()
+                            ),
+                        ));
+                    }
+                    let opt_in = meta.path.is_ident(OPT_IN);
+                    if mode.replace(opt_in).is_some_and(|mode| mode != opt_in) {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{OPT_IN}` cannot be combined with `{OPT_OUT}`"),
+                        ));
+                    }
+                    builder.opt_in = opt_in;
+                    Ok(())
+                } else if meta.path.is_ident(TEST) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{TEST}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.leak_test = true;
+                    Ok(())
+                } else if meta.path.is_ident(ALL) {
+                    builder.all = true;
+                    Ok(())
+                } else if meta.path.is_ident(VARIANT_AS) {
+                    let path: Path = meta.value()?.parse()?;
+                    builder.variant_as = Some(path.require_ident()?.clone());
+                    Ok(())
+                } else if meta.path.is_ident(IN_PLACE) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{IN_PLACE}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.in_place = true;
+                    Ok(())
+                } else if meta.path.is_ident(TRANSPARENT) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{TRANSPARENT}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.transparent = true;
+                    Ok(())
+                } else if meta.path.is_ident(BOUND) {
+                    let bound: LitStr = meta.value()?.parse()?;
+                    let predicates = bound.parse_with(
+                        Punctuated::<WherePredicate, syn::Token![,]>::parse_terminated,
+                    )?;
+                    builder.bound = Some(predicates.into_iter().collect());
+                    Ok(())
+                } else if meta.path.is_ident(BUILDER) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{BUILDER}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    builder.expunged_builder = true;
+                    Ok(())
+                } else if meta.path.is_ident(REMOTE) {
+                    if !is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{REMOTE}` is not permitted on fields or variants"),
+                        ));
+                    }
+                    let remote: LitStr = meta.value()?.parse()?;
+                    builder.remote = Some(remote.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident(INHERIT) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{INHERIT}` is not permitted on containers"),
+                        ));
+                    }
+                    builder.inherit = true;
+                    Ok(())
+                } else if meta.path.is_ident(PROFILE) {
+                    if is_container {
+                        return Err(syn::Error::new(
+                            meta.path.span(),
+                            format!("`{PROFILE}` is not permitted on containers"),
+                        ));
+                    }
+                    meta.parse_nested_meta(|meta| parse_profile(&meta, &mut builder.profiles))
                 } else {
                     Err(syn::Error::new(
                         meta.path.span(),
-                        format!("unrecognized option `{:?}`", meta.path),
+                        format!("unrecognized option `{}`", meta.path.to_token_stream()),
+                    ))
+                }
+            })?;
+
+            if builder.all && builder.skip {
+                return Err(syn::Error::new(
+                    attr.meta.span(),
+                    format!("`{ALL}` cannot be combined with `{SKIP}`"),
+                ));
+            }
+
+            // checked once every option has been parsed, so that `as` or `with` may come after it
+            if let Some(span) = zeroize {
+                if builder.expunge_as.is_none() && builder.expunge_with.is_none() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{ZEROIZE}` requires that `{AS}` or `{WITH}` be specified since it consumes the value"),
+                    ));
+                }
+            }
+
+            // `secret` is shorthand for `as = "<expunged>".into(), zeroize`, and may be combined
+            // with `as` for a different placeholder
+            if let Some(span) = secret {
+                if builder.expunge_as.is_none() && builder.has_transformation() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{SECRET}` can only be combined with `{AS}`"),
+                    ));
+                }
+                if builder.expunge_as.is_none() {
+                    builder.expunge_as = Some(quote! { ::std::convert::From::from("<expunged>") });
+                }
+                builder.zeroize = true;
+            }
+
+            if let Some(parent) = parent {
+                let overrides = builder.has_transformation() || builder.skip;
+                if parent.is_final && overrides {
+                    return Err(syn::Error::new(
+                        attr.meta.span(),
+                        format!("the container is marked `{FINAL}`, so its attributes cannot be overridden"),
+                    ));
+                }
+                builder.is_final = parent.is_final;
+                builder.strict = parent.strict;
+                builder.in_place = parent.in_place;
+                builder.asynchronous = parent.asynchronous;
+                builder.erasing = parent.erasing;
+                builder.opt_in = parent.opt_in;
+                builder.debug_placeholder = parent.debug_placeholder;
+                builder.debug_opaque = parent.debug_opaque;
+                if builder.class.is_none() {
+                    builder.class = parent.class;
+                }
+                if builder.groups.is_empty() {
+                    builder.groups = parent.groups;
+                }
+            }
+
+            Ok(Some(builder))
+        }
+        n => Err(syn::Error::new(
+            span,
+            format!("expected 1 or 0 `expunge` or `expunge_field` tags, found {n}"),
+        )),
+    }
+}
+
+/// Derives the expunging statements for each field. Returns the statements that must run before
+/// `self` is consumed, i.e. while the original container is still available, separately.
+///
+/// Fields are numbered by their index in the container. When `borrowed` is set, `self` is a
+/// reference & each field is bound to a clone of its value, as for enum variants.
+fn derive_fields(
+    container: &Ident,
+    variant: Option<&Ident>,
+    prefix: TokenStream,
+    fields: impl IntoIterator<Item = (usize, Field)>,
+    parent: Builder,
+    borrowed: bool,
+) -> Result<(TokenStream, TokenStream), syn::Error> {
+    let is_bound = variant.is_some() || borrowed;
+    let (preludes, bodies): (Vec<_>, Vec<_>) = fields
+        .into_iter()
+        .map(|(i, field)| {
+            let span = field.span();
+            let own = own_attributes(span, &field.attrs)?;
+            if let Some(variant_as) = own.variant_as {
+                return Err(syn::Error::new(
+                    variant_as.span(),
+                    format!("`{VARIANT_AS}` is only permitted on enums & variants"),
+                ));
+            }
+            if own.all {
+                return Err(syn::Error::new(
+                    span,
+                    format!("`{ALL}` is only permitted on containers & variants"),
+                ));
+            }
+            let mut builder = field_builder(&field, &parent)?;
+            // markers have no data, so they're skipped unless they're explicitly transformed
+            if is_marker(&field.ty) && !builder.has_transformation() {
+                builder.skip = true;
+            }
+            if let Some(expunge_as) = builder.expunge_as.take() {
+                builder.expunge_as = Some(check_as_value(&field.ty, expunge_as));
+            }
+
+            let field_name = field
+                .ident
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or(i.to_string());
+            let notify = if cfg!(feature = "observer") && !builder.skip {
+                notify_observers(container, variant, &field_name, builder.class.as_ref())
+            } else {
+                TokenStream::default()
+            };
+            let count = if cfg!(feature = "metrics") && !builder.skip {
+                count_field(container, variant, &field_name)
+            } else {
+                TokenStream::default()
+            };
+
+            // temporaries are namespaced by variant since all variants share the same prelude
+            let temp_prefix = match variant {
+                Some(variant) => format!("__expunge_{variant}_{i}"),
+                None => format!("__expunge_{i}"),
+            };
+
+            let original = match (&field.ident, variant) {
+                (_, Some(_)) => None,
+                (Some(named), None) => Some(quote! { self.#named }),
+                (None, None) => {
+                    let index = Index::from(i);
+                    Some(quote! { self.#index })
+                }
+            };
+            let ident = match field.ident {
+                Some(named) if is_bound => named.into_token_stream(),
+                Some(named) => quote! { #prefix.#named },
+                None if is_bound => Ident::new(&format!("{prefix}{i}"), span).into_token_stream(),
+                None => {
+                    let index = Index::from(i);
+                    quote! { #prefix.#index }
+                }
+            };
+
+            if parent.erasing {
+                // the replaced value is zeroized first, if its type supports it
+                let wipe = quote_spanned! { span =>
+                    {
+                        #[allow(unused_imports)]
+                        use ::expunge::erase::{ViaKeep, ViaWipe};
+
+                        (&&::expunge::erase::WipedField::new(&mut #ident)).wipe();
+                    }
+                };
+                let erased = match (builder.skip, builder.none, builder.expunge_as) {
+                    (true, ..) => TokenStream::default(),
+                    (_, true, _) => quote_spanned! { span =>
+                        #wipe
+                        #ident = ::std::option::Option::None;
+                    },
+                    // `as` values are constants, so they're as irrecoverable as erasing the value
+                    (_, _, Some(expunge_as)) => quote_spanned! { span =>
+                        #wipe
+                        #ident = #expunge_as;
+                    },
+                    _ => quote_spanned! { span =>
+                        #ident = {
+                            #[allow(unused_imports)]
+                            use ::expunge::erase::{ViaDerived, ViaErase, ViaExpunge, ViaZeroize};
+
+                            (&&&&::expunge::erase::ErasedField::new(#ident)).erase()
+                        };
+                    },
+                };
+                return Ok((TokenStream::default(), erased));
+            }
+
+            // salts are also read before any field is expunged, in case the salt field is expunged.
+            // Hashing is a `with` function, so that with `zeroize` the digest is computed before
+            // the original is zeroized
+            let salt = if builder.hash && !builder.skip {
+                if builder.expunge_as.is_some() || builder.expunge_with.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{HASH}` cannot be combined with other transformations"),
+                    ));
+                }
+                builder.hash = false;
+                match (builder.hash_salt.take(), &original) {
+                    (Some(_), None) => {
+                        return Err(syn::Error::new(
+                            span,
+                            format!("`{SALT_FIELD}` is only supported on struct fields"),
+                        ))
+                    }
+                    (Some(salt_field), Some(_)) => {
+                        let temp = Ident::new(&format!("{temp_prefix}_salt"), Span::mixed_site());
+                        builder.expunge_with = Some(quote_spanned! { span =>
+                            |value| ::expunge::utils::hash(value, ::std::option::Option::Some(#temp.as_bytes()))
+                        });
+                        quote_spanned! { span =>
+                            let #temp = ::std::string::ToString::to_string(&self.#salt_field);
+                        }
+                    }
+                    (None, _) => {
+                        builder.expunge_with = Some(quote_spanned! { span =>
+                            |value| ::expunge::utils::hash(value, ::std::option::Option::None)
+                        });
+                        TokenStream::default()
+                    }
+                }
+            } else {
+                TokenStream::default()
+            };
+
+            if builder.encrypt && !builder.skip {
+                builder.encrypt = false;
+                let context = match variant {
+                    Some(variant) => format!("{container}::{variant}.{field_name}"),
+                    None => format!("{container}.{field_name}"),
+                };
+                builder.expunge_with = Some(quote_spanned! { span =>
+                    |value| ::expunge::crypto::Encrypt::encrypt(value, #context)
+                });
+            }
+
+            // `with_self` functions are called before any field is expunged, so that they all
+            // receive the original container
+            let with_self = match (builder.expunge_with_self.take(), original) {
+                (Some(_), None) => {
+                    return Err(syn::Error::new(
+                        span,
+                        format!("`{WITH_SELF}` is only supported on struct fields"),
+                    ))
+                }
+                (Some(with_self), Some(original)) if !builder.skip => {
+                    let temp = Ident::new(&format!("{temp_prefix}_with_self"), Span::mixed_site());
+                    builder.expunge_as = Some(temp.to_token_stream());
+                    quote_spanned! { span =>
+                        let #temp = (#with_self)(::std::clone::Clone::clone(&#original), &self);
+                    }
+                }
+                _ => TokenStream::default(),
+            };
+
+            // conditions are also evaluated against the original container
+            let (condition, guard) = match builder.condition.take() {
+                Some(condition) if !builder.skip => {
+                    let temp = Ident::new(&format!("{temp_prefix}_condition"), Span::mixed_site());
+                    (
+                        quote_spanned! { span =>
+                            let #temp: bool = #condition;
+                        },
+                        Some(temp),
+                    )
+                }
+                _ => (TokenStream::default(), None),
+            };
+
+            if builder.none {
+                if !matches!(wrapper(&field.ty), Some(("Option", _))) {
+                    return Err(syn::Error::new(
+                        field.ty.span(),
+                        format!("`{NONE}` can only be used on `Option` fields"),
+                    ));
+                }
+                builder.none = false;
+                builder.expunge_as = Some(quote! { ::std::option::Option::None });
+            }
+            if builder.clear {
+                let ty = &field.ty;
+                let clear = clear_value(ty, quote! { value })?;
+                builder.clear_in_place = Some(clear);
+            } else if builder.in_place
+                && !builder.skip
+                && !builder.has_transformation()
+                && builder.max_len.is_none()
+                && builder.expunge_with_async.is_none()
+            {
+                builder.clear_in_place = clear_strings(&field.ty, quote! { value });
+            }
+
+            let warning = if builder.zeroize && !builder.skip && is_copy(&field.ty) {
+                zeroize_copy_warning(&builder, &field.ty)?
+            } else {
+                TokenStream::default()
+            };
+            let max_len = builder.max_len.take().filter(|_| !builder.skip);
+            let profiles = std::mem::take(&mut builder.profiles);
+            let with_async = builder
+                .expunge_with_async
+                .take()
+                .filter(|_| parent.asynchronous && !builder.skip);
+            let zeroize = builder.zeroize;
+            let expunged = match with_async {
+                // as with `with`, the function is given a copy & the original is zeroized
+                Some(with_async) if zeroize => {
+                    let expunged = Ident::new("expunged", Span::mixed_site());
+                    quote_spanned! { span =>
+                        let #expunged = (#with_async)(::std::clone::Clone::clone(&#ident)).await;
+                        ::expunge::zeroize::Zeroize::zeroize(&mut #ident);
+                        #ident = #expunged;
+                    }
+                }
+                Some(with_async) => quote_spanned! { span =>
+                    #ident = (#with_async)(#ident).await;
+                },
+                None => builder.build(span, ident.clone())?,
+            };
+            // oversized values are replaced before any `with` function could process them
+            let expunged = match max_len {
+                Some(max_len) => {
+                    let len = Ident::new(&format!("{temp_prefix}_len"), Span::mixed_site());
+                    let zeroizer = zeroize.then(|| {
+                        quote_spanned! { span => ::expunge::zeroize::Zeroize::zeroize(&mut #ident); }
+                    });
+                    quote_spanned! { span =>
+                        let #len = ::expunge::utils::Oversized::byte_len(&#ident);
+                        if #len > #max_len {
+                            #zeroizer
+                            #ident = ::expunge::utils::Oversized::oversized(#len);
+                        } else {
+                            #expunged
+                        }
+                    }
+                }
+                None => expunged,
+            };
+            let expunged = quote! {
+                #expunged
+                #notify
+                #count
+            };
+            let expunged = match guard {
+                Some(guard) => quote! {
+                    if #guard {
+                        #expunged
+                    }
+                },
+                None => expunged,
+            };
+
+            // a rule of the policy overrides the attributes of the field
+            let path = match variant {
+                Some(variant) => format!("{container}.{variant}.{field_name}"),
+                None => format!("{container}.{field_name}"),
+            };
+            let policy = Ident::new("policy", Span::mixed_site());
+            let rule = if profiles.is_empty() {
+                quote! { ::expunge::policy::Policy::rule(#policy, #path) }
+            } else {
+                let (profiles, rules): (Vec<_>, Vec<_>) = profiles
+                    .iter()
+                    .map(|(profile, rule)| (profile.to_string(), rule))
+                    .unzip();
+                quote! {
+                    ::expunge::policy::Policy::rule(#policy, #path).or_else(|| {
+                        match ::expunge::profile::current().as_deref() {
+                            #(::std::option::Option::Some(#profiles) => ::std::option::Option::Some(&#rules),)*
+                            _ => ::std::option::Option::None,
+                        }
+                    })
+                }
+            };
+            let expunged = quote! {
+                match #rule {
+                    ::std::option::Option::Some(rule) => {
+                        #[allow(unused_imports)]
+                        use ::expunge::policy::{PolicyField, ViaAny, ViaExpunge, ViaText};
+
+                        #ident = (&&&PolicyField::new(#ident)).apply(rule);
+                    }
+                    ::std::option::Option::None => {
+                        #expunged
+                    }
+                }
+            };
+
+            Ok::<_, syn::Error>((
+                quote! {
+                    #condition
+                    #with_self
+                    #salt
+                },
+                quote! {
+                    #warning
+                    #expunged
+                },
+            ))
+        })
+        .collect::<Result<Vec<_>, syn::Error>>()?
+        .into_iter()
+        .unzip();
+
+    Ok((quote! { #(#preludes)* }, quote! { #(#bodies)* }))
+}
+
+/// A `with` function, or a closure that applies each function in an array in turn, e.g.
+/// `with = [str::trim, sha256::digest]`
+fn chain_with(expr: Expr) -> Result<TokenStream, syn::Error> {
+    let Expr::Array(array) = expr else {
+        return Ok(expr.into_token_stream());
+    };
+    if array.elems.is_empty() {
+        return Err(syn::Error::new(
+            array.span(),
+            format!("`{WITH}` expects at least one function"),
+        ));
+    }
+    let steps = array.elems.iter().map(|f| {
+        quote_spanned! { f.span() =>
+            let value = (#f)(value);
+        }
+    });
+    Ok(quote! {
+        |value| {
+            #(#steps)*
+            value
+        }
+    })
+}
+
+/// Parses each `name = rule` of `profile(...)`, where the rule is `skip`, `expunge`, `hash` or
+/// `mask(keep_first = N, keep_last = N)`
+fn parse_profile(
+    meta: &ParseNestedMeta,
+    profiles: &mut Vec<(Ident, TokenStream)>,
+) -> Result<(), syn::Error> {
+    let profile = meta.path.require_ident()?.clone();
+    if profiles.iter().any(|(existing, _)| *existing == profile) {
+        return Err(syn::Error::new(
+            profile.span(),
+            format!("the `{profile}` profile is specified more than once"),
+        ));
+    }
+    let value: Expr = meta.value()?.parse()?;
+    let expected = || {
+        syn::Error::new(
+            value.span(),
+            format!("expected a rule for the `{profile}` profile: `skip`, `expunge`, `{HASH}` or `mask(keep_first = N, keep_last = N)`"),
+        )
+    };
+    let (name, args) = match &value {
+        Expr::Path(path) => (path.path.require_ident().map_err(|_| expected())?, None),
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) => (
+                path.path.require_ident().map_err(|_| expected())?,
+                Some(&call.args),
+            ),
+            _ => return Err(expected()),
+        },
+        _ => return Err(expected()),
+    };
+    let rule = match (name.to_string().as_str(), args) {
+        ("skip", None) => quote! { ::expunge::policy::Rule::Skip },
+        ("expunge", None) => quote! { ::expunge::policy::Rule::Expunge },
+        (HASH, None) => {
+            if !cfg!(feature = "hash") {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("the `{HASH}` feature must be enabled"),
+                ));
+            }
+            quote! { ::expunge::policy::Rule::Hash }
+        }
+        ("mask", args) => {
+            let (mut keep_first, mut keep_last) = (0usize, 0usize);
+            for arg in args.into_iter().flatten() {
+                let Expr::Assign(assign) = arg else {
+                    return Err(expected());
+                };
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Int(n), ..
+                }) = &*assign.right
+                else {
+                    return Err(expected());
+                };
+                let n = n.base10_parse::<usize>()?;
+                match &*assign.left {
+                    Expr::Path(path) if path.path.is_ident(KEEP_FIRST) => keep_first = n,
+                    Expr::Path(path) if path.path.is_ident(KEEP_LAST) => keep_last = n,
+                    _ => return Err(expected()),
+                }
+            }
+            quote! {
+                ::expunge::policy::Rule::Mask {
+                    keep_first: #keep_first,
+                    keep_last: #keep_last,
+                }
+            }
+        }
+        _ => return Err(expected()),
+    };
+    profiles.push((profile, rule));
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct Class {
+    // an expression for the `expunge::Classification`
+    expr: TokenStream,
+    // the name it was given in the attribute
+    name: String,
+}
+
+/// The only field of a `transparent` newtype
+fn transparent_field(data: &Data) -> Result<&Field, syn::Error> {
+    let error = |span: Span| {
+        syn::Error::new(
+            span,
+            format!("`{TRANSPARENT}` is only supported on structs with a single field"),
+        )
+    };
+    match data {
+        Data::Struct(s) if s.fields.len() == 1 => Ok(s.fields.iter().next().expect("one field")),
+        Data::Struct(s) => Err(error(s.fields.span())),
+        Data::Enum(e) => Err(error(e.enum_token.span())),
+        Data::Union(u) => Err(error(u.union_token.span())),
+    }
+}
+
+/// Whether the attributes include `#[expunge]` or `#[expunge_field]`, with or without options
+fn is_annotated(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| ATTRIBUTES.iter().any(|name| attr.path().is_ident(name)))
+}
+
+/// Ensures that every field is annotated, either directly or by its variant, so that none is
+/// expunged by default without a decision having been made
+fn check_annotated(data: &Data) -> Result<(), syn::Error> {
+    let check = |fields: &Fields| -> Result<(), syn::Error> {
+        for (i, field) in fields.iter().enumerate() {
+            if !is_annotated(&field.attrs) {
+                let name = field
+                    .ident
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or(i.to_string());
+                return Err(syn::Error::new(
+                    field.span(),
+                    format!("field `{name}` must be annotated with `#[expunge(...)]` or `#[expunge(skip)]` since the container is marked `{DENY_UNANNOTATED}`"),
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    match data {
+        Data::Struct(s) => check(&s.fields),
+        Data::Enum(e) => e
+            .variants
+            .iter()
+            .filter(|variant| !is_annotated(&variant.attrs))
+            .try_for_each(|variant| check(&variant.fields)),
+        // only the field annotated with `as` is used, which is already required
+        Data::Union(_) => Ok(()),
+    }
+}
+
+/// Ensures that no field classified as `secret` would be persisted in plaintext, i.e. that each
+/// one is always hashed or encrypted
+fn check_db_write_safe(data: &Data, parent: &Builder) -> Result<(), syn::Error> {
+    let check = |fields: &Fields, parent: &Builder| -> Result<(), syn::Error> {
+        for (i, field) in fields.iter().enumerate() {
+            let builder = field_builder(field, parent)?;
+            let is_secret = builder
+                .class
+                .as_ref()
+                .is_some_and(|class| class.name.eq_ignore_ascii_case("secret"));
+            if !is_secret {
+                continue;
+            }
+            let name = field
+                .ident
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or(i.to_string());
+            if builder.skip || !(builder.hash || builder.encrypt) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("field `{name}` is classified as `secret`, so it must use `{HASH}` or `{ENCRYPT}` since the container is marked `{DB_WRITE_SAFE}`"),
+                ));
+            }
+            // the plaintext would be persisted whenever the condition doesn't hold
+            if builder.condition.is_some() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("field `{name}` is classified as `secret`, so it cannot use `{IF}` or `{UNLESS}` since the container is marked `{DB_WRITE_SAFE}`"),
+                ));
+            }
+        }
+        Ok(())
+    };
+
+    match data {
+        Data::Struct(s) => check(&s.fields, parent),
+        Data::Enum(e) => {
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                check(&variant.fields, &parent)?;
+            }
+            Ok(())
+        }
+        Data::Union(u) => check(&Fields::Named(u.fields.clone()), parent),
+    }
+}
+
+/// What expunged values of a type are replaced with, for `reject_redacted_input`
+#[derive(Default)]
+struct Redactions {
+    // the placeholder of the generated `Debug` & every `as` that's a string literal
+    placeholders: Vec<String>,
+    // the `(keep_first, keep_last)` of each masked field
+    masks: Vec<(usize, usize)>,
+    // whether any field is hashed
+    hashed: bool,
+    // the types of fields that are expunged by their own `Expunge` implementation
+    nested: Vec<Type>,
+}
+
+fn redactions(data: &Data, parent: &Builder) -> Result<Redactions, syn::Error> {
+    let mut redactions = Redactions::default();
+    let mut collect = |builder: &Builder, ty: Option<&Type>| {
+        let placeholder = match &builder.debug_placeholder {
+            Some(placeholder) => placeholder.value(),
+            None => "<expunged>".to_string(),
+        };
+        redactions.placeholders.push(placeholder);
+        if let Some(literal) = builder
+            .expunge_as
+            .as_ref()
+            .and_then(|expunge_as| syn::parse2(expunge_as.clone()).ok())
+            .and_then(|expr| literal_value(&expr))
+        {
+            redactions.placeholders.push(literal);
+        }
+        if builder.keep_first.is_some() || builder.keep_last.is_some() {
+            let mask = (
+                builder.keep_first.unwrap_or_default(),
+                builder.keep_last.unwrap_or_default(),
+            );
+            if !redactions.masks.contains(&mask) {
+                redactions.masks.push(mask);
+            }
+        }
+        redactions.hashed |= builder.hash;
+        if let Some(ty) = ty {
+            if !builder.skip && !builder.has_transformation() {
+                redactions.nested.push(ty.clone());
+            }
+        }
+    };
+    collect(parent, None);
+    match data {
+        Data::Struct(s) => {
+            for field in &s.fields {
+                collect(&field_builder(field, parent)?, Some(&field.ty));
+            }
+        }
+        Data::Enum(e) => {
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                collect(&parent, None);
+                for field in &variant.fields {
+                    collect(&field_builder(field, &parent)?, Some(&field.ty));
+                }
+            }
+        }
+        Data::Union(u) => {
+            for field in &u.fields.named {
+                collect(&field_builder(field, parent)?, Some(&field.ty));
+            }
+        }
+    }
+    // an empty string is also what most types are expunged to by default, so it can't be rejected
+    redactions
+        .placeholders
+        .retain(|placeholder| !placeholder.is_empty());
+    redactions.placeholders.sort();
+    redactions.placeholders.dedup();
+    Ok(redactions)
+}
+
+/// The string that an `as` expression always evaluates to, if it's a string literal or a
+/// conversion of one, e.g. `"<redacted>".to_string()` or `String::from("<redacted>")`, but not
+/// `"*".repeat(8)`
+fn literal_value(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Some(s.value()),
+        Expr::Paren(paren) => literal_value(&paren.expr),
+        Expr::Group(group) => literal_value(&group.expr),
+        Expr::MethodCall(call)
+            if call.args.is_empty()
+                && ["to_string", "to_owned", "into"].contains(&&*call.method.to_string()) =>
+        {
+            literal_value(&call.receiver)
+        }
+        Expr::Call(call) if call.args.len() == 1 => match &*call.func {
+            Expr::Path(path)
+                if path.path.segments.last().is_some_and(|segment| {
+                    ["from", "new", "Borrowed", "Owned"].contains(&&*segment.ident.to_string())
+                }) =>
+            {
+                literal_value(&call.args[0])
+            }
+            _ => None,
+        },
+        // `as_str` keeps its placeholder in a static
+        Expr::Block(block) => block.block.stmts.iter().find_map(|stmt| match stmt {
+            Stmt::Item(Item::Static(item)) => literal_value(&item.expr),
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+/// Parses `group = "gdpr"` or `group = ["gdpr", "pci"]` into the names of the groups
+fn parse_groups(expr: &Expr) -> Result<Vec<String>, syn::Error> {
+    let expected = |span: Span| {
+        syn::Error::new(
+            span,
+            format!("expected a string or an array of strings for `{GROUP}`"),
+        )
+    };
+    let group = |expr: &Expr| match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.value()),
+        _ => Err(expected(expr.span())),
+    };
+    match expr {
+        Expr::Array(array) if array.elems.is_empty() => Err(expected(array.span())),
+        Expr::Array(array) => array.elems.iter().map(group).collect(),
+        expr => Ok(vec![group(expr)?]),
+    }
+}
+
+/// The groups of each field that belongs to any, keyed by its path as in `expunge::policy`
+fn group_fields(
+    name: &Ident,
+    data: &Data,
+    parent: &Builder,
+) -> Result<Vec<(String, Vec<String>)>, syn::Error> {
+    let groups = |variant: Option<&Ident>, fields: &Fields, parent: &Builder| {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let builder =
+                    parse_attributes(field.span(), Some(parent.clone()), field.attrs.clone())?
+                        .unwrap_or(parent.clone());
+                let field_name = field
+                    .ident
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or(i.to_string());
+                let path = match variant {
+                    Some(variant) => format!("{name}.{variant}.{field_name}"),
+                    None => format!("{name}.{field_name}"),
+                };
+                Ok((path, builder.groups))
+            })
+            .filter(|field| !matches!(field, Ok((_, groups)) if groups.is_empty()))
+            .collect::<Result<Vec<_>, syn::Error>>()
+    };
+
+    match data {
+        Data::Struct(s) => groups(None, &s.fields, parent),
+        Data::Enum(e) => {
+            let mut fields = vec![];
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                fields.extend(groups(Some(&variant.ident), &variant.fields, &parent)?);
+            }
+            Ok(fields)
+        }
+        Data::Union(u) => match groups(None, &Fields::Named(u.fields.clone()), parent)?.first() {
+            Some(_) => Err(syn::Error::new(
+                u.union_token.span(),
+                format!("`{GROUP}` is not supported on unions"),
+            )),
+            None => Ok(vec![]),
+        },
+    }
+}
+
+/// Parses `class = pii` or `class = "custom"` into an `expunge::Classification` expression
+fn parse_class(expr: &Expr) -> Result<Class, syn::Error> {
+    match expr {
+        Expr::Path(path) => {
+            let name = path.path.get_ident().map(ToString::to_string);
+            let variant = match name.as_deref() {
+                Some("pii") => quote! { Pii },
+                Some("spi") => quote! { Spi },
+                Some("secret") => quote! { Secret },
+                Some("financial") => quote! { Financial },
+                Some("health") => quote! { Health },
+                _ => {
+                    return Err(syn::Error::new(
+                        expr.span(),
+                        format!("unrecognized `{CLASS}`, expected one of `pii`, `spi`, `secret`, `financial`, `health` or a string"),
                     ))
                 }
-            })?;
+            };
+            Ok(Class {
+                expr: quote! { ::expunge::Classification::#variant },
+                name: name.unwrap_or_default(),
+            })
+        }
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(Class {
+            expr: quote! {
+                <::expunge::Classification as ::std::str::FromStr>::from_str(#s).unwrap()
+            },
+            name: s.value(),
+        }),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            format!("expected an identifier or string for `{CLASS}`"),
+        )),
+    }
+}
 
-            Ok(Some(builder))
+fn notify_observers(
+    container: &Ident,
+    variant: Option<&Ident>,
+    field: &str,
+    class: Option<&Class>,
+) -> TokenStream {
+    let type_name = container.to_string();
+    let variant = match variant {
+        Some(variant) => {
+            let variant = variant.to_string();
+            quote! { Some(#variant) }
         }
-        n => Err(syn::Error::new(
-            span,
-            format!("expected 1 or 0 `expunge` tags, found {n}"),
+        None => quote! { None },
+    };
+    let class = match class {
+        Some(Class { expr, .. }) => quote! { Some(#expr) },
+        None => quote! { None },
+    };
+    quote! {
+        if ::expunge::telemetry::is_observed() {
+            ::expunge::telemetry::observe(#type_name, #variant, #field, #class);
+        }
+    }
+}
+
+fn count_field(container: &Ident, variant: Option<&Ident>, field: &str) -> TokenStream {
+    let type_name = match variant {
+        Some(variant) => format!("{container}::{variant}"),
+        None => container.to_string(),
+    };
+    quote! {
+        ::expunge::telemetry::count(#type_name, #field);
+    }
+}
+
+/// Types with a `clear` method
+const CLEARABLE: &[&str] = &[
+    "String",
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "BinaryHeap",
+    "HashMap",
+    "HashSet",
+    "BTreeMap",
+    "BTreeSet",
+];
+
+/// The name of the type's last path segment and its first generic type argument, e.g. `Option` &
+/// `T` for `std::option::Option<T>`
+fn wrapper(ty: &Type) -> Option<(&str, Option<&Type>)> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segment = path.path.segments.last()?;
+    let name = match segment.ident.to_string().as_str() {
+        "Option" => "Option",
+        "Box" => "Box",
+        other => CLEARABLE.iter().find(|clearable| **clearable == other)?,
+    };
+    let arg = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        }),
+        _ => None,
+    };
+    Some((name, arg))
+}
+
+/// Clears the value, given a `&mut` reference to it. `Option`s are cleared if they're `Some` and
+/// `Box`es are cleared through.
+fn clear_value(ty: &Type, value: TokenStream) -> Result<TokenStream, syn::Error> {
+    match wrapper(ty) {
+        Some(("Option", Some(inner))) => {
+            let clear = clear_value(inner, value.clone())?;
+            Ok(quote! {
+                if let ::std::option::Option::Some(#value) = ::std::option::Option::as_mut(#value) {
+                    #clear
+                }
+            })
+        }
+        Some(("Box", Some(inner))) => {
+            let clear = clear_value(inner, value.clone())?;
+            Ok(quote! {
+                let #value = &mut **#value;
+                #clear
+            })
+        }
+        Some((name, _)) if name != "Option" && name != "Box" => Ok(quote! {
+            #value.clear();
+        }),
+        _ => Err(syn::Error::new(
+            ty.span(),
+            format!("`{CLEAR}` can only be used on strings & std collections, optionally wrapped in `Option` or `Box`"),
         )),
     }
 }
 
-fn derive_fields(
-    is_enum: bool,
-    prefix: TokenStream,
-    fields: impl IntoIterator<Item = Field>,
-    parent: Builder,
-) -> Result<TokenStream, syn::Error> {
-    fields
-        .into_iter()
-        .enumerate()
-        .map(|(i, field)| {
-            let span = field.span();
-            let builder = parse_attributes(span, Some(parent.clone()), field.attrs)?
-                .map(|f| {
-                    let Builder {
-                        expunge_as,
-                        expunge_with,
-                        skip,
-                        zeroize,
-                        slog,
-                        debug_allowed,
-                    } = f;
-                    let (expunge_as, expunge_with) = match (expunge_as, expunge_with) {
-                        (Some(ra), None) => (Some(ra), None),
-                        (None, Some(rw)) => (None, Some(rw)),
-                        (None, None) => (parent.expunge_as.clone(), parent.expunge_with.clone()),
-                        (Some(_), Some(_)) => {
-                            return Err(syn::Error::new(span, "`as` and `with` cannot be combined"))
-                        }
-                    };
-                    let skip = skip || parent.skip;
-                    let zeroize = zeroize || parent.zeroize;
-                    Ok(Builder {
-                        expunge_as,
-                        expunge_with,
-                        skip,
-                        zeroize,
-                        slog,
-                        debug_allowed,
-                    })
-                })
-                .transpose()?;
+/// Clears the strings in the value in place, given a `&mut` reference to it, if that has the same
+/// result as `Expunge::expunge`, i.e. for a `String` that may be wrapped in an `Option`, `Box`,
+/// `Vec` or `VecDeque`
+fn clear_strings(ty: &Type, value: TokenStream) -> Option<TokenStream> {
+    let (wrapper, inner) = match wrapper(ty)? {
+        ("String", _) => return Some(quote! { #value.clear(); }),
+        (wrapper @ ("Option" | "Box" | "Vec" | "VecDeque"), Some(inner)) => (wrapper, inner),
+        _ => return None,
+    };
+    let clear = clear_strings(inner, value.clone())?;
+    Some(match wrapper {
+        "Option" => quote! {
+            if let ::std::option::Option::Some(#value) = ::std::option::Option::as_mut(#value) {
+                #clear
+            }
+        },
+        "Box" => quote! {
+            let #value = &mut **#value;
+            #clear
+        },
+        _ => quote! {
+            for #value in #value.iter_mut() {
+                #clear
+            }
+        },
+    })
+}
 
-            let builder = builder.or(Some(parent.clone()));
+const COPY_PRIMITIVES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize", "f32",
+    "f64", "bool", "char",
+];
 
-            Ok(builder
-                .map(|builder| {
-                    let ident = match field.ident {
-                        Some(named) => {
-                            if is_enum {
-                                named.into_token_stream()
-                            } else {
-                                quote! { #prefix.#named }
-                            }
-                        }
-                        None => {
-                            if is_enum {
-                                Ident::new(&format!("{prefix}{i}"), span).into_token_stream()
-                            } else {
-                                let index = Index::from(i);
-                                quote! { #prefix.#index }
-                            }
-                        }
-                    };
+/// Whether the type is obviously `Copy`, i.e. a primitive. Aliases & other `Copy` types can't be
+/// detected from the tokens alone.
+fn is_copy(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .get_ident()
+            .is_some_and(|ident| COPY_PRIMITIVES.contains(&ident.to_string().as_str())),
+        _ => false,
+    }
+}
 
-                    builder.build(span, ident)
-                })
-                .transpose()?
-                .unwrap_or(TokenStream::default()))
-        })
-        .collect()
+/// Zeroizing a `Copy` type only zeroizes the current copy, so it has no real effect. Since
+/// proc_macros can't emit warnings on stable, a deprecated item is used to trigger one instead.
+fn zeroize_copy_warning(builder: &Builder, ty: &Type) -> Result<TokenStream, syn::Error> {
+    let message = format!(
+        "`{ZEROIZE}` has no effect on `Copy` types, since other copies of the value are left as-is"
+    );
+    if builder.strict {
+        return Err(syn::Error::new(ty.span(), message));
+    }
+    Ok(quote_spanned! { ty.span() =>
+        const _: () = {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const zeroize_on_copy_type: () = ();
+            zeroize_on_copy_type
+        };
+    })
 }
 
 fn get_fields(fields: Fields) -> Option<impl IntoIterator<Item = Field>> {
@@ -411,12 +2654,18 @@ fn get_fields(fields: Fields) -> Option<impl IntoIterator<Item = Field>> {
     }
 }
 
-fn derive_struct(s: DataStruct, parent: Builder) -> Result<TokenStream, syn::Error> {
-    let impls = get_fields(s.fields)
-        .map(|fields| derive_fields(false, quote! { next }, fields, parent))
-        .transpose()?;
+fn derive_struct(name: &Ident, s: DataStruct, parent: Builder) -> Result<TokenStream, syn::Error> {
+    let (prelude, impls) = get_fields(s.fields)
+        .map(|fields| {
+            let fields = fields.into_iter().enumerate();
+            derive_fields(name, None, quote! { next }, fields, parent, false)
+        })
+        .transpose()?
+        .unwrap_or_default();
 
     Ok(quote! {
+        #prelude
+
         let mut next = self;
 
         #impls
@@ -425,7 +2674,7 @@ fn derive_struct(s: DataStruct, parent: Builder) -> Result<TokenStream, syn::Err
     })
 }
 
-fn derive_enum(e: DataEnum, parent: Builder) -> Result<TokenStream, syn::Error> {
+fn derive_enum(name: &Ident, e: DataEnum, parent: Builder) -> Result<TokenStream, syn::Error> {
     let span = e.enum_token.span();
 
     let variant_idents = e.variants.iter().map(|variant| &variant.ident);
@@ -469,12 +2718,23 @@ fn derive_enum(e: DataEnum, parent: Builder) -> Result<TokenStream, syn::Error>
         syn::Fields::Unit => Default::default(),
     });
 
-    let variant_bodies: Result<Vec<TokenStream>, syn::Error> = e
+    let variant_bodies: Result<Vec<(TokenStream, TokenStream)>, syn::Error> = e
         .variants
         .iter()
         .map(|variant| {
-            let parent = parse_attributes(span, Some(parent.clone()), variant.attrs.clone())?
-                .unwrap_or(parent.clone());
+            let parent = variant_builder(variant, &parent)?;
+            if parent.expunge_with_self.is_some() {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    format!("`{WITH_SELF}` is only supported on struct fields"),
+                ));
+            }
+            if parent.condition.is_some() {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    format!("`{IF}` and `{UNLESS}` are not permitted on variants"),
+                ));
+            }
 
             let prefix = if let Fields::Unnamed(..) = &variant.fields {
                 quote! { arg }
@@ -483,20 +2743,672 @@ fn derive_enum(e: DataEnum, parent: Builder) -> Result<TokenStream, syn::Error>
             };
 
             get_fields(variant.fields.clone())
-                .map(|fields| derive_fields(true, prefix, fields, parent))
+                .map(|fields| {
+                    let fields = fields.into_iter().enumerate();
+                    derive_fields(name, Some(&variant.ident), prefix, fields, parent, false)
+                })
                 .transpose()
                 .map(Option::unwrap_or_default)
         })
         .collect();
 
-    let bodies = variant_bodies?.into_iter();
+    let (preludes, bodies): (Vec<_>, Vec<_>) = variant_bodies?.into_iter().unzip();
+
+    let arms = e
+        .variants
+        .iter()
+        .zip(variant_idents)
+        .zip(variant_destructures_mut.zip(variant_destructures))
+        .zip(preludes.into_iter().zip(bodies))
+        .map(
+            |(((variant, ident), (destructure_mut, destructure)), (prelude, body))| {
+                let parent = variant_builder(variant, &parent)?;
+                Ok(match variant_as(&e, variant, &parent)? {
+                    // the fields are dropped along with the variant
+                    Some(variant_as) => quote_spanned! { variant_as.span() =>
+                        Self::#ident { .. } => Self::#variant_as,
+                    },
+                    None if prelude.is_empty() => quote! {
+                        Self::#ident #destructure_mut => {
+                            #body
+                            Self::#ident #destructure
+                        },
+                    },
+                    // the prelude only runs for its own variant, & before the fields are moved
+                    // out of `self` since conditions can refer to it
+                    None => quote! {
+                        Self::#ident { .. } => {
+                            #prelude
+                            #[allow(unreachable_patterns)]
+                            match self {
+                                Self::#ident #destructure_mut => {
+                                    #body
+                                    Self::#ident #destructure
+                                },
+                                _ => ::std::unreachable!(),
+                            }
+                        },
+                    },
+                })
+            },
+        )
+        .collect::<Result<Vec<_>, syn::Error>>()?;
 
     Ok(quote_spanned! { span =>
         match self {
-                    #(Self::#variant_idents #variant_destructures_mut => {
-                        #bodies
-                        Self::#variant_idents #variant_destructures
-                    },)*
+            #(#arms)*
+        }
+    })
+}
+
+/// The unit variant that the variant is replaced with by `variant_as`, either its own or the
+/// enum's, unless the variant is skipped
+fn variant_as(
+    e: &DataEnum,
+    variant: &syn::Variant,
+    parent: &Builder,
+) -> Result<Option<Ident>, syn::Error> {
+    let own = own_attributes(variant.span(), &variant.attrs)?;
+    let Some(target) = own.variant_as.or(parent.variant_as.clone()) else {
+        return Ok(None);
+    };
+    if parent.skip {
+        return Ok(None);
+    }
+    match e.variants.iter().find(|variant| variant.ident == target) {
+        Some(fallback) if matches!(fallback.fields, Fields::Unit) => Ok(Some(target)),
+        Some(_) => Err(syn::Error::new(
+            target.span(),
+            format!("`{VARIANT_AS}` must name a unit variant"),
+        )),
+        None => Err(syn::Error::new(
+            target.span(),
+            format!("`{VARIANT_AS}` must name a variant of this enum"),
+        )),
+    }
+}
+
+/// Finds the field of a union annotated with `as`, which the whole union is replaced with since
+/// the active field can't be known
+fn union_field(u: &DataUnion, parent: &Builder) -> Result<(Field, Builder), syn::Error> {
+    if parent.has_transformation() || parent.zeroize || parent.expunge_with_async.is_some() {
+        return Err(syn::Error::new(
+            u.union_token.span(),
+            format!("unions only support `{AS}` on a single field"),
+        ));
+    }
+
+    let mut annotated = vec![];
+    for field in &u.fields.named {
+        let span = field.span();
+        if !is_annotated(&field.attrs) {
+            continue;
+        }
+        let builder =
+            parse_attributes(span, Some(parent.clone()), field.attrs.clone())?.unwrap_or_default();
+        let is_as_only = builder.expunge_as.is_some()
+            && builder.expunge_with.is_none()
+            && builder.expunge_with_self.is_none()
+            && builder.expunge_with_async.is_none()
+            && !(builder.none || builder.clear || builder.hash || builder.skip)
+            && !(builder.zeroize || builder.inherit)
+            && builder.keep_first.is_none()
+            && builder.keep_last.is_none()
+            && builder.condition.is_none()
+            && builder.profiles.is_empty();
+        if !is_as_only {
+            return Err(syn::Error::new(
+                span,
+                format!("only `{AS}` or `{DEFAULT}` are supported on union fields"),
+            ));
+        }
+        annotated.push((field.clone(), builder));
+    }
+
+    match annotated.len() {
+        1 => Ok(annotated.remove(0)),
+        0 => Err(syn::Error::new(
+            u.union_token.span(),
+            format!("unions require a single field to be annotated with `{AS}`, which replaces the whole content of the union"),
+        )),
+        _ => Err(syn::Error::new(
+            annotated[1].0.span(),
+            format!("only one union field can be annotated with `{AS}`, since it replaces the whole content of the union"),
+        )),
+    }
+}
+
+fn derive_union(name: &Ident, u: DataUnion, parent: Builder) -> Result<TokenStream, syn::Error> {
+    let (field, builder) = union_field(&u, &parent)?;
+    let ident = field.ident.as_ref().expect("union fields are named");
+    let ty = &field.ty;
+    let expunge_as = check_as_value(ty, builder.expunge_as.expect("checked by union_field"));
+
+    let field_name = ident.to_string();
+    let notify = if cfg!(feature = "observer") {
+        notify_observers(name, None, &field_name, builder.class.as_ref())
+    } else {
+        TokenStream::default()
+    };
+    let count = if cfg!(feature = "metrics") {
+        count_field(name, None, &field_name)
+    } else {
+        TokenStream::default()
+    };
+
+    // the previous content is simply overwritten, so it must not need dropping
+    Ok(quote_spanned! { ty.span() =>
+        fn assert_copy<T: ::std::marker::Copy>() {}
+        assert_copy::<#ty>();
+
+        #notify
+        #count
+
+        Self { #ident: #expunge_as }
+    })
+}
+
+/// Checks that an `as` value has the same type as the field, so that mismatches are reported in
+/// terms of the attribute
+fn check_as_value(ty: &Type, expunge_as: TokenStream) -> TokenStream {
+    quote_spanned! { expunge_as.span() =>
+        ::expunge::checks::as_value_must_match_field_type::<#ty>(#expunge_as)
+    }
+}
+
+/// Whether any field is expunged with `with_async`, in which case `ExpungeAsync` is implemented
+fn uses_with_async(data: &Data, parent: &Builder) -> Result<bool, syn::Error> {
+    let uses = |fields: &Fields, parent: &Builder| -> Result<bool, syn::Error> {
+        for field in fields {
+            let builder = field_builder(field, parent)?;
+            if builder.expunge_with_async.is_some() && !builder.skip {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    };
+
+    match data {
+        Data::Struct(s) => uses(&s.fields, parent),
+        Data::Enum(e) => {
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                if uses(&variant.fields, &parent)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        // unions only support `as`
+        Data::Union(_) => Ok(false),
+    }
+}
+
+fn is_skipped(field: &Field, parent: &Builder) -> Result<bool, syn::Error> {
+    Ok(field_builder(field, parent)?.skip || is_marker(&field.ty))
+}
+
+/// The attributes of a field resolved against its parent's. A field's own transformation or
+/// `skip` takes precedence over its variant's, which takes precedence over the container's.
+fn field_builder(field: &Field, parent: &Builder) -> Result<Builder, syn::Error> {
+    let span = field.span();
+    if parent.opt_in && !is_annotated(&field.attrs) {
+        return Ok(Builder {
+            skip: true,
+            ..parent.clone()
+        });
+    }
+    Ok(
+        parse_attributes(span, Some(parent.clone()), field.attrs.clone())?
+            .map(|builder| builder.resolve(parent, span))
+            .transpose()?
+            .unwrap_or_else(|| parent.clone()),
+    )
+}
+
+/// The attributes of a variant resolved against the container's, so that its fields inherit the
+/// container's transformation unless the variant specifies its own
+fn variant_builder(variant: &syn::Variant, parent: &Builder) -> Result<Builder, syn::Error> {
+    let span = variant.span();
+    let Some(mut builder) = parse_attributes(span, Some(parent.clone()), variant.attrs.clone())?
+    else {
+        return Ok(parent.clone());
+    };
+    if builder.inherit {
+        return Err(syn::Error::new(
+            span,
+            format!("`{INHERIT}` is not permitted on variants"),
+        ));
+    }
+    // annotating a variant opts in its unannotated fields
+    builder.opt_in = builder.opt_in && !is_annotated(&variant.attrs);
+    builder.resolve(parent, span)
+}
+
+/// Whether the type is a marker without any data, i.e. `PhantomData` or `PhantomPinned`
+fn is_marker(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path.path.segments.last().is_some_and(|segment| {
+            segment.ident == "PhantomData" || segment.ident == "PhantomPinned"
+        })
+}
+
+/// A `&dyn Debug` for the field, which is a placeholder for sensitive fields
+fn debug_value(
+    field: &Field,
+    binding: &TokenStream,
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    let builder = field_builder(field, parent)?;
+    let is_shown =
+        builder.skip || builder.show_in_debug || parent.show_in_debug || is_marker(&field.ty);
+    Ok(if is_shown {
+        quote! { (&&Field(#binding)).as_debug() }
+    } else {
+        let placeholder = placeholder(parent);
+        quote! { &#placeholder }
+    })
+}
+
+/// Debug arguments for each field, printing a placeholder for sensitive fields without cloning or
+/// allocating.
+fn debug_fields(
+    fields: &Fields,
+    bindings: &[TokenStream],
+    parent: &Builder,
+) -> Result<Vec<TokenStream>, syn::Error> {
+    fields
+        .iter()
+        .zip(bindings)
+        .map(|(field, binding)| {
+            let value = debug_value(field, binding, parent)?;
+            Ok(match &field.ident {
+                Some(ident) => {
+                    let name = ident.to_string();
+                    quote! { .field(#name, #value) }
+                }
+                None => quote! { .field(#value) },
+            })
+        })
+        .collect()
+}
+
+fn debug_fields_body(
+    name: String,
+    fields: &Fields,
+    bindings: &[TokenStream],
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    let args = debug_fields(fields, bindings, parent)?;
+    Ok(match fields {
+        Fields::Named(..) => quote! { f.debug_struct(#name) #(#args)* .finish() },
+        Fields::Unnamed(..) => quote! { f.debug_tuple(#name) #(#args)* .finish() },
+        Fields::Unit => quote! { f.write_str(#name) },
+    })
+}
+
+/// The number of fields that are expunged, out of the total, as a `(usize, usize)` expression
+fn derive_summary(data: &Data, parent: &Builder) -> Result<TokenStream, syn::Error> {
+    let count = |fields: &Fields, parent: &Builder| -> Result<TokenStream, syn::Error> {
+        let mut redacted = 0usize;
+        for field in fields {
+            if !is_skipped(field, parent)? {
+                redacted += 1;
+            }
+        }
+        let total = fields.len();
+        Ok(quote! { (#redacted, #total) })
+    };
+
+    match data {
+        Data::Struct(s) => count(&s.fields, parent),
+        Data::Enum(e) if e.variants.is_empty() => Ok(quote! { (0, 0) }),
+        Data::Enum(e) => {
+            let arms = e
+                .variants
+                .iter()
+                .map(|variant| {
+                    let parent = variant_builder(variant, parent)?;
+                    let ident = &variant.ident;
+                    let count = count(&variant.fields, &parent)?;
+                    Ok(quote! { Self::#ident { .. } => #count, })
+                })
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+            Ok(quote! {
+                match self {
+                    #(#arms)*
+                }
+            })
+        }
+        // the whole content is replaced
+        Data::Union(_) => Ok(quote! { (1, 1) }),
+    }
+}
+
+/// Printed in place of sensitive values by the generated `Debug`
+fn placeholder(parent: &Builder) -> TokenStream {
+    match &parent.debug_placeholder {
+        Some(placeholder) => quote! { Text(#placeholder) },
+        None => quote! { Placeholder },
+    }
+}
+
+fn derive_debug(name: &Ident, data: &Data, parent: &Builder) -> Result<TokenStream, syn::Error> {
+    if parent.debug_opaque {
+        let placeholder = placeholder(parent);
+        return Ok(quote! { ::std::fmt::Debug::fmt(&#placeholder, f) });
+    }
+
+    // printed as the inner value, without the name of the newtype
+    if parent.transparent {
+        let field = transparent_field(data)?;
+        let binding = match &field.ident {
+            Some(ident) => quote! { &self.#ident },
+            None => quote! { &self.0 },
+        };
+        let value = debug_value(field, &binding, parent)?;
+        return Ok(quote! { ::std::fmt::Debug::fmt(#value, f) });
+    }
+
+    match data {
+        Data::Struct(s) => {
+            let bindings: Vec<_> = s
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| match &field.ident {
+                    Some(ident) => quote! { &self.#ident },
+                    None => {
+                        let index = Index::from(i);
+                        quote! { &self.#index }
+                    }
+                })
+                .collect();
+            debug_fields_body(name.to_string(), &s.fields, &bindings, parent)
+        }
+        Data::Enum(e) => {
+            let arms = e
+                .variants
+                .iter()
+                .map(|variant| {
+                    let span = variant.span();
+                    let parent = variant_builder(variant, parent)?;
+                    let ident = &variant.ident;
+                    if let Some(variant_as) = variant_as(e, variant, &parent)? {
+                        let variant_as = variant_as.to_string();
+                        return Ok(quote! { Self::#ident { .. } => f.write_str(#variant_as), });
+                    }
+                    let bindings: Vec<_> = variant
+                        .fields
+                        .iter()
+                        .enumerate()
+                        .map(|(i, field)| match &field.ident {
+                            Some(ident) => ident.into_token_stream(),
+                            None => Ident::new(&format!("arg{i}"), span).into_token_stream(),
+                        })
+                        .collect();
+                    let pattern = match &variant.fields {
+                        Fields::Named(..) => quote! { { #(#bindings),* } },
+                        Fields::Unnamed(..) => quote! { ( #(#bindings),* ) },
+                        Fields::Unit => TokenStream::default(),
+                    };
+                    let body =
+                        debug_fields_body(ident.to_string(), &variant.fields, &bindings, &parent)?;
+                    Ok(quote! { Self::#ident #pattern => #body, })
+                })
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+            Ok(quote! {
+                match self {
+                    #(#arms)*
+                }
+            })
+        }
+        // the active field is unknown, so none of them can be read
+        Data::Union(_) => {
+            let name = name.to_string();
+            Ok(quote! {
+                f.debug_struct(#name).finish_non_exhaustive()
+            })
+        }
+    }
+}
+
+/// The attributes of a field or variant without those of its parent, e.g. to find an `error`
+/// message that mustn't be inherited
+fn own_attributes(span: Span, attrs: &[Attribute]) -> Result<Builder, syn::Error> {
+    Ok(parse_attributes(span, Some(Builder::default()), attrs.to_vec())?.unwrap_or_default())
+}
+
+/// The names the fields of a struct or variant are bound to, the same as in the generated `Debug`
+fn field_bindings(fields: &Fields, span: Span) -> Vec<Ident> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("arg{i}"), span),
+        })
+        .collect()
+}
+
+fn field_pattern(fields: &Fields, bindings: &[Ident]) -> TokenStream {
+    match fields {
+        Fields::Named(..) => quote! { { #(#bindings),* } },
+        Fields::Unnamed(..) => quote! { ( #(#bindings),* ) },
+        Fields::Unit => TokenStream::default(),
+    }
+}
+
+/// Rejects `error` messages & `source` fields where they'd be ignored
+fn check_error_attributes(data: &Data, parent: &Builder) -> Result<(), syn::Error> {
+    let fields: Vec<&Field> = match data {
+        Data::Struct(s) => s.fields.iter().collect(),
+        Data::Enum(e) => {
+            if parent.error_message.is_some() {
+                return Err(syn::Error::new(
+                    parent.error_message.span(),
+                    format!("`{ERROR} = \"...\"` is not permitted on enums, use it on each variant instead"),
+                ));
+            }
+            for variant in &e.variants {
+                let builder = own_attributes(variant.span(), &variant.attrs)?;
+                if builder.error_message.is_some() && !parent.error {
+                    return Err(syn::Error::new(
+                        builder.error_message.span(),
+                        format!(
+                            "`{ERROR} = \"...\"` requires `#[expunge({ERROR})]` on the container"
+                        ),
+                    ));
+                }
+            }
+            e.variants.iter().flat_map(|v| &v.fields).collect()
+        }
+        Data::Union(u) => {
+            if parent.error {
+                return Err(syn::Error::new(
+                    u.union_token.span(),
+                    format!("`{ERROR}` is not supported on unions"),
+                ));
+            }
+            u.fields.named.iter().collect()
+        }
+    };
+
+    for field in fields {
+        let builder = own_attributes(field.span(), &field.attrs)?;
+        if let Some(message) = builder.error_message {
+            return Err(syn::Error::new(
+                message.span(),
+                format!("`{ERROR} = \"...\"` is not permitted on fields"),
+            ));
+        }
+        if builder.source && !parent.error {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("`{SOURCE}` requires `#[expunge({ERROR})]` on the container"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites positional arguments such as `{0}` in an `error` message to the names tuple fields are
+/// bound to, so that every field can be captured by the format string
+fn error_message(message: &LitStr) -> LitStr {
+    let value = message.value();
+    let mut rewritten = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        rewritten.push(c);
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => rewritten.extend(chars.next()),
+            ('{', Some(next)) if next.is_ascii_digit() => rewritten.push_str("arg"),
+            _ => {}
+        }
+    }
+    LitStr::new(&rewritten, message.span())
+}
+
+/// Formats the `error` message with a placeholder in place of each sensitive field, or prints the
+/// fields like `Debug` when there's no message
+fn error_display_body(
+    name: String,
+    message: Option<&LitStr>,
+    fields: &Fields,
+    bindings: &[Ident],
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    let Some(message) = message else {
+        let bindings: Vec<_> = bindings.iter().map(ToTokens::to_token_stream).collect();
+        return debug_fields_body(name, fields, &bindings, parent);
+    };
+
+    let placeholder = placeholder(parent);
+    let mut hidden = vec![];
+    for (field, binding) in fields.iter().zip(bindings) {
+        let builder = field_builder(field, parent)?;
+        if !(builder.skip || builder.show_in_debug) {
+            hidden.push(binding);
+        }
+    }
+    let message = error_message(message);
+    Ok(quote! {
+        {
+            #(let #hidden = &#placeholder;)*
+            ::std::write!(f, #message)
         }
     })
 }
+
+fn derive_error_display(
+    name: &Ident,
+    data: &Data,
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    match data {
+        Data::Struct(s) => {
+            let bindings = field_bindings(&s.fields, name.span());
+            let pattern = field_pattern(&s.fields, &bindings);
+            let body = error_display_body(
+                name.to_string(),
+                parent.error_message.as_ref(),
+                &s.fields,
+                &bindings,
+                parent,
+            )?;
+            Ok(quote! {
+                let Self #pattern = self;
+                #body
+            })
+        }
+        Data::Enum(e) => {
+            let arms = e
+                .variants
+                .iter()
+                .map(|variant| {
+                    let span = variant.span();
+                    let message = own_attributes(span, &variant.attrs)?.error_message;
+                    let parent = variant_builder(variant, parent)?;
+                    let ident = &variant.ident;
+                    let bindings = field_bindings(&variant.fields, span);
+                    let pattern = field_pattern(&variant.fields, &bindings);
+                    let body = error_display_body(
+                        ident.to_string(),
+                        message.as_ref(),
+                        &variant.fields,
+                        &bindings,
+                        &parent,
+                    )?;
+                    Ok(quote! { Self::#ident #pattern => #body, })
+                })
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+            Ok(quote! {
+                match self {
+                    #(#arms)*
+                }
+            })
+        }
+        Data::Union(_) => unreachable!("rejected by check_error_attributes"),
+    }
+}
+
+/// The field marked `source`, or named `source`, of a struct or variant
+fn source_field(fields: &Fields) -> Result<Option<(TokenStream, Span)>, syn::Error> {
+    let mut source = None;
+    for (i, field) in fields.iter().enumerate() {
+        let is_source = own_attributes(field.span(), &field.attrs)?.source
+            || field.ident.as_ref().is_some_and(|ident| ident == SOURCE);
+        if !is_source {
+            continue;
+        }
+        if source.is_some() {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("only one field can be the `{SOURCE}`"),
+            ));
+        }
+        let member = match &field.ident {
+            Some(ident) => ident.to_token_stream(),
+            None => Index::from(i).to_token_stream(),
+        };
+        source = Some((member, field.ty.span()));
+    }
+    Ok(source)
+}
+
+/// The body of `std::error::Error::source`, which only accepts sources whose own messages are
+/// redacted
+fn derive_error_source(data: &Data) -> Result<TokenStream, syn::Error> {
+    let some = |source: TokenStream, span: Span| {
+        quote_spanned! { span =>
+            ::std::option::Option::Some(::expunge::checks::source_must_be_redacted(#source))
+        }
+    };
+    match data {
+        Data::Struct(s) => Ok(match source_field(&s.fields)? {
+            Some((field, span)) => some(quote! { &self.#field }, span),
+            None => quote! { ::std::option::Option::None },
+        }),
+        Data::Enum(e) => {
+            let mut arms = vec![];
+            for variant in &e.variants {
+                if let Some((field, span)) = source_field(&variant.fields)? {
+                    let ident = &variant.ident;
+                    let source = Ident::new("source", Span::mixed_site());
+                    let body = some(source.to_token_stream(), span);
+                    arms.push(quote! { Self::#ident { #field: #source, .. } => #body, });
+                }
+            }
+            Ok(quote! {
+                match self {
+                    #(#arms)*
+                    _ => ::std::option::Option::None,
+                }
+            })
+        }
+        Data::Union(_) => unreachable!("rejected by check_error_attributes"),
+    }
+}
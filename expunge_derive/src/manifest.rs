@@ -0,0 +1,129 @@
+use std::fmt::Write;
+
+use proc_macro2::Ident;
+use quote::ToTokens;
+use syn::{Data, Fields};
+
+use crate::{field_builder, union_field, variant_builder, Builder};
+
+/// Describes how each field of the type is expunged, as JSON
+pub fn describe(name: &Ident, data: &Data, parent: &Builder) -> Result<String, syn::Error> {
+    let (kind, fields) = match data {
+        Data::Struct(s) => ("struct", describe_fields(None, &s.fields, parent)?),
+        Data::Enum(e) => {
+            let mut fields = vec![];
+            for variant in &e.variants {
+                let parent = variant_builder(variant, parent)?;
+                fields.extend(describe_fields(
+                    Some(&variant.ident),
+                    &variant.fields,
+                    &parent,
+                )?);
+            }
+            ("enum", fields)
+        }
+        Data::Union(u) => {
+            let (field, _) = union_field(u, parent)?;
+            let fields = Fields::Named(syn::FieldsNamed {
+                brace_token: u.fields.brace_token,
+                named: [field].into_iter().collect(),
+            });
+            ("union", describe_fields(None, &fields, parent)?)
+        }
+    };
+
+    Ok(format!(
+        r#"{{"type":{},"kind":"{kind}","fields":[{}]}}"#,
+        string(&name.to_string()),
+        fields.join(",")
+    ))
+}
+
+fn describe_fields(
+    variant: Option<&Ident>,
+    fields: &Fields,
+    parent: &Builder,
+) -> Result<Vec<String>, syn::Error> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let builder = field_builder(field, parent)?;
+
+            let name = field
+                .ident
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or(i.to_string());
+            let (action, value) = action(&builder);
+
+            let mut json = format!(r#"{{"name":{}"#, string(&name));
+            if let Some(variant) = variant {
+                let _ = write!(json, r#","variant":{}"#, string(&variant.to_string()));
+            }
+            let _ = write!(json, r#","action":"{action}""#);
+            if let Some(value) = value {
+                let _ = write!(json, r#","value":{}"#, string(&value));
+            }
+            if let Some(class) = &builder.class {
+                let _ = write!(json, r#","class":{}"#, string(&class.name));
+            }
+            let _ = write!(
+                json,
+                r#","conditional":{},"zeroize":{}}}"#,
+                builder.condition.is_some(),
+                builder.zeroize
+            );
+            Ok(json)
+        })
+        .collect()
+}
+
+/// The name of the transformation & its argument, if any
+fn action(builder: &Builder) -> (&'static str, Option<String>) {
+    let tokens = |tokens: &dyn ToTokens| Some(tokens.to_token_stream().to_string());
+    if builder.skip {
+        ("skip", None)
+    } else if let Some(with_self) = &builder.expunge_with_self {
+        ("with_self", tokens(with_self))
+    } else if builder.hash {
+        (
+            "hash",
+            builder.hash_salt.as_ref().and_then(|salt| tokens(salt)),
+        )
+    } else if builder.encrypt {
+        ("encrypt", None)
+    } else if builder.none {
+        ("none", None)
+    } else if builder.clear {
+        ("clear", None)
+    } else if builder.keep_first.is_some() || builder.keep_last.is_some() {
+        let keep_first = builder.keep_first.unwrap_or_default();
+        let keep_last = builder.keep_last.unwrap_or_default();
+        ("mask", Some(format!("{keep_first},{keep_last}")))
+    } else if let Some(expunge_as) = &builder.expunge_as {
+        ("as", tokens(expunge_as))
+    } else if let Some(expunge_with) = &builder.expunge_with {
+        ("with", tokens(expunge_with))
+    } else {
+        ("expunge", None)
+    }
+}
+
+/// Encodes a JSON string
+fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str(r#"\""#),
+            '\\' => json.push_str(r"\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
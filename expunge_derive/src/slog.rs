@@ -0,0 +1,531 @@
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Attribute, Data, Expr, ExprLit, Field, Fields,
+    Generics, Lit, LitStr, Meta, Path,
+};
+
+use crate::{
+    derive_fields, field_builder, is_marker, variant_as, variant_builder, Builder, KV, SLOG,
+};
+
+/// Implements `slog::Value` & `expunge::slog::SerializeExpunged`.
+///
+/// The value is serialized through a mirror of the type, with the same `#[serde(...)]` attributes,
+/// whose fields are references. Skipped fields are borrowed, fields that are expunged by their
+/// type are expunged while they're serialized if it implements `SerializeExpunged`, and only the
+/// other fields are cloned & expunged by the usual statements.
+///
+/// The mirror has a type parameter for each field that's expunged by its type, since whether it's
+/// borrowed or cloned is only known once the field's type is resolved.
+pub fn derive(
+    name: &Ident,
+    attrs: &[Attribute],
+    generics: &Generics,
+    data: &Data,
+    parent: &Builder,
+) -> Result<TokenStream, syn::Error> {
+    // `slog::SerdeValue` requires an owned, `'static` copy of the value that can be sent to
+    // another thread, which generic or borrowed types can't provide
+    if !generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            generics,
+            format!("`{SLOG}` is not supported on types with generic or lifetime parameters"),
+        ));
+    }
+
+    let serializer = Ident::new("serializer", Span::mixed_site());
+    let policy = Ident::new("policy", Span::mixed_site());
+    let mirror = format_ident!("__ExpungeSlog");
+
+    // the function needs the whole value, & `into` converts a clone of it
+    let is_owned = parent.container_with.is_some() || has_serde_option(attrs, "into")?;
+    let body = match data {
+        Data::Union(_) => None,
+        _ if is_owned => None,
+        Data::Struct(s) if s.fields.is_empty() => Some(quote! {
+            ::serde::Serialize::serialize(self, #serializer)
+        }),
+        Data::Struct(s) => {
+            let mirrored = mirror_fields(name, None, &s.fields, parent)?;
+            let Mirrored {
+                params,
+                definition,
+                helpers,
+                pattern,
+                statements,
+                construction,
+            } = mirrored;
+            let container_attrs = serde_attributes(attrs, name)?;
+            let semi = matches!(s.fields, Fields::Unnamed(_)).then(|| quote! { ; });
+            Some(quote! {
+                #[derive(::serde::Serialize)]
+                #(#container_attrs)*
+                struct #mirror<'a, #(#params),*> #definition #semi
+
+                #(#helpers)*
+
+                let #name #pattern = self;
+                #statements
+                ::serde::Serialize::serialize(&#mirror #construction, #serializer)
+            })
+        }
+        Data::Enum(e) => {
+            let mut params = vec![];
+            let mut variants = vec![];
+            let mut helpers = vec![];
+            let mut arms = vec![];
+            // the parameters of each variant, which are only inferred for the variant that's built
+            let mut variant_params = vec![];
+            for variant in &e.variants {
+                let ident = &variant.ident;
+                let variant_attrs = variant
+                    .attrs
+                    .iter()
+                    .filter(|attr| attr.path().is_ident("serde"));
+                let parent = variant_builder(variant, parent)?;
+                if let Some(variant_as) = variant_as(e, variant, &parent)? {
+                    // it's never serialized, but it keeps the index of each variant the same
+                    variants.push(quote! { #(#variant_attrs)* #ident });
+                    variant_params.push(vec![]);
+                    arms.push((
+                        quote! { #name::#ident { .. } },
+                        TokenStream::default(),
+                        variant_as.to_token_stream(),
+                        TokenStream::default(),
+                    ));
+                    continue;
+                }
+                let Mirrored {
+                    params: own_params,
+                    definition,
+                    helpers: variant_helpers,
+                    pattern,
+                    statements,
+                    construction,
+                } = mirror_fields(name, Some(ident), &variant.fields, &parent)?;
+                variants.push(quote! { #(#variant_attrs)* #ident #definition });
+                helpers.extend(variant_helpers);
+                params.extend(own_params.iter().cloned());
+                variant_params.push(own_params);
+                arms.push((
+                    quote! { #name::#ident #pattern },
+                    statements,
+                    ident.to_token_stream(),
+                    construction,
+                ));
+            }
+            let arms = arms.into_iter().zip(&variant_params).map(
+                |((pattern, statements, ident, construction), own_params)| {
+                    let arguments = params.iter().map(|param| {
+                        if own_params.contains(param) {
+                            quote! { _ }
+                        } else {
+                            quote! { () }
+                        }
+                    });
+                    // the arguments are given to the variant, since a struct expression can't
+                    // have them on the enum
+                    let arguments = if params.is_empty() {
+                        TokenStream::default()
+                    } else {
+                        quote! { ::<'_, #(#arguments),*> }
+                    };
+                    quote! {
+                        #pattern => {
+                            #statements
+                            ::serde::Serialize::serialize(
+                                &#mirror::#ident #arguments #construction,
+                                #serializer,
+                            )
+                        }
+                    }
+                },
+            );
+            let container_attrs = serde_attributes(attrs, name)?;
+            Some(quote! {
+                #[derive(::serde::Serialize)]
+                #(#container_attrs)*
+                enum #mirror<'a, #(#params),*> {
+                    #(#variants,)*
+                    #[serde(skip)]
+                    __ExpungeLifetime(::std::marker::PhantomData<&'a ()>),
+                }
+
+                #(#helpers)*
+
+                match self {
+                    #(#arms)*
+                }
+            })
+        }
+    };
+    let body = body.unwrap_or_else(|| {
+        quote! {
+            ::serde::Serialize::serialize(
+                &::expunge::Expunge::expunge(::std::clone::Clone::clone(self)),
+                #serializer,
+            )
+        }
+    });
+    let skip_if = parent.skip_if.as_ref().map(|skip_if| {
+        quote! {
+            if #skip_if {
+                return ::serde::Serialize::serialize(self, #serializer);
+            }
+        }
+    });
+    let kv_impl = if parent.slog_kv {
+        derive_kv(name, data, parent)?
+    } else {
+        TokenStream::default()
+    };
+
+    Ok(quote! {
+        impl ::slog::Value for #name {
+            fn serialize(
+                &self,
+                record: &::slog::Record,
+                key: ::slog::Key,
+                serializer: &mut dyn ::slog::Serializer,
+            ) -> ::slog::Result {
+                ::slog::Value::serialize(&::expunge::slog::ExpungedRef(self), record, key, serializer)
+            }
+        }
+
+        impl ::expunge::slog::SerializeExpunged for #name {
+            #[allow(
+                unused_mut,
+                unused_variables,
+                dead_code,
+                clippy::redundant_closure_call,
+                clippy::needless_borrow
+            )]
+            fn serialize_expunged<S>(&self, #serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                let #policy = &::expunge::policy::NoPolicy;
+
+                #skip_if
+
+                #body
+            }
+        }
+
+        #kv_impl
+    })
+}
+
+/// Implements `slog::KV`, which emits each field that's serialized as its own key-value pair,
+/// keyed by the field's name & expunged the same way as by `SerializeExpunged`
+fn derive_kv(name: &Ident, data: &Data, parent: &Builder) -> Result<TokenStream, syn::Error> {
+    let fields = match data {
+        Data::Struct(s) if matches!(s.fields, Fields::Named(_)) => &s.fields,
+        _ => {
+            return Err(syn::Error::new(
+                name.span(),
+                format!("`{SLOG}({KV})` is only supported on structs with named fields"),
+            ))
+        }
+    };
+    let serializer = Ident::new("serializer", Span::mixed_site());
+    let policy = Ident::new("policy", Span::mixed_site());
+
+    let mut emitted = vec![];
+    for field in fields {
+        if has_serde_option(&field.attrs, "skip")?
+            || has_serde_option(&field.attrs, "skip_serializing")?
+        {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let key = LitStr::new(&ident.to_string(), ident.span());
+        emitted.push((ident, key));
+    }
+    let emit = |value: &dyn Fn(&Ident) -> TokenStream| {
+        let emits = emitted.iter().map(|(ident, key)| {
+            let value = value(ident);
+            quote! {
+                #serializer.emit_serde(#key.into(), &::expunge::slog::KvValue(#value))?;
+            }
+        });
+        quote! { #(#emits)* }
+    };
+
+    let body = if parent.container_with.is_some() {
+        // the function needs the whole value
+        let value = Ident::new("value", Span::mixed_site());
+        let emits = emit(&|ident| quote! { &#value.#ident });
+        quote! {
+            let #value = ::expunge::Expunge::expunge(::std::clone::Clone::clone(self));
+            #emits
+        }
+    } else {
+        let Mirrored {
+            pattern,
+            statements,
+            ..
+        } = mirror_fields(name, None, fields, parent)?;
+        let emits = emit(&|ident| quote! { &#ident });
+        quote! {
+            let #name #pattern = self;
+            #statements
+            #emits
+        }
+    };
+    let skip_if = parent.skip_if.as_ref().map(|skip_if| {
+        let emits = emit(&|ident| quote! { &self.#ident });
+        quote! {
+            if #skip_if {
+                #emits
+                return ::std::result::Result::Ok(());
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl ::slog::KV for #name {
+            #[allow(
+                unused_mut,
+                unused_variables,
+                dead_code,
+                clippy::redundant_closure_call,
+                clippy::needless_borrow
+            )]
+            fn serialize(
+                &self,
+                record: &::slog::Record,
+                #serializer: &mut dyn ::slog::Serializer,
+            ) -> ::slog::Result {
+                let #policy = &::expunge::policy::NoPolicy;
+
+                #skip_if
+
+                #body
+
+                ::std::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// The parts of a mirror of a struct or variant
+struct Mirrored {
+    // a type parameter for each field that's expunged by its type
+    params: Vec<Ident>,
+    // the fields of the definition, e.g. `{ email: &'a String }`
+    definition: TokenStream,
+    // functions that adapt the `#[serde(...)]` attributes of the fields to references
+    helpers: Vec<TokenStream>,
+    // binds a reference to each field of the original, e.g. `{ email }`
+    pattern: TokenStream,
+    // binds the expunged value of each field that isn't skipped
+    statements: TokenStream,
+    // the fields of the mirror, e.g. `{ email: &email }`
+    construction: TokenStream,
+}
+
+fn mirror_fields(
+    name: &Ident,
+    variant: Option<&Ident>,
+    fields: &Fields,
+    parent: &Builder,
+) -> Result<Mirrored, syn::Error> {
+    let mut params = vec![];
+    let mut definitions = vec![];
+    let mut helpers = vec![];
+    let mut bindings = vec![];
+    let mut statements = vec![];
+    let mut values = vec![];
+    let mut transformed = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let binding = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => Ident::new(&format!("arg{i}"), field.span()),
+        };
+        let builder = field_builder(field, parent)?;
+        let is_skipped = builder.skip || (is_marker(&field.ty) && !builder.has_transformation());
+        let prefix = match variant {
+            Some(variant) => format!("__expunge_slog_{variant}_{i}"),
+            None => format!("__expunge_slog_{i}"),
+        };
+        let (attrs, field_helpers) = field_serde_attributes(field, &prefix)?;
+        // fields are serialized by their type, unless the attributes need the field's own type
+        let by_type = !is_skipped
+            && field_helpers.is_empty()
+            && !builder.has_transformation()
+            && builder.condition.is_none()
+            && builder.profiles.is_empty()
+            && builder.max_len.is_none()
+            && builder.expunge_with_async.is_none();
+        helpers.extend(field_helpers);
+
+        let ty = &field.ty;
+        let ty = if by_type {
+            let param = match variant {
+                Some(variant) => format_ident!("__ExpungeSlog{variant}Field{i}"),
+                None => format_ident!("__ExpungeSlogField{i}"),
+            };
+            params.push(param.clone());
+            param.to_token_stream()
+        } else {
+            quote! { &'a #ty }
+        };
+        let value = if is_skipped || by_type {
+            binding.to_token_stream()
+        } else {
+            quote! { &#binding }
+        };
+        match &field.ident {
+            Some(ident) => {
+                definitions.push(quote! { #(#attrs)* #ident: #ty });
+                values.push(quote! { #ident: #value });
+            }
+            None => {
+                definitions.push(quote! { #(#attrs)* #ty });
+                values.push(value);
+            }
+        }
+        bindings.push(binding.clone());
+
+        if by_type {
+            statements.push(quote! {
+                let #binding = {
+                    #[allow(unused_imports)]
+                    use ::expunge::slog::{ViaClone, ViaSerializeExpunged};
+
+                    (&&::expunge::slog::SlogField(#binding)).expunged()
+                };
+            });
+        } else if !is_skipped {
+            statements.push(quote! {
+                let mut #binding = ::std::clone::Clone::clone(#binding);
+            });
+            transformed.push((i, field.clone()));
+        }
+    }
+    let (preludes, bodies) = derive_fields(
+        name,
+        variant,
+        quote! { arg },
+        transformed,
+        parent.clone(),
+        true,
+    )?;
+
+    let (definition, pattern, construction) = match fields {
+        Fields::Named(_) => (
+            quote! { { #(#definitions,)* } },
+            quote! { { #(#bindings,)* } },
+            quote! { { #(#values,)* } },
+        ),
+        Fields::Unnamed(_) => (
+            quote! { ( #(#definitions,)* ) },
+            quote! { ( #(#bindings,)* ) },
+            quote! { ( #(#values,)* ) },
+        ),
+        Fields::Unit => Default::default(),
+    };
+    Ok(Mirrored {
+        params,
+        definition,
+        helpers,
+        pattern,
+        statements: quote! {
+            #(#statements)*
+            #preludes
+            #bodies
+        },
+        construction,
+    })
+}
+
+/// The `#[serde(...)]` attributes of the field, where functions that take a reference to the
+/// field are replaced with helpers that take a reference to the mirror's reference
+fn field_serde_attributes(
+    field: &Field,
+    prefix: &str,
+) -> Result<(Vec<TokenStream>, Vec<TokenStream>), syn::Error> {
+    let ty = &field.ty;
+    let mut attrs = vec![];
+    let mut helpers = vec![];
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        let mut options = vec![];
+        for meta in attr.parse_args_with(Punctuated::<Meta, syn::Token![,]>::parse_terminated)? {
+            let function = match &meta {
+                Meta::NameValue(option) => match &option.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(path),
+                        ..
+                    }) => Some((option.path.clone(), path.parse::<Path>()?)),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let Some((option, path)) = function else {
+                options.push(meta.into_token_stream());
+                continue;
+            };
+            if option.is_ident("skip_serializing_if") {
+                let helper = format_ident!("{prefix}_skip_serializing_if");
+                helpers.push(quote! {
+                    fn #helper(value: &&#ty) -> bool {
+                        #path(*value)
+                    }
+                });
+                let helper = LitStr::new(&helper.to_string(), path.span());
+                options.push(quote! { skip_serializing_if = #helper });
+            } else if option.is_ident("serialize_with") || option.is_ident("with") {
+                let serialize = if option.is_ident("with") {
+                    quote! { #path::serialize }
+                } else {
+                    path.to_token_stream()
+                };
+                let helper = format_ident!("{prefix}_serialize_with");
+                helpers.push(quote! {
+                    fn #helper<S>(value: &&#ty, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                    where
+                        S: ::serde::Serializer,
+                    {
+                        #serialize(*value, serializer)
+                    }
+                });
+                let helper = LitStr::new(&helper.to_string(), path.span());
+                options.push(quote! { serialize_with = #helper });
+            } else {
+                options.push(meta.into_token_stream());
+            }
+        }
+        attrs.push(quote! { #[serde(#(#options),*)] });
+    }
+    Ok((attrs, helpers))
+}
+
+/// The `#[serde(...)]` attributes of the container, which also keep its name
+fn serde_attributes(attrs: &[Attribute], name: &Ident) -> Result<Vec<TokenStream>, syn::Error> {
+    let mut serde: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+        .map(ToTokens::to_token_stream)
+        .collect();
+    if !has_serde_option(attrs, "rename")? {
+        let name = LitStr::new(&name.to_string(), name.span());
+        serde.push(quote! { #[serde(rename = #name)] });
+    }
+    Ok(serde)
+}
+
+fn has_serde_option(attrs: &[Attribute], option: &str) -> Result<bool, syn::Error> {
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let metas = attr.parse_args_with(Punctuated::<Meta, syn::Token![,]>::parse_terminated)?;
+        if metas.iter().any(|meta| meta.path().is_ident(option)) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
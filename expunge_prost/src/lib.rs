@@ -0,0 +1,260 @@
+//! Derives `Expunge` for the types generated by [prost_build], using custom options in the
+//! protobuf schema rather than annotating the generated structs by hand.
+//!
+//! Mark sensitive fields with the `(expunge.redact)` option, which is defined in
+//! [OPTIONS_PROTO]:
+//!
+//! ```proto
+//! import "expunge/options.proto";
+//!
+//! message User {
+//!   string id = 1;
+//!   string email = 2 [(expunge.redact) = true];
+//! }
+//! ```
+//!
+//! Then compile the schema in `build.rs` from an encoded `FileDescriptorSet`, e.g. one written by
+//! `protoc --include_imports --descriptor_set_out=...` or encoded by `protox`. Custom options are
+//! only preserved in the encoded form, so the bytes are needed rather than a decoded
+//! [prost_types::FileDescriptorSet].
+//!
+//! ```rust,ignore
+//! let descriptor_set = std::fs::read("descriptors.bin")?;
+//! expunge_prost::compile_fds(&mut prost_build::Config::new(), &descriptor_set)?;
+//! ```
+//!
+//! Every generated message & oneof derives `Expunge`, with fields that aren't marked
+//! `(expunge.redact)` marked `#[expunge(skip)]`. Since `Expunge` implements `Debug` with
+//! placeholders for the redacted fields, prost's own `Debug` implementation is skipped.
+//!
+//! Fields & oneof members whose type is another message aren't skipped, so that the redacted
+//! fields of nested messages are expunged along with their parent. Maps & the well-known types of
+//! `google.protobuf`, which don't implement `Expunge`, are skipped unless they're marked
+//! `(expunge.redact)`. Messages from other crates, e.g. with `extern_path`, must implement
+//! `Expunge` too.
+
+use std::io;
+
+use prost::Message;
+use prost_build::Config;
+
+/// The protobuf definition of the custom options, to be written to an include directory as
+/// `expunge/options.proto`
+pub const OPTIONS_PROTO: &str = include_str!("../proto/expunge/options.proto");
+
+/// The field number of the `(expunge.redact)` extension of `google.protobuf.FieldOptions`
+pub const REDACT: u32 = 51235;
+
+/// Configures prost to derive `Expunge` for each message, according to the options in the encoded
+/// `FileDescriptorSet`
+pub fn configure(config: &mut Config, descriptor_set: &[u8]) -> io::Result<()> {
+    config
+        .message_attribute(".", "#[derive(::expunge::Expunge)]")
+        .skip_debug(["."]);
+
+    for file in fields(descriptor_set, 1) {
+        let file = file?;
+        let package = string(file, 2)?;
+        let prefix = match package {
+            Some(package) => format!(".{package}"),
+            None => String::new(),
+        };
+        for message in fields(file, 4) {
+            configure_message(config, &prefix, message?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Configures prost with [configure], then generates the code for the `FileDescriptorSet`
+pub fn compile_fds(config: &mut Config, descriptor_set: &[u8]) -> io::Result<()> {
+    configure(config, descriptor_set)?;
+    let descriptor_set = prost_types::FileDescriptorSet::decode(descriptor_set)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    config.compile_fds(descriptor_set)
+}
+
+/// The `type` of a `FieldDescriptorProto` for message fields
+const TYPE_MESSAGE: u64 = 11;
+
+/// Marks every field of a `DescriptorProto` that isn't redacted or a message as skipped, recursing
+/// into nested messages
+fn configure_message(config: &mut Config, prefix: &str, message: &[u8]) -> io::Result<()> {
+    let name = string(message, 1)?.unwrap_or_default();
+    let path = format!("{prefix}.{name}");
+
+    // map entries aren't generated as structs
+    if is_map_entry(message)? {
+        return Ok(());
+    }
+
+    // map fields are typed as their entries, which are nested messages
+    let mut map_entries = vec![];
+    for nested in fields(message, 3) {
+        let nested = nested?;
+        if is_map_entry(nested)? {
+            let name = string(nested, 1)?.unwrap_or_default();
+            map_entries.push(format!("{path}.{name}"));
+        }
+    }
+
+    let oneofs = fields(message, 8)
+        .map(|oneof| Ok(string(oneof?, 1)?.unwrap_or_default().to_string()))
+        .collect::<io::Result<Vec<_>>>()?;
+    // the oneofs of proto3 optional fields, which are generated as `Option`s instead
+    let mut synthetic = vec![];
+    for field in fields(message, 2) {
+        let field = field?;
+        let name = string(field, 1)?.unwrap_or_default();
+        let is_redacted = option(field, 8, REDACT)?.is_some_and(|redact| redact != 0);
+        let is_message = varint(field, 5)? == Some(TYPE_MESSAGE)
+            && string(field, 6)?.is_some_and(|type_name| {
+                !type_name.starts_with(".google.protobuf.")
+                    && !map_entries.iter().any(|entry| entry == type_name)
+            });
+        let skip = !is_redacted && !is_message;
+        match (varint(field, 9)?, varint(field, 17)? == Some(1)) {
+            (Some(oneof), true) => synthetic.push(oneof),
+            // a member of a oneof, which is generated as a variant of its enum
+            (Some(oneof), false) => {
+                let oneof = usize::try_from(oneof)
+                    .ok()
+                    .and_then(|oneof| oneofs.get(oneof))
+                    .ok_or_else(invalid)?;
+                if skip {
+                    config.field_attribute(format!("{path}.{oneof}.{name}"), "#[expunge(skip)]");
+                }
+                continue;
+            }
+            (None, _) => {}
+        }
+        if skip {
+            config.field_attribute(format!("{path}.{name}"), "#[expunge(skip)]");
+        }
+    }
+
+    for (i, oneof) in oneofs.iter().enumerate() {
+        if !synthetic.contains(&(i as u64)) {
+            config.enum_attribute(format!("{path}.{oneof}"), "#[derive(::expunge::Expunge)]");
+        }
+    }
+
+    for nested in fields(message, 3) {
+        configure_message(config, &path, nested?)?;
+    }
+    Ok(())
+}
+
+fn is_map_entry(message: &[u8]) -> io::Result<bool> {
+    Ok(option(message, 7, 7)? == Some(1))
+}
+
+fn invalid() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "invalid FileDescriptorSet")
+}
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// Iterates over the fields of an encoded message. This is needed because the options of the
+/// decoded [prost_types] descriptors don't include extensions.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for (i, byte) in self.buf.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.buf = &self.buf[i + 1..];
+                return Ok(value);
+            }
+        }
+        Err(invalid())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(invalid());
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+}
+
+impl<'a> Iterator for Reader<'a> {
+    type Item = io::Result<(u32, Value<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let field = (|| {
+            let key = self.read_varint()?;
+            let number = u32::try_from(key >> 3).map_err(|_| invalid())?;
+            let value = match key & 0x7 {
+                0 => Value::Varint(self.read_varint()?),
+                1 => self.read_bytes(8).map(|_| Value::Fixed)?,
+                2 => {
+                    let len = usize::try_from(self.read_varint()?).map_err(|_| invalid())?;
+                    Value::Bytes(self.read_bytes(len)?)
+                }
+                5 => self.read_bytes(4).map(|_| Value::Fixed)?,
+                _ => return Err(invalid()),
+            };
+            Ok((number, value))
+        })();
+        if field.is_err() {
+            // stop after the first error
+            self.buf = &[];
+        }
+        Some(field)
+    }
+}
+
+/// The length delimited fields with this number, e.g. repeated messages
+fn fields(message: &[u8], number: u32) -> impl Iterator<Item = io::Result<&[u8]>> {
+    Reader { buf: message }.filter_map(move |field| match field {
+        Ok((n, Value::Bytes(bytes))) if n == number => Some(Ok(bytes)),
+        Ok(_) => None,
+        Err(err) => Some(Err(err)),
+    })
+}
+
+/// The last varint field with this number
+fn varint(message: &[u8], number: u32) -> io::Result<Option<u64>> {
+    let mut found = None;
+    for field in (Reader { buf: message }) {
+        if let (n, Value::Varint(value)) = field? {
+            if n == number {
+                found = Some(value);
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// The last varint field with this number in the options message, which may be split across
+/// several fields
+fn option(message: &[u8], options: u32, number: u32) -> io::Result<Option<u64>> {
+    let mut found = None;
+    for options in fields(message, options) {
+        found = varint(options?, number)?.or(found);
+    }
+    Ok(found)
+}
+
+/// The last string field with this number
+fn string(message: &[u8], number: u32) -> io::Result<Option<&str>> {
+    let mut found = None;
+    for bytes in fields(message, number) {
+        found = Some(std::str::from_utf8(bytes?).map_err(|_| invalid())?);
+    }
+    Ok(found)
+}
@@ -0,0 +1,46 @@
+// This file is @generated by prost-build.
+#[derive(::expunge::Expunge)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[prost(skip_debug)]
+pub struct User {
+    #[prost(string, tag = "1")]
+    #[expunge(skip)]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub email: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "6")]
+    pub address: ::core::option::Option<user::Address>,
+    #[prost(map = "string, string", tag = "7")]
+    #[expunge(skip)]
+    pub labels: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(oneof = "user::Contact", tags = "3, 4, 5")]
+    pub contact: ::core::option::Option<user::Contact>,
+}
+/// Nested message and enum types in `User`.
+pub mod user {
+    #[derive(::expunge::Expunge)]
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+    #[prost(skip_debug)]
+    pub struct Address {
+        #[prost(string, tag = "1")]
+        pub street: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        #[expunge(skip)]
+        pub country: ::prost::alloc::string::String,
+    }
+    #[derive(::expunge::Expunge)]
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    #[prost(skip_debug)]
+    pub enum Contact {
+        #[prost(string, tag = "3")]
+        Phone(::prost::alloc::string::String),
+        #[prost(string, tag = "4")]
+        #[expunge(skip)]
+        Handle(::prost::alloc::string::String),
+        #[prost(message, tag = "5")]
+        Office(Address),
+    }
+}
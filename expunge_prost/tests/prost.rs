@@ -0,0 +1,263 @@
+use prost::Message;
+
+// the subset of `descriptor.proto` needed to describe a schema, including the custom option
+#[derive(Message)]
+struct FileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    file: Vec<FileDescriptorProto>,
+}
+
+#[derive(Message)]
+struct FileDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    package: Option<String>,
+    #[prost(message, repeated, tag = "4")]
+    message_type: Vec<DescriptorProto>,
+    #[prost(string, optional, tag = "12")]
+    syntax: Option<String>,
+}
+
+#[derive(Message)]
+struct DescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    field: Vec<FieldDescriptorProto>,
+    #[prost(message, repeated, tag = "3")]
+    nested_type: Vec<DescriptorProto>,
+    #[prost(message, optional, tag = "7")]
+    options: Option<MessageOptions>,
+    #[prost(message, repeated, tag = "8")]
+    oneof_decl: Vec<OneofDescriptorProto>,
+}
+
+#[derive(Message)]
+struct FieldDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+    #[prost(int32, optional, tag = "3")]
+    number: Option<i32>,
+    #[prost(int32, optional, tag = "4")]
+    label: Option<i32>,
+    #[prost(int32, optional, tag = "5")]
+    r#type: Option<i32>,
+    #[prost(string, optional, tag = "6")]
+    type_name: Option<String>,
+    #[prost(message, optional, tag = "8")]
+    options: Option<FieldOptions>,
+    #[prost(int32, optional, tag = "9")]
+    oneof_index: Option<i32>,
+    #[prost(string, optional, tag = "10")]
+    json_name: Option<String>,
+}
+
+#[derive(Message)]
+struct OneofDescriptorProto {
+    #[prost(string, optional, tag = "1")]
+    name: Option<String>,
+}
+
+#[derive(Message)]
+struct MessageOptions {
+    #[prost(bool, optional, tag = "7")]
+    map_entry: Option<bool>,
+}
+
+#[derive(Message)]
+struct FieldOptions {
+    #[prost(bool, optional, tag = "51235")]
+    redact: Option<bool>,
+}
+
+const LABEL_OPTIONAL: i32 = 1;
+const LABEL_REPEATED: i32 = 3;
+const TYPE_STRING: i32 = 9;
+const TYPE_MESSAGE: i32 = 11;
+
+fn field(name: &str, number: i32, redact: bool, oneof_index: Option<i32>) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_string()),
+        number: Some(number),
+        label: Some(LABEL_OPTIONAL),
+        r#type: Some(TYPE_STRING),
+        type_name: None,
+        options: redact.then_some(FieldOptions { redact: Some(true) }),
+        oneof_index,
+        json_name: Some(name.to_string()),
+    }
+}
+
+fn message_field(
+    name: &str,
+    number: i32,
+    type_name: &str,
+    oneof_index: Option<i32>,
+) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        r#type: Some(TYPE_MESSAGE),
+        type_name: Some(type_name.to_string()),
+        ..field(name, number, false, oneof_index)
+    }
+}
+
+fn message(name: &str, field: Vec<FieldDescriptorProto>) -> DescriptorProto {
+    DescriptorProto {
+        name: Some(name.to_string()),
+        field,
+        nested_type: vec![],
+        options: None,
+        oneof_decl: vec![],
+    }
+}
+
+/// `users.proto`, as it would be encoded by `protoc`:
+///
+/// ```proto
+/// message User {
+///   string id = 1;
+///   string email = 2 [(expunge.redact) = true];
+///   oneof contact {
+///     string phone = 3 [(expunge.redact) = true];
+///     string handle = 4;
+///     Address office = 5;
+///   }
+///   Address address = 6;
+///   map<string, string> labels = 7;
+///
+///   message Address {
+///     string street = 1 [(expunge.redact) = true];
+///     string country = 2;
+///   }
+/// }
+/// ```
+fn descriptor_set() -> Vec<u8> {
+    let labels_entry = DescriptorProto {
+        options: Some(MessageOptions {
+            map_entry: Some(true),
+        }),
+        ..message(
+            "LabelsEntry",
+            vec![field("key", 1, false, None), field("value", 2, false, None)],
+        )
+    };
+    let user = DescriptorProto {
+        nested_type: vec![
+            message(
+                "Address",
+                vec![
+                    field("street", 1, true, None),
+                    field("country", 2, false, None),
+                ],
+            ),
+            labels_entry,
+        ],
+        oneof_decl: vec![OneofDescriptorProto {
+            name: Some("contact".to_string()),
+        }],
+        ..message(
+            "User",
+            vec![
+                field("id", 1, false, None),
+                field("email", 2, true, None),
+                field("phone", 3, true, Some(0)),
+                field("handle", 4, false, Some(0)),
+                message_field("office", 5, ".users.User.Address", Some(0)),
+                message_field("address", 6, ".users.User.Address", None),
+                FieldDescriptorProto {
+                    label: Some(LABEL_REPEATED),
+                    ..message_field("labels", 7, ".users.User.LabelsEntry", None)
+                },
+            ],
+        )
+    };
+    FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("users.proto".to_string()),
+            package: Some("users".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![user],
+        }],
+    }
+    .encode_to_vec()
+}
+
+/// The code generated from [descriptor_set], which is checked by
+/// `it_generates_the_checked_in_code`
+mod users {
+    include!("generated/users.rs");
+}
+
+/// Regenerate with `EXPUNGE_PROST_OVERWRITE=1 cargo test -p expunge_prost`
+#[test]
+fn it_generates_the_checked_in_code() {
+    let out_dir = tempfile::tempdir().unwrap();
+    let mut config = prost_build::Config::new();
+    config.out_dir(out_dir.path());
+    expunge_prost::compile_fds(&mut config, &descriptor_set()).unwrap();
+
+    let generated = std::fs::read_to_string(out_dir.path().join("users.rs")).unwrap();
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/generated/users.rs");
+    if std::env::var_os("EXPUNGE_PROST_OVERWRITE").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        generated,
+        "the generated code has changed, regenerate it with EXPUNGE_PROST_OVERWRITE=1"
+    );
+}
+
+#[test]
+fn it_expunges_generated_types() {
+    use expunge::Expunge;
+    use users::{user::Address, user::Contact, User};
+
+    let address = Address {
+        street: "1 Main St".to_string(),
+        country: "GB".to_string(),
+    };
+    let user = User {
+        id: "101".to_string(),
+        email: "jane@example.com".to_string(),
+        contact: Some(Contact::Phone("07700 900123".to_string())),
+        address: Some(address.clone()),
+        labels: [("tier".to_string(), "gold".to_string())].into(),
+    }
+    .expunge();
+
+    assert_eq!("101", user.id);
+    assert_eq!("", user.email);
+    assert_eq!(Some(Contact::Phone(String::new())), user.contact);
+    assert_eq!(
+        Some(Address {
+            street: String::new(),
+            country: "GB".to_string(),
+        }),
+        user.address,
+        "nested messages should be expunged"
+    );
+    assert_eq!("gold", user.labels["tier"]);
+
+    let handle = Some(Contact::Handle("@jane".to_string()));
+    assert_eq!(handle.clone(), handle.expunge());
+    assert_eq!(
+        Some(Contact::Office(Address {
+            street: String::new(),
+            country: "GB".to_string(),
+        })),
+        Some(Contact::Office(address)).expunge()
+    );
+    assert_eq!(
+        r#"User { id: "101", email: <expunged>, address: <expunged>, labels: {"tier": "gold"}, contact: <expunged> }"#,
+        format!("{user:?}")
+    );
+}
+
+#[test]
+fn it_rejects_invalid_descriptor_sets() {
+    let mut config = prost_build::Config::new();
+    let err = expunge_prost::configure(&mut config, &[0x0a, 0x05, 0x01]).unwrap_err();
+    assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+}
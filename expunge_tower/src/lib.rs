@@ -0,0 +1,178 @@
+//! A [tower] layer that redacts JSON request & response bodies for access logs, at the edge of a
+//! service where typed structs aren't available.
+//!
+//! [ExpungeBodyLayer] buffers JSON bodies, applies a [dynamic redaction engine](Engine) to them
+//! and adds the result to the extensions of the request or response as a [SanitizedBody]. The
+//! bodies themselves are passed on unchanged, so logging layers can record the [SanitizedBody]
+//! instead:
+//!
+//! - Layers wrapped by [ExpungeBodyLayer] see it in the request extensions
+//! - Layers wrapping [ExpungeBodyLayer] see it in the response extensions
+//!
+//! ```rust
+//! use expunge::dynamic::{Action, Engine};
+//! use expunge_tower::ExpungeBodyLayer;
+//!
+//! let engine = Engine::new().rule("$..password", Action::Expunge)?;
+//!
+//! let layer = ExpungeBodyLayer::new(engine);
+//! # Ok::<_, expunge::dynamic::SelectorError>(())
+//! ```
+//!
+//! Only bodies with a JSON content type and a known length within [ExpungeBodyLayer::limit] are
+//! buffered. Other bodies, such as streams, are passed through without being read.
+//!
+//! [tower]: https://docs.rs/tower
+
+use std::{future::Future, pin::Pin, sync::Arc, task::Context, task::Poll};
+
+use bytes::Bytes;
+use expunge::dynamic::Engine;
+use http::{header::CONTENT_TYPE, Extensions, HeaderMap, Request, Response};
+use http_body::Body;
+use http_body_util::{BodyExt, Either, Full};
+use serde_json::Value;
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// The error type of [ExpungeBodyService], since reading a body can fail as well as the service
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A body that was buffered, or passed through because it isn't JSON
+pub type MaybeBuffered<B> = Either<Full<Bytes>, B>;
+
+/// The redacted JSON body of a request or response, added to its extensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct SanitizedBody(pub Value);
+
+/// Bodies larger than this aren't buffered by default
+pub const DEFAULT_LIMIT: usize = 1024 * 1024;
+
+/// A [Layer] that adds a [SanitizedBody] to JSON requests & responses. See the
+/// [crate docs](crate).
+#[derive(Debug, Clone)]
+pub struct ExpungeBodyLayer {
+    engine: Arc<Engine>,
+    limit: usize,
+}
+
+impl ExpungeBodyLayer {
+    /// Redact bodies with the rules of the engine
+    pub fn new(engine: Engine) -> Self {
+        Self {
+            engine: Arc::new(engine),
+            limit: DEFAULT_LIMIT,
+        }
+    }
+
+    /// The size in bytes of the largest body that will be buffered. Defaults to [DEFAULT_LIMIT].
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+impl<S> Layer<S> for ExpungeBodyLayer {
+    type Service = ExpungeBodyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExpungeBodyService {
+            inner,
+            engine: self.engine.clone(),
+            limit: self.limit,
+        }
+    }
+}
+
+/// The service created by [ExpungeBodyLayer]
+#[derive(Debug, Clone)]
+pub struct ExpungeBodyService<S> {
+    inner: S,
+    engine: Arc<Engine>,
+    limit: usize,
+}
+
+impl<S, B, RB> Service<Request<B>> for ExpungeBodyService<S>
+where
+    S: Service<Request<MaybeBuffered<B>>, Response = Response<RB>> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    RB: Body<Data = Bytes> + Send + 'static,
+    RB::Error: Into<BoxError>,
+{
+    type Response = Response<MaybeBuffered<RB>>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        // the clone might not be ready, so use the service that was polled
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let engine = self.engine.clone();
+        let limit = self.limit;
+
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let body =
+                sanitize(&parts.headers, &mut parts.extensions, body, &engine, limit).await?;
+            let response = inner
+                .call(Request::from_parts(parts, body))
+                .await
+                .map_err(Into::into)?;
+
+            let (mut parts, body) = response.into_parts();
+            let body =
+                sanitize(&parts.headers, &mut parts.extensions, body, &engine, limit).await?;
+            Ok(Response::from_parts(parts, body))
+        })
+    }
+}
+
+/// Buffers a JSON body to add a [SanitizedBody] to the extensions. Bodies that can't be parsed are
+/// still buffered, but don't get a [SanitizedBody].
+async fn sanitize<B>(
+    headers: &HeaderMap,
+    extensions: &mut Extensions,
+    body: B,
+    engine: &Engine,
+    limit: usize,
+) -> Result<MaybeBuffered<B>, BoxError>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    let fits = body
+        .size_hint()
+        .upper()
+        .is_some_and(|len| len <= limit as u64);
+    if !is_json(headers) || !fits {
+        return Ok(Either::Right(body));
+    }
+
+    let bytes = body.collect().await.map_err(Into::into)?.to_bytes();
+    if let Ok(value) = serde_json::from_slice::<Value>(&bytes) {
+        extensions.insert(SanitizedBody(engine.apply(value)));
+    }
+    Ok(Either::Left(Full::new(bytes)))
+}
+
+/// Whether the content type is `application/json` or a `+json` suffix, e.g.
+/// `application/problem+json`
+fn is_json(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence == "application/json" || essence.ends_with("+json")
+}
@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use expunge::dynamic::{Action, Engine};
+use expunge_tower::{ExpungeBodyLayer, MaybeBuffered, SanitizedBody};
+use http::{header::CONTENT_TYPE, Request, Response};
+use http_body_util::{BodyExt, Full};
+use serde_json::json;
+use tower::{service_fn, BoxError, Layer, ServiceExt};
+
+type Seen = Arc<Mutex<Vec<Option<SanitizedBody>>>>;
+
+/// Echoes the request body, recording the sanitized request body it was given
+fn echo(
+    layer: ExpungeBodyLayer,
+    seen: Seen,
+) -> impl tower::Service<
+    Request<Full<Bytes>>,
+    Response = Response<MaybeBuffered<MaybeBuffered<Full<Bytes>>>>,
+    Error = BoxError,
+> {
+    layer.layer(service_fn(
+        move |request: Request<MaybeBuffered<Full<Bytes>>>| {
+            let seen = seen.clone();
+            async move {
+                seen.lock()
+                    .unwrap()
+                    .push(request.extensions().get::<SanitizedBody>().cloned());
+                let content_type = request.headers().get(CONTENT_TYPE).cloned();
+                let mut response = Response::new(request.into_body());
+                if let Some(content_type) = content_type {
+                    response.headers_mut().insert(CONTENT_TYPE, content_type);
+                }
+                Ok::<_, BoxError>(response)
+            }
+        },
+    ))
+}
+
+fn layer() -> ExpungeBodyLayer {
+    let engine = Engine::new().rule("$..password", Action::Expunge).unwrap();
+    ExpungeBodyLayer::new(engine)
+}
+
+fn request(content_type: &str, body: &str) -> Request<Full<Bytes>> {
+    Request::builder()
+        .header(CONTENT_TYPE, content_type)
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap()
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn it_sanitizes_json_bodies() {
+    let seen = Seen::default();
+    let body = r#"{"user":"jane","password":"hunter2"}"#;
+    let response = echo(layer(), seen.clone())
+        .oneshot(request("application/json; charset=utf-8", body))
+        .await
+        .unwrap();
+
+    let sanitized = SanitizedBody(json!({ "user": "jane", "password": "" }));
+    assert_eq!(vec![Some(sanitized.clone())], *seen.lock().unwrap());
+    assert_eq!(
+        Some(&sanitized),
+        response.extensions().get::<SanitizedBody>()
+    );
+
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    assert_eq!(body, bytes, "the body itself should be left as-is");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn it_passes_other_bodies_through() {
+    let seen = Seen::default();
+    let body = r#"{"password":"hunter2"}"#;
+
+    for request in [
+        request("text/plain", body),
+        request("application/problem+json", "not json"),
+    ] {
+        let response = echo(layer(), seen.clone()).oneshot(request).await.unwrap();
+        assert_eq!(None, response.extensions().get::<SanitizedBody>());
+    }
+
+    let response = echo(layer().limit(4), seen.clone())
+        .oneshot(request("application/json", body))
+        .await
+        .unwrap();
+    assert!(
+        matches!(response.body(), MaybeBuffered::Right(_)),
+        "bodies over the limit shouldn't be buffered"
+    );
+
+    assert_eq!(vec![None, None, None], *seen.lock().unwrap());
+}